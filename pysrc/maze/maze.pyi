@@ -3,44 +3,495 @@ Maze and maze image generation
 """
 
 from io import BytesIO
-from typing import List, NamedTuple, Sequence, Tuple
+from os import PathLike
+from typing import Any, Dict, FrozenSet, Iterator, List, Literal, Protocol, Sequence, Tuple, overload
 from typing_extensions import Never
 
 __version__: str
 __all__: Tuple[str, ...]
 
-_XY = _Direction = Tuple[int, int]
+_XY = Tuple[int, int]
+_PointLike = Point | Sequence[int]
+_Direction = Direction | Tuple[int, int]
 _Rgb = _Rgba = Sequence[int]
+_Colour = _Rgb | _Rgba | str | int
+"""An RGB(A) sequence, a `"#RRGGBB"`/`"#RRGGBBAA"` hex code, a `0xRRGGBB` int, or a CSS colour name"""
+_Move = Tuple[_Direction, int, bool]
+_DirectionLabels = Dict[str, str]
+_ImageFormat = Literal["png", "jpeg", "jpg", "webp", "bmp"]
+_PngCompression = Literal["fast", "default", "best"]
+_PngFilter = Literal["none", "sub", "up", "avg", "paeth", "adaptive"]
+_ResizeFilter = Literal["nearest", "triangle", "bilinear", "catmullrom", "bicubic", "gaussian", "lanczos3"]
+_Projection = Literal["top-down", "isometric"]
+_Terrain = Literal["normal", "ice", "mud"]
+_Crop = Tuple[int, int, int, int] | Sequence[_PointLike]
+_PilImage = Any  # `PIL.Image.Image`, Pillow is an optional dependency
 
-UP: _Direction
-DOWN: _Direction
-LEFT: _Direction
-RIGHT: _Direction
+UP: Direction
+DOWN: Direction
+LEFT: Direction
+RIGHT: Direction
 
-class _Solution(NamedTuple):
-    move_count: int
-    directions: List[str]
+class Direction:
+    UP: Direction
+    DOWN: Direction
+    LEFT: Direction
+    RIGHT: Direction
+    def __init__(self) -> Never:
+        """This class is not to be instantiated directly, use `UP`, `DOWN`, `LEFT`, or `RIGHT` instead"""
+    @property
+    def delta(self) -> Tuple[int, int]: ...
+    @property
+    def name(self) -> Literal["UP", "DOWN", "LEFT", "RIGHT"]: ...
+    def opposite(self) -> Direction: ...
+    def turn_left(self) -> Direction: ...
+    def turn_right(self) -> Direction: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, idx: int, /) -> int: ...
+    def __iter__(self) -> Iterator[int]: ...
+
+class Point:
+    def __init__(self, x: int, y: int) -> None: ...
+    @property
+    def x(self) -> int: ...
+    @property
+    def y(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, idx: int, /) -> int: ...
+    def __iter__(self) -> Iterator[int]: ...
+
+class Solution:
+    def __init__(self) -> Never:
+        """This class is not to be instantiated directly, use `Maze.get_solution_expensively` instead"""
+    @property
+    def move_count(self) -> int: ...
+    @property
+    def directions(self) -> List[str]: ...
+    @property
+    def path(self) -> List[_XY]: ...
+
+class Cell:
+    def __init__(self) -> Never:
+        """This class is not to be instantiated directly, index a maze with `maze[x, y]` instead"""
+    @property
+    def xy(self) -> Point: ...
+    @property
+    def maze(self) -> Maze: ...
+    @property
+    def is_dead_end(self) -> bool: ...
+    @property
+    def is_junction(self) -> bool: ...
+    def open_directions(self) -> List[Direction]: ...
+    def neighbours(self) -> List[Cell]: ...
+
+class Validation:
+    def __init__(self) -> Never:
+        """This class is not to be instantiated directly, use `Maze.validate` instead"""
+    @property
+    def is_perfect(self) -> bool: ...
+    @property
+    def unreachable(self) -> List[_XY]: ...
+    @property
+    def loops(self) -> List[Tuple[_XY, _XY]]: ...
+    def __bool__(self) -> bool: ...
+
+_ThemeName = Literal["dark", "light", "neon", "paper"]
+_PaletteName = Literal["okabe-ito", "okabe-ito-dark", "tol-bright", "tol-high-contrast", "ibm"]
+
+class Theme:
+    def __init__(
+        self,
+        *,
+        bg_colour: _Colour,
+        wall_colour: _Colour,
+        solution_colour: _Colour,
+        trail_colour: _Colour | None = ...,
+        fog_colour: _Colour | None = ...,
+        wall_cap: Literal["square", "butt", "round"] = ...,
+        wall_dash: Tuple[int, int] | None = ...,
+        supersample: Literal[1, 2, 3, 4] = ...,
+    ) -> None: ...
+    @staticmethod
+    def preset(name: _ThemeName, /) -> Theme: ...
+    @staticmethod
+    def presets() -> List[_ThemeName]: ...
+    @staticmethod
+    def palette(name: _PaletteName, /) -> Theme:
+        """A colourblind-safe set of colours with the default style"""
+    @staticmethod
+    def palettes() -> List[_PaletteName]: ...
+    def with_palette(self, name: _PaletteName, /) -> Theme: ...
+    @property
+    def bg_colour(self) -> _Rgba: ...
+    @property
+    def wall_colour(self) -> _Rgba: ...
+    @property
+    def solution_colour(self) -> _Rgba: ...
+    @property
+    def trail_colour(self) -> _Rgba | None: ...
+    @property
+    def fog_colour(self) -> _Rgba | None: ...
+    @property
+    def wall_cap(self) -> Literal["square", "butt", "round"]: ...
+    @property
+    def wall_dash(self) -> Tuple[int, int] | None: ...
+    @property
+    def supersample(self) -> int: ...
+
+class _Writable(Protocol):
+    def write(self, data: bytes, /) -> object: ...
 
 class SolutionNotFound(Exception): ...
 
 class Maze:
     def __init__(self) -> Never:
         """This class is not to be instantiated directly, use the `generate_maze` function instead"""
-    def has_wall_between(self, a: _XY, b: _XY, /) -> bool: ...
-    def undraw_at(self, xy: _XY, /) -> None: ...
-    def draw_player_at(self, xy: _XY, /) -> None: ...
-    def compute_solution(self, *, draw_path: bool) -> None: ...
-    def get_solution_expensively(self) -> _Solution: ...
-    def get_image_expensively(self) -> BytesIO: ...
-    def move_max(self, current: _XY, direction: _Direction, /) -> _XY: ...
+    def __buffer__(self, flags: int, /) -> memoryview: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, xy: _PointLike, /) -> Cell: ...
+    def __iter__(self) -> Iterator[_XY]: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def clone(self) -> Maze: ...
+    def __copy__(self) -> Maze: ...
+    def __deepcopy__(self, memo: Any, /) -> Maze: ...
+    def __getstate__(self) -> Dict[str, Any]: ...
+    def __setstate__(self, state: Dict[str, Any], /) -> None: ...
+    def has_wall_between(self, a: _PointLike, b: _PointLike, /) -> bool: ...
+    def can_move(self, xy: _PointLike, direction: _Direction, /) -> bool: ...
+    def visible_cells(self, xy: _PointLike, /) -> List[_XY]: ...
+    def legal_moves(self, xy: _PointLike, /) -> List[Direction]: ...
+    def describe(self, xy: _PointLike, /) -> str: ...
+    def undraw_at(self, xy: _PointLike, /, *, player: str | None = ...) -> None: ...
+    def draw_marker_at(
+        self,
+        xy: _PointLike,
+        image_or_colour: _Colour | bytes | str | PathLike[str] | _PilImage,
+        /,
+        tag: str | None = ...,
+    ) -> None: ...
+    def remove_marker(self, tag: str, /) -> int: ...
+    def add_player(
+        self,
+        id: str,
+        icon: _Colour | bytes | str | PathLike[str] | _PilImage,
+        /,
+        xy: _PointLike | None = ...,
+    ) -> None: ...
+    def remove_player(self, id: str, /) -> bool: ...
+    @property
+    def players(self) -> Dict[str, Point | None]: ...
+    def add_chaser(
+        self,
+        id: str,
+        icon: _Colour | bytes | str | PathLike[str] | _PilImage,
+        xy: _PointLike,
+        /,
+        *,
+        target: str | None = ...,
+        speed: int = ...,
+    ) -> None: ...
+    def remove_chaser(self, id: str, /) -> bool: ...
+    @property
+    def chasers(self) -> Dict[str, Point]: ...
+    def move_chasers(self) -> List[str]: ...
+    def caught(self, *, player: str | None = ...) -> bool: ...
+    def place_items(
+        self, n: int, icon: _Colour | bytes | str | PathLike[str] | _PilImage, /
+    ) -> List[Point]: ...
+    def random_cell(
+        self,
+        *,
+        min_distance_from: Tuple[_PointLike, int] | None = ...,
+        rng_seed: int | None = ...,
+    ) -> Point: ...
+    @property
+    def items(self) -> List[Point]: ...
+    @property
+    def collected_items(self) -> List[Point]: ...
+    def add_portal(self, a: _PointLike, b: _PointLike, /, colour: _Colour | None = ...) -> None: ...
+    def remove_portal(self, xy: _PointLike, /) -> bool: ...
+    @property
+    def portals(self) -> List[Tuple[_XY, _XY]]: ...
+    def add_switch(
+        self,
+        xy: _PointLike,
+        walls: Sequence[Tuple[_PointLike, _PointLike]],
+        /,
+        colour: _Colour | None = ...,
+    ) -> None: ...
+    def remove_switch(self, xy: _PointLike, /) -> bool: ...
+    @property
+    def switches(self) -> Dict[_XY, List[Tuple[_XY, _XY]]]: ...
+    def add_checkpoint(self, xy: _PointLike, /) -> None: ...
+    def remove_checkpoint(self, xy: _PointLike, /) -> bool: ...
+    @property
+    def checkpoints(self) -> List[Point]: ...
+    @property
+    def reached_checkpoints(self) -> List[Point]: ...
+    @property
+    def checkpoint_splits(self) -> List[int]: ...
+    def set_terrain(self, xy: _PointLike, terrain: _Terrain, /) -> None: ...
+    def terrain_at(self, xy: _PointLike, /) -> _Terrain: ...
+    @property
+    def terrain(self) -> Dict[_XY, _Terrain]: ...
+    def draw_text_at(self, xy: _PointLike, text: str, colour: _Colour, size: float = ...) -> None: ...
+    def draw_coordinate_labels(
+        self, colour: _Colour, *, size: float = ..., cell_indices: bool = ...
+    ) -> None: ...
+    def draw_player_at(
+        self, xy: _PointLike, /, direction: _Direction | None = ..., *, player: str | None = ...
+    ) -> None: ...
+    def teleport_player(self, player: str | None, xy: _PointLike, /) -> None: ...
+    def compute_solution(self, *, draw_path: bool) -> Solution: ...
+    def get_solution_expensively(self) -> Solution: ...
+    def get_moves_expensively(self) -> List[_Move]: ...
+    def solve_collect_all(self) -> Tuple[List[_Move], int]: ...
+    def get_directions_chunked(self, *, max_len: int = ...) -> List[str]: ...
+    def set_direction_labels(
+        self, labels: _DirectionLabels | None, /, *, ascii: bool = ...
+    ) -> None: ...
+    def validate_moves(self, start: _PointLike, moves: Sequence[_Move], /) -> Tuple[bool, bool]: ...
+    def simulate(
+        self, start: _PointLike, moves: Sequence[_Move], /
+    ) -> Tuple[_XY, List[_XY], int | None]: ...
+    def grade_run(self, moves: Sequence[_Move], /) -> Tuple[float, int]: ...
+    def render(self) -> None: ...
+    def get_image_expensively(
+        self,
+        *,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+        scale: float = ...,
+        scale_filter: _ResizeFilter = ...,
+        projection: _Projection = ...,
+        crop: _Crop | None = ...,
+    ) -> BytesIO: ...
+    def image_array(self) -> Any:
+        """Returns a `numpy.ndarray` of shape `(height, width, 4)` and dtype `uint8`"""
+    def to_pil(self) -> _PilImage:
+        """Requires Pillow to be installed"""
+    def wall_arrays(self) -> Tuple[Any, Any]:
+        """Returns `(horizontal, vertical)` boolean `numpy.ndarray`s of shapes
+        `(height - 1, width)` and `(height, width - 1)`"""
+    def to_csr(self) -> Tuple[Any, Any]:
+        """Returns `(indptr, indices)` as `int32` `numpy.ndarray`s, cell `(x, y)` being node `y * width + x`"""
+    def get_image_bytes(
+        self,
+        *,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+        scale: float = ...,
+        scale_filter: _ResizeFilter = ...,
+        projection: _Projection = ...,
+        crop: _Crop | None = ...,
+    ) -> bytes: ...
+    def tile_grid(self, tile_size: int = ...) -> Tuple[int, int]: ...
+    def render_first_person(
+        self,
+        xy: _PointLike,
+        facing: _Direction,
+        /,
+        *,
+        width: int = ...,
+        height: int = ...,
+        fov: float = ...,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+    ) -> bytes: ...
+    def render_tile(
+        self,
+        col: int,
+        row: int,
+        /,
+        *,
+        tile_size: int = ...,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+    ) -> bytes: ...
+    def save(
+        self,
+        path: str | PathLike[str],
+        format: _ImageFormat | None = ...,
+        *,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+        scale: float = ...,
+        scale_filter: _ResizeFilter = ...,
+        projection: _Projection = ...,
+        crop: _Crop | None = ...,
+    ) -> None: ...
+    def write_image(
+        self,
+        file: _Writable,
+        /,
+        *,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+        scale: float = ...,
+        scale_filter: _ResizeFilter = ...,
+        projection: _Projection = ...,
+        crop: _Crop | None = ...,
+    ) -> None: ...
+    def to_text(self, style: Literal["unicode", "ascii"] = ..., *, markers: bool = ...) -> str: ...
+    def to_ansi(self, *, draw_path: bool = ..., markers: bool = ..., truecolor: bool = ...) -> str: ...
+    def to_emoji(
+        self,
+        *,
+        wall: str = ...,
+        open: str = ...,
+        path: str = ...,
+        player: str = ...,
+        goal: str = ...,
+        draw_path: bool = ...,
+        markers: bool = ...,
+    ) -> str: ...
+    def render_print(
+        self,
+        *,
+        draw_path: bool = ...,
+        dpi: int = ...,
+        cell_mm: float = ...,
+        format: _ImageFormat = ...,
+        quality: int = ...,
+        png_compression: _PngCompression = ...,
+        png_filter: _PngFilter = ...,
+    ) -> bytes: ...
+    def to_svg(self, *, draw_path: bool = ...) -> str: ...
+    def render_replay_gif(
+        self, moves: Sequence[_Move], frame_delay: int = ..., *, start: _PointLike = ...
+    ) -> BytesIO: ...
+    def render_generation_gif(self, frame_delay: int = ..., *, frames: int = ...) -> BytesIO: ...
+    def render_generation_frames(self, *, frames: int = ...) -> List[bytes]: ...
+    def render_solution_animation(
+        self, frame_delay: int = ..., *, segments_per_frame: int = ...
+    ) -> BytesIO: ...
+    @overload
+    def move_max(
+        self,
+        current: _PointLike,
+        direction: _Direction,
+        /,
+        *,
+        stop_at_goal: Literal[False] = ...,
+        player: str | None = ...,
+    ) -> Point: ...
+    @overload
+    def move_max(
+        self,
+        current: _PointLike,
+        direction: _Direction,
+        /,
+        *,
+        stop_at_goal: Literal[True],
+        player: str | None = ...,
+    ) -> Tuple[Point, bool]: ...
+    def move_step(
+        self, current: _PointLike, direction: _Direction, /, *, player: str | None = ...
+    ) -> Tuple[Point, bool]: ...
+    def get_history(self, *, player: str | None = ...) -> List[Tuple[_XY, _XY]]: ...
+    def clear_history(self) -> None: ...
+    def redraw(self) -> None: ...
+    def validate(self) -> Validation: ...
+    def to_adjacency(self) -> Dict[_XY, List[_XY]]: ...
+    def edges(self) -> List[Tuple[_XY, _XY]]: ...
+    @property
+    def width(self) -> int: ...
+    @property
+    def height(self) -> int: ...
+    @property
+    def start(self) -> Point: ...
+    @property
+    def end(self) -> Point: ...
+    @property
+    def bg_colour(self) -> _Rgba: ...
+    @property
+    def solution_colour(self) -> _Rgba: ...
+    @property
+    def is_solved_computed(self) -> bool: ...
+    @property
+    def walls(self) -> FrozenSet[Tuple[_XY, _XY]]: ...
+    @property
+    def passages(self) -> FrozenSet[Tuple[_XY, _XY]]: ...
+    def to_json(self) -> str: ...
+    def to_bytes(self) -> bytes: ...
+    def canonical_hash(self, *, symmetric: bool = ...) -> str: ...
+    def components(self, *, repair: bool = ...) -> Tuple[int, Dict[_XY, int]]: ...
+    def remove_wall(self, a: _PointLike, b: _PointLike, /) -> None: ...
+    def add_wall(self, a: _PointLike, b: _PointLike, /, *, allow_disconnect: bool = ...) -> List[_XY]: ...
+    def shift_walls(self, n: int, /) -> List[Tuple[Tuple[_XY, _XY], Tuple[_XY, _XY]]]: ...
+    def submaze(self, x0: int, y0: int, w: int, h: int, /) -> Maze: ...
+    def rotate(self, n_quarter_turns: int = ..., /) -> None: ...
+    def mirror(self, axis: Literal["horizontal", "vertical"], /) -> None: ...
+    def transpose(self) -> None: ...
+    def recolour(self, *, bg: _Colour | None = ..., wall: _Colour | None = ..., solution: _Colour | None = ...) -> None: ...
+    def set_trail_colour(self, colour: _Colour | None, /, *, fade: int = ...) -> None: ...
+    def clear_trail(self) -> None: ...
+    def set_watermark(
+        self,
+        text_or_image: str | bytes | PathLike[str] | _PilImage,
+        /,
+        *,
+        corner: Literal["top-left", "top-right", "bottom-left", "bottom-right"] = ...,
+        inset: int = ...,
+        colour: _Colour | None = ...,
+        size: float = ...,
+    ) -> None: ...
+    def clear_watermark(self) -> None: ...
+    def enable_fog(self, colour: _Colour | None = ..., /, *, radius: int = ...) -> None: ...
+    def disable_fog(self) -> None: ...
 
 def generate_maze(
     *,
     width: int,
     height: int,
-    bg_colour: _Rgb | _Rgba,
-    wall_colour: _Rgb | _Rgba,
-    solution_colour: _Rgb | _Rgba,
-    player: bytes | None = ...,
-    endzone: bytes | None = ...,
+    bg_colour: _Colour | None = ...,
+    bg_image: bytes | str | PathLike[str] | _PilImage | None = ...,
+    bg_image_fit: Literal["stretch", "tile"] = ...,
+    wall_colour: _Colour | None = ...,
+    solution_colour: _Colour | None = ...,
+    solution_gradient: _Colour | None = ...,
+    theme: Theme | _ThemeName | None = ...,
+    palette: _PaletteName | None = ...,
+    player: bytes | str | PathLike[str] | _PilImage | None = ...,
+    endzone: bytes | str | PathLike[str] | _PilImage | None = ...,
+    direction_labels: _DirectionLabels | None = ...,
+    ascii_directions: bool = ...,
+    record_generation: bool = ...,
+    icon_filter: _ResizeFilter = ...,
+    player_facing: Literal["fixed", "rotate", "spritesheet"] = ...,
+    trail_colour: _Colour | None = ...,
+    trail_fade: int = ...,
+    supersample: Literal[1, 2, 3, 4] | None = ...,
+    wall_cap: Literal["square", "butt", "round"] | None = ...,
+    wall_dash: Tuple[int, int] | None = ...,
+    wall_jitter: int = ...,
+    wall_texture: bytes | str | PathLike[str] | _PilImage | None = ...,
+    border: bool = ...,
+    border_openings: bool = ...,
+    margin: int = ...,
+    render: bool = ...,
+) -> Maze: ...
+
+def set_asset_dir(path: str | PathLike[str] | None, /, *, strict: bool = ...) -> None: ...
+def from_json(s: str, /, **style: Any) -> Maze: ...
+def from_bytes(data: bytes, /, **style: Any) -> Maze: ...
+def stitch_mazes(
+    grid: Sequence[Sequence[Maze]], /, *, doorways: Sequence[Tuple[_PointLike, _PointLike]] | None = ...
 ) -> Maze: ...
@@ -1,8 +1,10 @@
-use crate::types::{EdgeSet, EdgeVec, Point};
+use super::mechanics::{Mechanics, SwitchMask, Travel};
+use crate::types::{DirectionLabels, EdgeSet, EdgeVec, Move, Point};
 use crate::util::{all_neighbours, out_of_bounds, wall_between};
 
 use std::{
-    collections::{HashMap, HashSet},
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
     hash::{Hash, Hasher},
 };
 
@@ -29,16 +31,24 @@ impl Hash for AStarNode {
     }
 }
 
+/// turns a structured move into its human-readable direction string
 #[rustfmt::skip]
-fn match_diff(diff: (i32, i32), max: bool, amt: i32) -> String {
-    match diff {
-        (0, -1) => if max { "⇈ Max up (+1)".to_string() } else { format!("↑ {amt} up (+{amt})") },
-        (0, 1) => if max { "⇊ Max down (+1)".to_string() } else { format!("↓ {amt} down (+{amt})") },
-        (-1, 0) => if max { "⇇ Max left (+1)".to_string() } else { format!("⇽ {amt} left (+{amt})") },
-        (1, 0) => if max { "⇉ Max right (+1)".to_string() } else { format!("⇾ {amt} right (+{amt})") },
+fn match_diff(m: &Move, labels: &DirectionLabels) -> String {
+    let template = match m.direction {
+        (0, -1) => if m.is_max { &labels.max_up } else { &labels.up },
+        (0, 1) => if m.is_max { &labels.max_down } else { &labels.down },
+        (-1, 0) => if m.is_max { &labels.max_left } else { &labels.left },
+        (1, 0) => if m.is_max { &labels.max_right } else { &labels.right },
 
         _ => unreachable!("the above branches cover all possibilities")
-    }
+    };
+
+    template.replace("{amount}", &m.amount.to_string())
+}
+
+/// formats a perfect run into human-readable directions (e.g "↓ 2 down (+2)")
+pub fn describe_moves(moves: &[Move], labels: &DirectionLabels) -> UserFriendlyDirections {
+    moves.iter().map(|m| match_diff(m, labels)).collect()
 }
 
 /// part of the function below, finds the length to the end of a corridor past a turning point
@@ -74,12 +84,7 @@ fn remaining_length(
 /// this will count the moves in a solution, with the above condition in mind
 ///
 /// this function is quite long, so it's been split into two parts
-fn get_moves(
-    width: i32,
-    height: i32,
-    path: &EdgeVec,
-    walls: &EdgeSet,
-) -> (MoveCount, UserFriendlyDirections) {
+fn get_moves(width: i32, height: i32, path: &EdgeVec, walls: &EdgeSet) -> (MoveCount, Vec<Move>) {
     let mut n_moves = 0;
     let mut perfect_run = vec![];
     let (_, first_af) = path.iter().copied().next().unwrap(); // path is never empty
@@ -111,7 +116,7 @@ fn get_moves(
         let distance_from_before = remaining_length(width, height, before, old_diff, walls);
 
         if to_use > 0 && distance_from_before >= to_use {
-            perfect_run.push(match_diff(old_diff, false, to_use));
+            perfect_run.push(Move::new(old_diff, to_use, false));
             n_moves += to_use;
             continue;
         } else if distance_from_before >= to_use {
@@ -119,24 +124,24 @@ fn get_moves(
         }
 
         n_moves += distance_from_before;
-        perfect_run.push(match_diff(old_diff, true, 1));
+        perfect_run.push(Move::new(old_diff, 1, true));
         if distance_from_before == 1 {
             continue;
         }
 
-        perfect_run.push(match_diff(
+        perfect_run.push(Move::new(
             (-old_diff.0, -old_diff.1),
-            false,
             distance_from_before - 1,
+            false,
         ));
     }
 
     n_moves += 1;
-    perfect_run.push(match_diff(
+    perfect_run.push(Move::new(
         prev_diff,
+        1,
         // maze coordinates are zero-indexed, so width and height are adjusting accordingly
         prev_turn_point != (width - 2, height - 1) && prev_turn_point != (width - 1, height - 2),
-        1,
     ));
 
     (n_moves, perfect_run)
@@ -162,7 +167,7 @@ fn trace_path(min: i32, mut current: AStarNode, closed: &HashMap<Point, AStarNod
 
 /// part of the function below
 fn a_star_for_neighbours(
-    neighbours: &Vec<Point>,
+    neighbours: &[Point],
     best: AStarNode,
     walls: &EdgeSet,
     end: Point,
@@ -172,7 +177,7 @@ fn a_star_for_neighbours(
     let f_predicate = |&n: &&(i32, i32)| {
         !walls.contains(&(best.xy, *n))
             && !walls.contains(&(*n, best.xy))
-            && !closed.contains_key(&n)
+            && !closed.contains_key(n)
     };
 
     neighbours.iter().filter(f_predicate).for_each(|n| {
@@ -194,7 +199,7 @@ fn a_star_for_neighbours(
 type MoveCount = i32;
 type UserFriendlyDirections = Vec<String>;
 
-/// uses the A* algorithm to compute a maze's solution
+/// uses the A* algorithm to compute a maze's solution, or `None` if walls cut the goal off from the start
 ///
 /// this was quite a long function, so it's been split into multiple parts
 ///
@@ -203,7 +208,7 @@ pub fn a_star_solution(
     walls: &EdgeSet,
     width: i32,
     height: i32,
-) -> (MoveCount, UserFriendlyDirections, EdgeVec) {
+) -> Option<(MoveCount, Vec<Move>, EdgeVec)> {
     let min = width + height - 2; // theoretical minimum amount of moves it takes to finish a maze of a given size
     let mut open: HashSet<AStarNode> = HashSet::with_capacity(min as usize);
     let mut closed: HashMap<Point, AStarNode> = HashMap::with_capacity(min as usize);
@@ -219,11 +224,11 @@ pub fn a_star_solution(
 
     let end = (width - 1, height - 1);
     let last_node = loop {
+        // running out of cells to look at means the goal can't be reached
         let best = open
             .iter()
             .min_by(|a, b| i32::cmp(&a.f_cost, &b.f_cost))
-            .copied()
-            .unwrap_or(start_node);
+            .copied()?;
 
         open.remove(&best);
         closed.insert(best.xy, best);
@@ -238,5 +243,229 @@ pub fn a_star_solution(
     let path = trace_path(min, last_node, &closed);
     let (n_moves, moves) = get_moves(width, height, &path.iter().rev().copied().collect(), walls);
 
-    (n_moves, moves, path)
+    Some((n_moves, moves, path))
+}
+
+/// which of the items being looked for have been picked up, one bit each
+type ItemMask = u64;
+
+/// a cell, which switches have been pressed by the time the player's there, and which items they've picked up
+type Spot = (Point, SwitchMask, ItemMask);
+
+/// `(move, travel, cost, picked_up)` a move that can be made from a cell, and the items it picks up
+type MoveOption = (Move, Travel, MoveCount, ItemMask);
+
+/// `(from, move, travel)` of every move along a route, from the start to the end
+type Route = Vec<(Point, Move, Travel)>;
+
+/// the most items that `collect_all_solution` finds the very best order for, past that it goes for the nearest one each time
+pub const EXACT_ITEMS: usize = 8;
+
+/// Dijkstra's algorithm over whole moves (a single step, or a max move, in each direction) from `start`,
+/// until it gets to a spot that `done` is happy with
+///
+/// moving onto or over a cell in `items` picks up the items in its mask; returns the spot it ended on,
+/// how many moves it took to get there, and the route there
+fn cheapest_route(
+    walls: &EdgeSet,
+    mechanics: &Mechanics,
+    items: &HashMap<Point, ItemMask>,
+    start: Spot,
+    done: impl Fn(Spot) -> bool,
+    width: i32,
+    height: i32,
+) -> Option<(Spot, MoveCount, Route)> {
+    const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+    let mut costs = HashMap::from([(start, 0)]);
+    // the moves out of a cell don't depend on which items have been picked up, so they're only made once,
+    // along with what each one costs and which items it picks up
+    let mut moves_from: HashMap<(Point, SwitchMask), Vec<MoveOption>> = HashMap::new();
+    // which spot each one was best reached from, and which of its moves got there
+    let mut came_from: HashMap<Spot, (Spot, usize)> = HashMap::new();
+    let mut queue = BinaryHeap::from([Reverse((0, start))]);
+    let (last, cost) = loop {
+        let Reverse((cost, current)) = queue.pop()?;
+        if done(current) {
+            break (current, cost);
+        }
+
+        if costs.get(&current).is_some_and(|&best| cost > best) {
+            continue;
+        }
+
+        let (xy, pressed, collected) = current;
+        let options = moves_from.entry((xy, pressed)).or_insert_with(|| {
+            DIRECTIONS
+                .into_iter()
+                .flat_map(|d| [Move::new(d, 1, false), Move::new(d, 1, true)])
+                .map(|m| {
+                    let travel = mechanics.travel(walls, xy, m, pressed, None, width, height);
+                    let cost = m.cost() + travel.extra_cost;
+                    let picked = travel
+                        .visited
+                        .iter()
+                        .filter_map(|xy| items.get(xy))
+                        .fold(0, |mask, bits| mask | bits);
+
+                    (m, travel, cost, picked)
+                })
+                .collect()
+        });
+
+        for (i, (_, travel, move_cost, picked)) in options.iter().enumerate() {
+            let next = (travel.position, travel.pressed, collected | picked);
+            if travel.collided || next == current {
+                continue;
+            }
+
+            let next_cost = cost + move_cost;
+            if costs.get(&next).is_some_and(|&best| next_cost >= best) {
+                continue;
+            }
+
+            costs.insert(next, next_cost);
+            came_from.insert(next, (current, i));
+            queue.push(Reverse((next_cost, next)));
+        }
+    };
+
+    let mut route = vec![];
+    let mut current = last;
+    while current != start {
+        let (from, i) = came_from[&current];
+        let (m, travel, _, _) = moves_from[&(from.0, from.1)][i].clone();
+        route.push((from.0, m, travel));
+        current = from;
+    }
+    route.reverse();
+
+    Some((last, cost, route))
+}
+
+/// turns a route into its moves, merging single steps in a row into longer moves wherever playing them back
+/// would end up in the same place, along with the path it takes (from the end back to the start)
+fn route_moves(route: Route) -> (Vec<Move>, EdgeVec) {
+    let mut path = vec![];
+    let mut moves: Vec<Move> = vec![];
+    // whether the last move can be carried on by another step, which it can't after it slid on ice
+    let mut extendable = false;
+    for (from, m, travel) in route {
+        let mut previous = from;
+        for &cell in &travel.visited {
+            path.push((previous, cell));
+            previous = cell;
+        }
+
+        match moves.last_mut() {
+            Some(last) if extendable && !m.is_max && last.direction == m.direction => {
+                last.amount += 1;
+            }
+            _ => moves.push(m),
+        }
+
+        extendable = !m.is_max && !travel.slid;
+    }
+    path.reverse();
+
+    (moves, path)
+}
+
+/// the solution to a maze with portals, switches, or terrain, or `None` if the goal can't be reached from the start
+///
+/// A*'s heuristic doesn't hold up once the player can jump across the maze, open up walls, or get slowed down by mud,
+/// so this is Dijkstra's algorithm over whole moves instead, where each spot is a cell along with which switches
+/// have been pressed
+pub fn mechanics_solution(
+    walls: &EdgeSet,
+    mechanics: &Mechanics,
+    width: i32,
+    height: i32,
+) -> Option<(MoveCount, Vec<Move>, EdgeVec)> {
+    let end = (width - 1, height - 1);
+    let at_end = |(xy, _, _): Spot| xy == end;
+    let start = ((0, 0), 0, 0);
+    let (_, n_moves, route) = cheapest_route(
+        walls,
+        mechanics,
+        &HashMap::new(),
+        start,
+        at_end,
+        width,
+        height,
+    )?;
+
+    let (moves, path) = route_moves(route);
+    Some((n_moves, moves, path))
+}
+
+/// a route from the start that picks up every one of `items` before ending on the goal,
+/// or `None` if one of them (or the goal) can't be reached
+///
+/// up to `EXACT_ITEMS` items, every spot also keeps track of which items have been picked up, so the route found
+/// is the shortest there is; past that, it heads for whichever item is the fewest moves away each time instead,
+/// which is usually close but not always the best
+pub fn collect_all_solution(
+    walls: &EdgeSet,
+    mechanics: &Mechanics,
+    items: &[Point],
+    width: i32,
+    height: i32,
+) -> Option<(MoveCount, Vec<Move>, EdgeVec)> {
+    let end = (width - 1, height - 1);
+    let start = ((0, 0), 0, 0);
+
+    // items sharing a cell are picked up together, and ones on the start are picked up before the route leaves it
+    let mut items: Vec<Point> = items.iter().copied().filter(|xy| *xy != (0, 0)).collect();
+    items.sort_unstable();
+    items.dedup();
+
+    if items.len() <= EXACT_ITEMS {
+        let bits: HashMap<Point, ItemMask> = items
+            .iter()
+            .enumerate()
+            .map(|(i, xy)| (*xy, 1 << i))
+            .collect();
+        let all = (1 << items.len()) - 1;
+        let done = |(xy, _, picked): Spot| xy == end && picked == all;
+        let (_, n_moves, route) =
+            cheapest_route(walls, mechanics, &bits, start, done, width, height)?;
+
+        let (moves, path) = route_moves(route);
+        return Some((n_moves, moves, path));
+    }
+
+    let mut remaining: HashSet<Point> = items.iter().copied().collect();
+    let (mut current, mut n_moves, mut full_route) = (start, 0, vec![]);
+    while !remaining.is_empty() {
+        // every item left is worth the same, it's just the first one to be picked up that matters
+        let bits = remaining.iter().map(|xy| (*xy, 1)).collect();
+        let done = |(_, _, picked): Spot| picked == 1;
+        let ((xy, pressed, _), cost, route) =
+            cheapest_route(walls, mechanics, &bits, current, done, width, height)?;
+
+        for (_, _, travel) in &route {
+            travel.visited.iter().for_each(|xy| {
+                remaining.remove(xy);
+            });
+        }
+
+        (current, n_moves) = ((xy, pressed, 0), n_moves + cost);
+        full_route.extend(route);
+    }
+
+    let at_end = |(xy, _, _): Spot| xy == end;
+    let (_, cost, route) = cheapest_route(
+        walls,
+        mechanics,
+        &HashMap::new(),
+        current,
+        at_end,
+        width,
+        height,
+    )?;
+    full_route.extend(route);
+
+    let (moves, path) = route_moves(full_route);
+    Some((n_moves + cost, moves, path))
 }
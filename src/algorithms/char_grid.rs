@@ -0,0 +1,313 @@
+use crate::types::{EdgeSet, Point, Pxl};
+use crate::util::wall_grids;
+
+/// which characters `maze_text` draws walls with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextStyle {
+    /// `+`, `-`, and `|`
+    Ascii,
+    /// box-drawing characters, joined up properly at every corner
+    Unicode,
+}
+
+impl TextStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ascii" => Some(Self::Ascii),
+            "unicode" => Some(Self::Unicode),
+            _ => None,
+        }
+    }
+}
+
+/// the cells to mark on a rendered maze, if any
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Marks {
+    pub player: Option<Point>,
+    pub goal: Option<Point>,
+}
+
+/// the walls of a maze as lines along the grid between its cells, including the outer border
+pub struct GridLines {
+    horizontal: Vec<Vec<bool>>,
+    vertical: Vec<Vec<bool>>,
+    width: i32,
+    height: i32,
+}
+
+impl GridLines {
+    pub fn new(walls: &EdgeSet, width: i32, height: i32) -> Self {
+        let (horizontal, vertical) = wall_grids(walls, width, height);
+        Self {
+            horizontal,
+            vertical,
+            width,
+            height,
+        }
+    }
+
+    /// whether there's a line along the top of `(x, y)`, with `y` going up to the height for the bottom border
+    pub fn above(&self, x: i32, y: i32) -> bool {
+        y == 0 || y == self.height || self.horizontal[y as usize - 1][x as usize]
+    }
+
+    /// whether there's a line along the left of `(x, y)`, with `x` going up to the width for the right border
+    pub fn left_of(&self, x: i32, y: i32) -> bool {
+        x == 0 || x == self.width || self.vertical[y as usize][x as usize - 1]
+    }
+
+    /// which of the lines `(up, down, left, right)` meet at the top left corner of `(x, y)`
+    fn corner(&self, x: i32, y: i32) -> (bool, bool, bool, bool) {
+        (
+            y > 0 && self.left_of(x, y - 1),
+            y < self.height && self.left_of(x, y),
+            x > 0 && self.above(x - 1, y),
+            x < self.width && self.above(x, y),
+        )
+    }
+}
+
+fn box_char(corner: (bool, bool, bool, bool)) -> char {
+    match corner {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+/// draws the maze out of characters, three per cell across and one down, with the outer border closed off
+///
+/// the player and goal get marked in the middle of their cells if they're given
+pub fn maze_text(
+    walls: &EdgeSet,
+    style: TextStyle,
+    marks: Marks,
+    width: i32,
+    height: i32,
+) -> String {
+    let lines = GridLines::new(walls, width, height);
+    let (across, down, player_mark, goal_mark) = match style {
+        TextStyle::Ascii => ("---", '|', " @ ", " X "),
+        TextStyle::Unicode => ("───", '│', " ● ", " ◎ "),
+    };
+
+    let mut rows = Vec::with_capacity(height as usize * 2 + 1);
+    for y in 0..=height {
+        let mut row = String::new();
+        for x in 0..=width {
+            row.push(match style {
+                TextStyle::Unicode => box_char(lines.corner(x, y)),
+                TextStyle::Ascii if lines.corner(x, y) == (false, false, false, false) => ' ',
+                TextStyle::Ascii => '+',
+            });
+
+            if x < width {
+                row.push_str(if lines.above(x, y) { across } else { "   " });
+            }
+        }
+
+        rows.push(row.trim_end().to_owned());
+        if y == height {
+            break;
+        }
+
+        let mut row = String::new();
+        for x in 0..=width {
+            row.push(if lines.left_of(x, y) { down } else { ' ' });
+            if x < width {
+                row.push_str(match Some((x, y)) {
+                    xy if xy == marks.player => player_mark,
+                    xy if xy == marks.goal => goal_mark,
+                    _ => "   ",
+                });
+            }
+        }
+
+        rows.push(row.trim_end().to_owned());
+    }
+
+    rows.join("\n")
+}
+
+/// the colours `maze_ansi` paints with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AnsiColours {
+    pub bg: Pxl,
+    pub wall: Pxl,
+    pub path: Pxl,
+    /// whether the terminal supports 24-bit colour, if not the colours are squashed down to the 256 colour palette
+    pub truecolor: bool,
+}
+
+/// what fills each block of a maze drawn as a grid of blocks (one for each cell, wall, and corner)
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Block {
+    Open,
+    Wall,
+    Path,
+}
+
+/// the escape code to set the background colour, transparent colours leave the terminal's own background showing
+fn ansi_bg(colour: Pxl, truecolor: bool) -> String {
+    let [r, g, b, a] = colour.0;
+    if a == 0 {
+        "\x1b[49m".to_owned()
+    } else if truecolor {
+        format!("\x1b[48;2;{r};{g};{b}m")
+    } else {
+        // the 6x6x6 colour cube that takes up most of the 256 colour palette
+        let level = |c: u8| (u16::from(c) * 5 + 127) / 255;
+        format!(
+            "\x1b[48;5;{}m",
+            16 + 36 * level(r) + 6 * level(g) + level(b)
+        )
+    }
+}
+
+/// where a cell's block is in a block grid
+pub fn block_of(xy: Point) -> (usize, usize) {
+    (xy.0 as usize * 2 + 1, xy.1 as usize * 2 + 1)
+}
+
+/// splits the maze up into a grid of blocks, `2w + 1` across and `2h + 1` down,
+/// with `path` (if given) filled in along with the gaps between its cells
+pub fn block_grid(
+    walls: &EdgeSet,
+    path: Option<&[Point]>,
+    width: i32,
+    height: i32,
+) -> Vec<Vec<Block>> {
+    let lines = GridLines::new(walls, width, height);
+    let (cols, rows) = (width as usize * 2 + 1, height as usize * 2 + 1);
+    let mut blocks = vec![vec![Block::Open; cols]; rows];
+
+    for y in 0..=height {
+        for x in 0..=width {
+            let (bx, by) = (x as usize * 2, y as usize * 2);
+            if lines.corner(x, y) != (false, false, false, false) {
+                blocks[by][bx] = Block::Wall;
+            }
+
+            if x < width && lines.above(x, y) {
+                blocks[by][bx + 1] = Block::Wall;
+            }
+
+            if y < height && lines.left_of(x, y) {
+                blocks[by + 1][bx] = Block::Wall;
+            }
+        }
+    }
+
+    for pair in path.unwrap_or_default().windows(2) {
+        let (from, to) = (block_of(pair[0]), block_of(pair[1]));
+        blocks[from.1][from.0] = Block::Path;
+        blocks[(from.1 + to.1) / 2][(from.0 + to.0) / 2] = Block::Path;
+        blocks[to.1][to.0] = Block::Path;
+    }
+
+    blocks
+}
+
+/// draws the maze with coloured blocks for terminals, each cell and wall being a block two characters wide
+///
+/// `path` (if given) is a list of cells that gets coloured in along with the gaps between them,
+/// and the player and goal are marked with `<>` and `[]` if they're given
+pub fn maze_ansi(
+    walls: &EdgeSet,
+    colours: AnsiColours,
+    path: Option<&[Point]>,
+    marks: Marks,
+    width: i32,
+    height: i32,
+) -> String {
+    let blocks = block_grid(walls, path, width, height);
+    let marked = |bx: usize, by: usize| {
+        let is = |xy: Option<Point>| xy.is_some_and(|xy| block_of(xy) == (bx, by));
+        if is(marks.player) {
+            "<>"
+        } else if is(marks.goal) {
+            "[]"
+        } else {
+            "  "
+        }
+    };
+
+    let mut out = String::new();
+    for (by, row) in blocks.iter().enumerate() {
+        let mut last = None;
+        for (bx, block) in row.iter().enumerate() {
+            if last != Some(*block) {
+                let colour = match block {
+                    Block::Open => colours.bg,
+                    Block::Wall => colours.wall,
+                    Block::Path => colours.path,
+                };
+
+                out.push_str(&ansi_bg(colour, colours.truecolor));
+                last = Some(*block);
+            }
+
+            out.push_str(marked(bx, by));
+        }
+
+        out.push_str("\x1b[0m\n");
+    }
+
+    out.pop(); // the last newline
+    out
+}
+
+/// the emoji `maze_emoji` draws each kind of block with
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmojiSet {
+    pub wall: String,
+    pub open: String,
+    pub path: String,
+    pub player: String,
+    pub goal: String,
+}
+
+/// draws the maze as rows of emoji, with a block for each cell, wall, and corner
+///
+/// `path` (if given) is a list of cells that gets filled in along with the gaps between them,
+/// and the player and goal are drawn over whatever's under them if they're given
+pub fn maze_emoji(
+    walls: &EdgeSet,
+    emoji: &EmojiSet,
+    path: Option<&[Point]>,
+    marks: Marks,
+    width: i32,
+    height: i32,
+) -> String {
+    let mut rows = Vec::with_capacity(height as usize * 2 + 1);
+    for (by, row) in block_grid(walls, path, width, height).iter().enumerate() {
+        let mut line = String::new();
+        for (bx, block) in row.iter().enumerate() {
+            let is = |xy: Option<Point>| xy.is_some_and(|xy| block_of(xy) == (bx, by));
+            line.push_str(match block {
+                _ if is(marks.player) => &emoji.player,
+                _ if is(marks.goal) => &emoji.goal,
+                Block::Open => &emoji.open,
+                Block::Wall => &emoji.wall,
+                Block::Path => &emoji.path,
+            });
+        }
+
+        rows.push(line);
+    }
+
+    rows.join("\n")
+}
@@ -0,0 +1,95 @@
+use crate::types::{EdgeSet, Point};
+use crate::util::slide;
+
+/// the four directions with how they're said when going that way, and when something's over that way
+const DIRECTIONS: [((i32, i32), &str, &str); 4] = [
+    ((0, -1), "up", "above you"),
+    ((0, 1), "down", "below you"),
+    ((-1, 0), "left", "to the left"),
+    ((1, 0), "right", "to the right"),
+];
+
+fn cells(n: i32) -> String {
+    match n {
+        1 => "1 cell".to_owned(),
+        n => format!("{n} cells"),
+    }
+}
+
+/// joins a list up the way it'd be said, e.g `"a, b, or c"`
+fn spoken_list(items: &[String], conjunction: &str) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [a, b] => format!("{a} {conjunction} {b}"),
+        [rest @ .., last] => format!("{}, {conjunction} {last}", rest.join(", ")),
+    }
+}
+
+/// describes what can be seen from `xy` in plain English, for playing with a screen reader
+///
+/// covers which ways are open and how far until the next wall in each,
+/// which ways are walled off, and whether the goal can be seen from here
+pub fn describe_surroundings(
+    walls: &EdgeSet,
+    xy: Point,
+    goal: Point,
+    width: i32,
+    height: i32,
+) -> String {
+    let mut sentences = vec![if xy == goal {
+        format!("You're at the goal, {xy:?}.")
+    } else {
+        format!("You're at {xy:?}.")
+    }];
+
+    let mut open = vec![];
+    let mut blocked = vec![];
+    let mut goal_seen = None;
+    for (direction, going, over) in DIRECTIONS {
+        let end = slide(walls, xy, direction, width, height);
+        let distance = (end.0 - xy.0).abs() + (end.1 - xy.1).abs();
+        if distance == 0 {
+            blocked.push(over.trim_end_matches(" you").to_owned());
+            continue;
+        }
+
+        open.push(format!("{going} for {}", cells(distance)));
+        let to_goal = (goal.0 - xy.0, goal.1 - xy.1);
+        let in_line = to_goal.0 * direction.1 == to_goal.1 * direction.0;
+        let ahead = to_goal.0 * direction.0 + to_goal.1 * direction.1;
+        if in_line && (1..=distance).contains(&ahead) {
+            goal_seen = Some((ahead, over));
+        }
+    }
+
+    sentences.push(match (open.is_empty(), blocked.is_empty()) {
+        (true, _) => "There are walls on every side.".to_owned(),
+        (false, true) => format!("You can go {}.", spoken_list(&open, "or")),
+        (false, false) => format!(
+            "You can go {}, and {} {}.",
+            spoken_list(&open, "or"),
+            if blocked.len() == 1 {
+                "there's a wall"
+            } else {
+                "there are walls"
+            },
+            spoken_list(&blocked, "and")
+        ),
+    });
+
+    match open.len() {
+        1 if xy != goal => sentences.push("This is a dead end.".to_owned()),
+        3 | 4 => sentences.push("This is a junction.".to_owned()),
+        _ => {}
+    }
+
+    if xy != goal {
+        sentences.push(match goal_seen {
+            Some((distance, over)) => format!("The goal is in sight, {} {over}.", cells(distance)),
+            None => "The goal isn't in sight.".to_owned(),
+        });
+    }
+
+    sentences.join(" ")
+}
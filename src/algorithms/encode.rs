@@ -0,0 +1,137 @@
+use crate::types::Pxl;
+
+use image::codecs::{
+    bmp::BmpEncoder,
+    gif::{GifEncoder, Repeat},
+    jpeg::JpegEncoder,
+    png::{CompressionType, FilterType, PngEncoder},
+};
+use image::error::{EncodingError, ImageFormatHint};
+use image::{ColorType, Delay, Frame, ImageEncoder, ImageError, ImageFormat, ImageResult};
+use imageproc::definitions::Image;
+
+use std::io::Write;
+
+/// formats that the maze image can be exported as
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// with the compression level and filter to encode with
+    Png(CompressionType, FilterType),
+    /// with a quality from 1 to 100, anything (partly) transparent is flattened onto white since JPEGs can't store alpha
+    Jpeg(u8),
+    /// always lossless
+    WebP,
+    Bmp,
+}
+
+impl OutputFormat {
+    /// the format with a given name (case-insensitive), `quality` only matters for JPEG
+    pub fn from_name(name: &str, quality: u8) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png(CompressionType::Fast, FilterType::Adaptive)),
+            "jpeg" | "jpg" => Some(Self::Jpeg(quality)),
+            "webp" => Some(Self::WebP),
+            "bmp" => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+}
+
+/// the PNG compression level with a given name (case-insensitive)
+pub fn png_compression_from_name(name: &str) -> Option<CompressionType> {
+    match name.to_ascii_lowercase().as_str() {
+        "fast" => Some(CompressionType::Fast),
+        "default" => Some(CompressionType::Default),
+        "best" => Some(CompressionType::Best),
+        _ => None,
+    }
+}
+
+/// the PNG filter with a given name (case-insensitive)
+pub fn png_filter_from_name(name: &str) -> Option<FilterType> {
+    match name.to_ascii_lowercase().as_str() {
+        "none" => Some(FilterType::NoFilter),
+        "sub" => Some(FilterType::Sub),
+        "up" => Some(FilterType::Up),
+        "avg" => Some(FilterType::Avg),
+        "paeth" => Some(FilterType::Paeth),
+        "adaptive" => Some(FilterType::Adaptive),
+        _ => None,
+    }
+}
+
+/// encodes an image in a given format, writing it out to `w`
+pub fn encode_image<W: Write>(img: &Image<Pxl>, format: OutputFormat, mut w: W) -> ImageResult<()> {
+    let (width, height) = img.dimensions();
+    let data = img.as_raw();
+
+    match format {
+        OutputFormat::Png(compression, filter) => PngEncoder::new_with_quality(
+            w,
+            compression,
+            filter,
+        )
+        .write_image(data, width, height, ColorType::Rgba8),
+        OutputFormat::Jpeg(quality) => {
+            let flat = flattened_rgb(img);
+            JpegEncoder::new_with_quality(w, quality).write_image(
+                &flat,
+                width,
+                height,
+                ColorType::Rgb8,
+            )
+        }
+        OutputFormat::Bmp => {
+            BmpEncoder::new(&mut w).write_image(data, width, height, ColorType::Rgba8)
+        }
+        OutputFormat::WebP => image_webp::WebPEncoder::new(w)
+            .encode(data, width, height, image_webp::ColorType::Rgba8)
+            .map_err(|e| {
+                let hint = ImageFormatHint::Exact(ImageFormat::WebP);
+                ImageError::Encoding(EncodingError::new(hint, e))
+            }),
+    }
+}
+
+/// the raw RGB data of an image blended over a white backdrop
+fn flattened_rgb(img: &Image<Pxl>) -> Vec<u8> {
+    img.pixels()
+        .flat_map(|px| {
+            let [r, g, b, a] = px.0.map(u16::from);
+            // white shows through wherever the pixel is transparent
+            [r, g, b].map(|c| ((c * a + 255 * (255 - a)) / 255) as u8)
+        })
+        .collect()
+}
+
+/// encodes an image in a given format into a new buffer
+pub fn encode_to_vec(img: &Image<Pxl>, format: OutputFormat) -> ImageResult<Vec<u8>> {
+    let mut buf = vec![];
+    encode_image(img, format, &mut buf)?;
+    Ok(buf)
+}
+
+/// encodes an image as a PNG
+pub fn encode_png(img: &Image<Pxl>) -> ImageResult<Vec<u8>> {
+    let format = OutputFormat::Png(CompressionType::Fast, FilterType::Adaptive);
+    encode_to_vec(img, format)
+}
+
+/// encodes frames into a looping GIF, with `delay_ms` milliseconds between each frame
+pub fn encode_gif(frames: Vec<Image<Pxl>>, delay_ms: u32) -> ImageResult<Vec<u8>> {
+    let mut buf = vec![];
+    {
+        // the default speed of 1 takes forever to quantize anything bigger than a tiny maze
+        let mut encoder = GifEncoder::new_with_speed(&mut buf, 10);
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let delay = Delay::from_numer_denom_ms(delay_ms, 1);
+        encoder.encode_frames(
+            frames
+                .into_iter()
+                .map(|f| Frame::from_parts(f, 0, 0, delay)),
+        )?;
+    }
+
+    Ok(buf)
+}
@@ -1,24 +1,203 @@
-use crate::types::{EdgeSet, EdgeVec, Pxl};
+use crate::types::{
+    Background, BackgroundFit, DrawStyle, EdgeSet, EdgeVec, Frame, PathPaint, Point, Pxl, WallCap,
+    WallFill,
+};
 
-use image::{imageops, GenericImage, Pixel, Rgba, RgbaImage};
-use imageproc::{definitions::Image, drawing::draw_filled_rect_mut, rect::Rect};
+use image::{imageops, io::Reader, GenericImage, Pixel, Rgba, RgbaImage};
+use imageproc::{
+    definitions::Image,
+    drawing::{draw_filled_circle_mut, draw_filled_rect_mut, draw_polygon_mut},
+    point::Point as Vertex,
+    rect::Rect,
+};
 
 use pyo3::prelude::*;
 use rayon::prelude::*;
 
-use pyo3::{exceptions::PyValueError, types::PyBytes};
+use pyo3::{
+    exceptions::{PyIOError, PyValueError},
+    types::PyBytes,
+};
 
-use std::cell::UnsafeCell;
+use std::{
+    cell::UnsafeCell,
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{OnceLock, RwLock},
+};
 
 /// path/wall pixel gaps in generated images
 const CELL: i32 = 20;
 const WALL_THICKNESS: i32 = 3;
 const SHIFT: i32 = 21;
 
+/// how big the player and endzone icons are, which is the size of a cell's inside
+pub const ICON_SIZE: u32 = 37;
+
 // fallback "player icon" in case the images in the assets folder don't work
 pub const HALF_WHITE: Pxl = Rgba([255, 255, 255, 100]);
 pub const HALF_BLACK: Pxl = Rgba([0, 0, 0, 127]);
 
+/// the icons from the assets folder, baked into the binary so that they're found
+/// no matter where the module is installed or run from
+const EMBEDDED_ICONS: [(&str, &[u8]); 4] = [
+    (
+        "player-white",
+        include_bytes!("../../assets/player-white.png"),
+    ),
+    (
+        "player-black",
+        include_bytes!("../../assets/player-black.png"),
+    ),
+    (
+        "endzone-white",
+        include_bytes!("../../assets/endzone-white.png"),
+    ),
+    (
+        "endzone-black",
+        include_bytes!("../../assets/endzone-black.png"),
+    ),
+];
+
+/// an embedded icon's name, and the icon itself if it decoded properly
+type NamedIcon = (&'static str, Option<Image<Pxl>>);
+
+static FALLBACK_ICONS: OnceLock<Vec<NamedIcon>> = OnceLock::new();
+
+/// where fallback icons get loaded from, set from Python with `set_asset_dir`
+pub struct AssetSettings {
+    /// checked before the embedded icons, if it's set
+    pub dir: Option<PathBuf>,
+    /// raise an error when an icon can't be loaded, rather than falling back to a translucent square
+    pub strict: bool,
+}
+
+pub static ASSET_SETTINGS: RwLock<AssetSettings> = RwLock::new(AssetSettings {
+    dir: None,
+    strict: false,
+});
+
+/// the area of the image that a cell's inside (and any icon drawn on it) takes up
+pub fn cell_rect(xy: Point) -> Rect {
+    Rect::at(xy.0 * 40, xy.1 * 40).of_size(ICON_SIZE, ICON_SIZE)
+}
+
+/// how many `(columns, rows)` of `tile_size` square tiles it takes to cover an image
+pub const fn tile_grid(dimensions: (u32, u32), tile_size: u32) -> (u32, u32) {
+    (
+        dimensions.0.div_ceil(tile_size),
+        dimensions.1.div_ceil(tile_size),
+    )
+}
+
+/// cuts out one `tile_size` square tile of an image, counting tiles from the top left
+///
+/// tiles hanging off the right or bottom edge are padded out with transparent pixels so every tile is the same size
+pub fn cut_tile(img: &Image<Pxl>, col: u32, row: u32, tile_size: u32) -> Image<Pxl> {
+    let (x, y) = (col * tile_size, row * tile_size);
+    let w = tile_size.min(img.width().saturating_sub(x));
+    let h = tile_size.min(img.height().saturating_sub(y));
+
+    let mut tile = RgbaImage::new(tile_size, tile_size);
+    imageops::replace(&mut tile, &*imageops::crop_imm(img, x, y, w, h), 0, 0);
+    tile
+}
+
+/// cuts out the cells in the `(x, y, width, height)` rectangle `cells`, along with the walls around them
+pub fn crop_to_cells(img: &Image<Pxl>, cells: (i32, i32, i32, i32)) -> Image<Pxl> {
+    let (x, y, w, h) = cells;
+    let left = (x * 40 - WALL_THICKNESS).max(0) as u32;
+    let top = (y * 40 - WALL_THICKNESS).max(0) as u32;
+    let right = (((x + w - 1) * 40 + ICON_SIZE as i32 + WALL_THICKNESS) as u32).min(img.width());
+    let bottom = (((y + h - 1) * 40 + ICON_SIZE as i32 + WALL_THICKNESS) as u32).min(img.height());
+
+    imageops::crop_imm(img, left, top, right - left, bottom - top).to_image()
+}
+
+/// pads an image out with `frame`'s margin (in `bg_colour`) and draws its border around the outside of it
+///
+/// openings are cut into the top of the border above the top left cell, and the bottom of it
+/// below the bottom right cell
+pub fn framed(
+    img: &Image<Pxl>,
+    frame: Frame,
+    bg_colour: Pxl,
+    width: i32,
+    height: i32,
+) -> Image<Pxl> {
+    let (w, h) = img.dimensions();
+    let (margin, thickness) = (frame.margin, WALL_THICKNESS as u32);
+    let pad = margin + if frame.border.is_some() { thickness } else { 0 };
+
+    let mut out = RgbaImage::from_pixel(w + 2 * pad, h + 2 * pad, bg_colour);
+    imageops::replace(&mut out, img, i64::from(pad), i64::from(pad));
+
+    if let Some(colour) = frame.border {
+        let (m, t) = (margin as i32, WALL_THICKNESS);
+        let (outer_w, outer_h) = (w + 2 * thickness, h + 2 * thickness);
+        let bottom = m + t + h as i32;
+        let right = m + t + w as i32;
+
+        draw_filled_rect_mut(&mut out, Rect::at(m, m).of_size(outer_w, thickness), colour);
+        draw_filled_rect_mut(
+            &mut out,
+            Rect::at(m, bottom).of_size(outer_w, thickness),
+            colour,
+        );
+        draw_filled_rect_mut(&mut out, Rect::at(m, m).of_size(thickness, outer_h), colour);
+        draw_filled_rect_mut(
+            &mut out,
+            Rect::at(right, m).of_size(thickness, outer_h),
+            colour,
+        );
+
+        if frame.openings {
+            let (start, goal) = (cell_rect((0, 0)), cell_rect((width - 1, height - 1)));
+            let gap = |x: i32, y: i32| Rect::at(x + m + t, y).of_size(ICON_SIZE, thickness);
+            draw_filled_rect_mut(&mut out, gap(start.left(), m), bg_colour);
+            draw_filled_rect_mut(&mut out, gap(goal.left(), bottom), bg_colour);
+        }
+    }
+
+    out
+}
+
+/// the area a cell takes up including the walls around it, which is what gets uncovered when fog lifts off of it
+pub fn surrounding_rect(xy: Point) -> Rect {
+    let size = ICON_SIZE + 2 * WALL_THICKNESS as u32;
+    Rect::at(xy.0 * 40 - WALL_THICKNESS, xy.1 * 40 - WALL_THICKNESS).of_size(size, size)
+}
+
+/// the area a line between the middles of two cells in the same row/column takes up,
+/// a bit thinner than the solution path so that both can be told apart
+pub fn trail_rect(from: Point, to: Point) -> Rect {
+    let (x, y) = (from.0.min(to.0) * 40 + 17, from.1.min(to.1) * 40 + 17);
+    let (dx, dy) = (
+        (from.0 - to.0).unsigned_abs(),
+        (from.1 - to.1).unsigned_abs(),
+    );
+    Rect::at(x, y).of_size(dx * 40 + 4, dy * 40 + 4)
+}
+
+/// the colour of a trail segment `age` moves old, fading out linearly over `fade` moves
+///
+/// `None` if it's completely faded out, a `fade` of 0 means the trail never fades
+pub fn faded_trail_colour(colour: Pxl, age: usize, fade: u32) -> Option<Pxl> {
+    if fade == 0 {
+        return Some(colour);
+    }
+
+    let fade = fade as usize;
+    if age >= fade {
+        return None;
+    }
+
+    let mut faded = colour;
+    faded.0[3] = (usize::from(colour.0[3]) * (fade - age) / fade) as u8;
+    Some(faded)
+}
+
 /// wraps an image and allows its mutability to be shared across threads
 /// in our case, each thread is drawing on non-overlapping pixels
 /// so we don't care about the race condition that this creates
@@ -50,49 +229,308 @@ impl<P: Pixel + Sync, I: GenericImage<Pixel = P>> SharedImage<P, I> {
 
 unsafe impl<P: Pixel + Sync, I: GenericImage<Pixel = P>> Sync for SharedImage<P, I> {}
 
+/// how big the maze image is for a maze of a given width and height
+pub const fn maze_image_size(width: i32, height: i32) -> (u32, u32) {
+    // subtract 1 from width and height as the coordinates are zero-indexed
+    (
+        ((width - 1) * CELL * 2 + 37) as u32,
+        ((height - 1) * CELL * 2 + 37) as u32,
+    )
+}
+
+/// stretches or tiles a background image out to `size`
+pub fn fit_background(img: &Image<Pxl>, fit: BackgroundFit, (w, h): (u32, u32)) -> Image<Pxl> {
+    match fit {
+        BackgroundFit::Stretch => imageops::resize(img, w, h, imageops::FilterType::Lanczos3),
+        BackgroundFit::Tile => {
+            let mut out = RgbaImage::new(w, h);
+            imageops::tile(&mut out, img);
+            out
+        }
+    }
+}
+
+/// the colour a background comes out as on average, for picking icons that stand out against it
+pub fn average_colour(bg: &Background) -> Pxl {
+    let Some(img) = &bg.image else {
+        return bg.colour;
+    };
+
+    let n = u64::from(img.width()) * u64::from(img.height());
+    let mut sums = [0u64; 4];
+    for px in img.pixels() {
+        for (sum, channel) in sums.iter_mut().zip(px.0) {
+            *sum += u64::from(channel);
+        }
+    }
+
+    let mut colour = bg.colour;
+    colour.blend(&Rgba(sums.map(|sum| (sum / n.max(1)) as u8)));
+    colour
+}
+
 /// generates the maze image using its wall edges
 pub fn maze_image(
     walls: &EdgeSet,
-    bg_colour: Pxl,
-    wall_colour: Pxl,
+    bg: &Background,
+    fill: &WallFill,
+    style: DrawStyle,
     end_icon: &Image<Pxl>,
     width: i32,
     height: i32,
 ) -> Image<Pxl> {
-    // subtract 1 from width and height as the coordinates are zero-indexed
-    let (w, h) = ((width - 1) * CELL * 2 + 37, (height - 1) * CELL * 2 + 37);
-    let mut img = RgbaImage::from_pixel(w as u32, h as u32, bg_colour);
+    let (w, h) = maze_image_size(width, height);
+    let area = Rect::at(0, 0).of_size(w, h);
+    maze_area(walls, bg, fill, style, end_icon, width, height, area)
+}
+
+/// redraws just `region` of an image made by `maze_image`, for when only the walls around it changed
+#[allow(clippy::too_many_arguments)]
+pub fn redraw_maze_region(
+    img: &mut Image<Pxl>,
+    walls: &EdgeSet,
+    bg: &Background,
+    fill: &WallFill,
+    style: DrawStyle,
+    end_icon: &Image<Pxl>,
+    width: i32,
+    height: i32,
+    region: Rect,
+) {
+    let (w, h) = (img.width() as i32, img.height() as i32);
+    let (x0, y0) = (region.left().max(0), region.top().max(0));
+    let (x1, y1) = ((region.right() + 1).min(w), (region.bottom() + 1).min(h));
+    if x0 >= x1 || y0 >= y1 {
+        return;
+    }
+
+    // a couple extra pixels all around, since the supersampled edges of the walls
+    // come out differently along the sides of a smaller image
+    const MARGIN: i32 = 2;
+    let (px, py) = ((x0 - MARGIN).max(0), (y0 - MARGIN).max(0));
+    let (pw, ph) = ((x1 + MARGIN).min(w) - px, (y1 + MARGIN).min(h) - py);
+    let area = Rect::at(px, py).of_size(pw as u32, ph as u32);
+
+    let nearby: EdgeSet = walls
+        .iter()
+        .filter(|(a, b)| surrounding_rect((*a).min(*b)).intersect(area).is_some())
+        .copied()
+        .collect();
+
+    let patch = maze_area(&nearby, bg, fill, style, end_icon, width, height, area);
+    let (dx, dy) = ((x0 - px) as u32, (y0 - py) as u32);
+    let inner = imageops::crop_imm(&patch, dx, dy, (x1 - x0) as u32, (y1 - y0) as u32);
+    imageops::replace(img, &*inner, i64::from(x0), i64::from(y0));
+}
+
+/// draws the part of the maze image inside of `area`, as its own image
+#[allow(clippy::too_many_arguments)]
+fn maze_area(
+    walls: &EdgeSet,
+    bg: &Background,
+    fill: &WallFill,
+    style: DrawStyle,
+    end_icon: &Image<Pxl>,
+    width: i32,
+    height: i32,
+    area: Rect,
+) -> Image<Pxl> {
+    let (w, h) = (area.width(), area.height());
+    let origin = (area.left(), area.top());
+    let (ox, oy) = (i64::from(origin.0), i64::from(origin.1));
+
+    let mut img = RgbaImage::from_pixel(w, h, bg.colour);
+    if let Some(bg_image) = &bg.image {
+        imageops::overlay(&mut img, bg_image, -ox, -oy);
+    }
 
     let (x, y) = ((i64::from(width) - 1) * 40, (i64::from(height) - 1) * 40);
-    imageops::overlay(&mut img, end_icon, x, y); // draws the end marker at the bottom-right corner
+    imageops::overlay(&mut img, end_icon, x - ox, y - oy); // draws the end marker at the bottom-right corner
+
+    if style.supersample == 1 && fill.texture.is_none() {
+        return draw_walls(img, walls, fill, style, 1, origin);
+    }
+
+    // drawn separately so that the smoothed edges and texture only apply to the walls themselves
+    let mut walls_img = supersampled((w, h), style.supersample, fill.colour, |canvas, factor| {
+        *canvas = draw_walls(std::mem::take(canvas), walls, fill, style, factor, origin);
+    });
+
+    if let Some(texture) = &fill.texture {
+        apply_texture(&mut walls_img, texture, origin);
+    }
+
+    imageops::overlay(&mut img, &walls_img, 0, 0);
+    img
+}
+
+/// the colour of a single wall, with its jitter (if there is any) applied
+fn wall_colour(fill: &WallFill, wall: (Point, Point)) -> Pxl {
+    if fill.jitter == 0 {
+        return fill.colour;
+    }
 
+    let (a, b) = (wall.0.min(wall.1), wall.0.max(wall.1));
+    let mut hasher = DefaultHasher::new();
+    (fill.seed, a, b).hash(&mut hasher);
+
+    let jitter = i32::from(fill.jitter);
+    let shift = (hasher.finish() % (2 * jitter as u64 + 1)) as i32 - jitter;
+
+    let mut colour = fill.colour;
+    for channel in &mut colour.0[..3] {
+        *channel = (i32::from(*channel) + shift).clamp(0, 255) as u8;
+    }
+
+    colour
+}
+
+/// swaps the colour of every wall pixel for the texture's, tiled from the top left, keeping how opaque each pixel is
+///
+/// `origin` is where the top left of `walls_img` is in the whole maze image, so that the tiles line up
+fn apply_texture(walls_img: &mut Image<Pxl>, texture: &Image<Pxl>, (ox, oy): Point) {
+    let (tw, th) = texture.dimensions();
+    let row_len = walls_img.width() as usize * 4;
+    walls_img
+        .par_chunks_mut(row_len)
+        .enumerate()
+        .for_each(|(y, row)| {
+            for (x, px) in row.chunks_exact_mut(4).enumerate() {
+                if px[3] == 0 {
+                    continue;
+                }
+
+                let tex = texture
+                    .get_pixel((x as i32 + ox) as u32 % tw, (y as i32 + oy) as u32 % th)
+                    .0;
+                let alpha = u16::from(px[3]) * u16::from(tex[3]) / 255;
+                px.copy_from_slice(&[tex[0], tex[1], tex[2], alpha as u8]);
+            }
+        });
+}
+
+/// draws every wall onto an image that's `factor` times the size of the maze image,
+/// or the part of it with its top left corner at `origin`
+fn draw_walls(
+    img: Image<Pxl>,
+    walls: &EdgeSet,
+    fill: &WallFill,
+    style: DrawStyle,
+    factor: i32,
+    origin: Point,
+) -> Image<Pxl> {
     let shared = SharedImage::new(img);
     walls.par_iter().for_each(|(node1, node2)| {
-        let (x, y) = (((node1.0 + 1) * CELL * 2), ((node1.1 + 1) * CELL * 2));
-        let rect = if node1.0 == node2.0 {
-            Rect::at(x - 43, y - WALL_THICKNESS).of_size(43, WALL_THICKNESS as u32)
-        } else {
-            Rect::at(x - WALL_THICKNESS, y - 43).of_size(WALL_THICKNESS as u32, 43)
-        };
-
         let img = shared.get_image_mut();
-        draw_filled_rect_mut(img, rect, wall_colour);
+        draw_wall(
+            img,
+            (*node1).min(*node2),
+            node1.0 == node2.0,
+            wall_colour(fill, (*node1, *node2)),
+            style,
+            factor,
+            origin,
+        );
     });
 
     shared.into_inner()
 }
 
+/// draws the wall on the bottom (if `horizontal`) or right side of `cell`, scaled up by `factor`
+/// and shifted so that `origin` is at the top left of `img`
+fn draw_wall(
+    img: &mut Image<Pxl>,
+    cell: Point,
+    horizontal: bool,
+    colour: Pxl,
+    style: DrawStyle,
+    factor: i32,
+    (ox, oy): Point,
+) {
+    let thickness = WALL_THICKNESS * factor;
+    let half = thickness / 2;
+
+    // where the corner the wall starts at begins, along the wall and across it
+    let (along, across) = if horizontal {
+        (
+            cell.0 * 40 - WALL_THICKNESS - ox,
+            (cell.1 + 1) * 40 - WALL_THICKNESS - oy,
+        )
+    } else {
+        (
+            cell.1 * 40 - WALL_THICKNESS - oy,
+            (cell.0 + 1) * 40 - WALL_THICKNESS - ox,
+        )
+    };
+
+    let (along, across) = (along * factor, across * factor);
+
+    // the wall itself runs from the middle of one corner to the middle of the next
+    let (start, end) = (along + half, along + half + 40 * factor);
+    let (on, off) = match style.wall_dash {
+        Some((on, off)) => (on as i32 * factor, off as i32 * factor),
+        None => (end - start, 0),
+    };
+
+    let mut dash_start = start;
+    while dash_start < end {
+        let dash_end = (dash_start + on).min(end);
+        let (from, to) = match style.wall_cap {
+            WallCap::Square => (dash_start - half, dash_end + thickness - half),
+            WallCap::Butt | WallCap::Round => (dash_start, dash_end),
+        };
+
+        let length = (to - from).max(1) as u32;
+        let rect = if horizontal {
+            Rect::at(from, across).of_size(length, thickness as u32)
+        } else {
+            Rect::at(across, from).of_size(thickness as u32, length)
+        };
+
+        draw_filled_rect_mut(img, rect, colour);
+        if style.wall_cap == WallCap::Round {
+            for point in [dash_start, dash_end] {
+                let centre = if horizontal {
+                    (point, across + half)
+                } else {
+                    (across + half, point)
+                };
+
+                draw_filled_circle_mut(img, centre, half, colour);
+            }
+        }
+
+        dash_start += on + off;
+    }
+}
+
 /// very similar to the function above, but still different enough to where a single macro
 /// can't cover both functions without tons of function-specific casing... and indents
 /// or maybe that's just a skill issue on my part
 pub fn solution_image(
     original: Image<Pxl>,
-    solution: &EdgeVec,
-    solution_line_colour: Pxl,
+    solution: &[(Point, Point)],
+    paint: PathPaint,
+) -> Image<Pxl> {
+    solution_segments_image(original, solution, 0, solution.len(), paint)
+}
+
+/// draws part of a solution path, `first` being how far along the whole path (which is `total` segments long)
+/// `segments` starts, so that gradients pick up where the last part left off
+fn solution_segments_image(
+    original: Image<Pxl>,
+    segments: &[(Point, Point)],
+    first: usize,
+    total: usize,
+    paint: PathPaint,
 ) -> Image<Pxl> {
     let shared = SharedImage::new(original);
+    let draw = |(i, (node1, node2)): (usize, &(Point, Point))| {
+        // jumps through a portal aren't drawn, there's nothing to draw between the two ends
+        if (node1.0 - node2.0).abs() + (node1.1 - node2.1).abs() != 1 {
+            return;
+        }
 
-    solution.par_iter().for_each(|(node1, node2)| {
         let (x, y) = ((((node1.0 + 1) * CELL) * 2), (((node1.1 + 1) * CELL) * 2));
         let rect = if node1.0 == node2.0 {
             let coords = if node1.1 < node2.1 {
@@ -113,29 +551,430 @@ pub fn solution_image(
         };
 
         let img = shared.get_image_mut();
-        draw_filled_rect_mut(img, rect, solution_line_colour);
-    });
+        match paint {
+            PathPaint::Solid(colour) => draw_filled_rect_mut(img, rect, colour),
+            PathPaint::Gradient(start, end) => {
+                // paths are traced back from the goal, so this steps from `node1` to `node2` in
+                // one pixel thick slices, with `progress` being how far back from the goal each one is
+                let vertical = node1.0 == node2.0;
+                let reversed = if vertical {
+                    node1.1 > node2.1
+                } else {
+                    node1.0 > node2.0
+                };
+                for step in 0..46 {
+                    let along = if reversed { 45 - step } else { step };
+                    let progress = ((first + i) as f32 + step as f32 / 45.0) / total.max(1) as f32;
+                    let colour = lerp_colour(end, start, progress);
+                    let slice = if vertical {
+                        Rect::at(rect.left(), rect.top() + along).of_size(6, 1)
+                    } else {
+                        Rect::at(rect.left() + along, rect.top()).of_size(1, 6)
+                    };
+
+                    draw_filled_rect_mut(img, slice, colour);
+                }
+            }
+        }
+    };
+
+    // segments overlap at the corners, so gradients are drawn in order for each corner to come out the same
+    // every time, while a solid colour looks the same whichever segment ends up on top
+    match paint {
+        PathPaint::Solid(_) => segments.par_iter().enumerate().for_each(draw),
+        PathPaint::Gradient(..) => segments.iter().enumerate().for_each(draw),
+    }
 
     shared.into_inner()
 }
 
+/// the colour `t` of the way from `start` to `end`, with `t` going from 0 to 1
+pub fn lerp_colour(start: Pxl, end: Pxl, t: f32) -> Pxl {
+    let t = t.clamp(0.0, 1.0);
+    let mut out = start;
+    for (channel, (a, b)) in out.0.iter_mut().zip(start.0.iter().zip(end.0)) {
+        *channel = (f32::from(*a) + (f32::from(b) - f32::from(*a)) * t).round() as u8;
+    }
+
+    out
+}
+
+/// decodes the embedded icons, this only does any work the first time it's called
+pub fn load_fallback_icons() -> &'static [NamedIcon] {
+    FALLBACK_ICONS.get_or_init(|| {
+        EMBEDDED_ICONS
+            .iter()
+            .map(|(name, bytes)| {
+                let img = image::load_from_memory_with_format(bytes, image::ImageFormat::Png);
+                (*name, img.ok().map(|img| img.into_rgba8()))
+            })
+            .collect()
+    })
+}
+
+/// whether the dark fallback icons should be used over a background, rather than the light ones
+///
+/// a (mostly) transparent background could end up shown over anything, so the icons go with the walls instead,
+/// since those have presumably been picked to stand out against whatever's behind the image
+fn wants_dark_icons(bg_colour: Pxl, wall_colour: Pxl) -> bool {
+    // summing the u8 channels will most likely overflow
+    let sum = |channels: &[u8]| -> u16 { channels.iter().map(|n_u8| u16::from(*n_u8)).sum() };
+    if bg_colour.0[3] < 128 {
+        // alpha says nothing about how bright the walls are
+        sum(&wall_colour.0[..3]) <= 382
+    } else {
+        sum(&bg_colour.0) > 382
+    }
+}
+
 /// if the supplied player icon is unusable/not given
-pub fn fallback_image(name: &str, bg_colour: Pxl) -> Image<Pxl> {
-    // summing 4 RGBA u8 values will most likely overflow
-    let bg_sum: u16 = bg_colour.0.iter().map(|n_u8| u16::from(*n_u8)).sum();
-    let path = if bg_sum > 382 { "black" } else { "white" };
-    let fallback_colour = if bg_sum > 382 { HALF_BLACK } else { HALF_WHITE };
+///
+/// icons in the asset directory (if one's set) take priority over the embedded ones
+pub fn fallback_image(name: &str, bg_colour: Pxl, wall_colour: Pxl) -> PyResult<Image<Pxl>> {
+    let dark = wants_dark_icons(bg_colour, wall_colour);
+    let path = if dark { "black" } else { "white" };
+    let fallback_colour = if dark { HALF_BLACK } else { HALF_WHITE };
+
+    let key = format!("{name}-{path}");
+    let settings = ASSET_SETTINGS.read().unwrap_or_else(|e| e.into_inner());
+    if let Some(dir) = &settings.dir {
+        let file = dir.join(format!("{key}.png"));
+        match image::open(&file) {
+            Ok(img) => return Ok(img.into_rgba8()),
+            Err(e) if settings.strict => {
+                let msg = format!("{name} icon: could not load {}: {e}", file.display());
+                return Err(PyIOError::new_err(msg));
+            }
+            Err(_) => {}
+        }
+    }
+
+    let embedded = load_fallback_icons()
+        .iter()
+        .find(|(icon_name, _)| *icon_name == key)
+        .and_then(|(_, img)| img.clone());
+
+    match embedded {
+        Some(img) => Ok(img),
+        None if settings.strict => {
+            let msg = format!("{name} icon: the embedded {key:?} icon is missing or broken");
+            Err(PyIOError::new_err(msg))
+        }
+        None => Ok(RgbaImage::from_pixel(ICON_SIZE, ICON_SIZE, fallback_colour)),
+    }
+}
+
+/// maps a resampling filter name from Python onto `image`'s filter types
+pub fn resize_filter_from_name(name: &str) -> Option<imageops::FilterType> {
+    match name.to_ascii_lowercase().as_str() {
+        "nearest" => Some(imageops::FilterType::Nearest),
+        "triangle" | "bilinear" => Some(imageops::FilterType::Triangle),
+        "catmullrom" | "bicubic" => Some(imageops::FilterType::CatmullRom),
+        "gaussian" => Some(imageops::FilterType::Gaussian),
+        "lanczos3" => Some(imageops::FilterType::Lanczos3),
+        _ => None,
+    }
+}
+
+/// scales an icon to fit inside a cell, keeping its aspect ratio
+///
+/// icons that aren't square are centred on a transparent background, and ones that
+/// are already the right size are passed through untouched
+pub fn fit_icon(icon: Image<Pxl>, filter: imageops::FilterType) -> Image<Pxl> {
+    let (w, h) = icon.dimensions();
+    if (w, h) == (ICON_SIZE, ICON_SIZE) {
+        return icon;
+    }
+
+    let scale = f64::from(ICON_SIZE) / f64::from(w.max(h));
+    let new_w = ((f64::from(w) * scale).round() as u32).clamp(1, ICON_SIZE);
+    let new_h = ((f64::from(h) * scale).round() as u32).clamp(1, ICON_SIZE);
+    let resized = imageops::resize(&icon, new_w, new_h, filter);
+
+    let mut fitted = RgbaImage::new(ICON_SIZE, ICON_SIZE);
+    let (x, y) = ((ICON_SIZE - new_w) / 2, (ICON_SIZE - new_h) / 2);
+    imageops::replace(&mut fitted, &resized, x.into(), y.into());
+    fitted
+}
+
+/// draws something at `factor` times the size and scales it back down, smoothing out its jagged edges
+///
+/// `draw` gets the bigger canvas along with `factor`, the canvas starts out as `colour` but fully
+/// transparent so that the smoothed edges don't get darkened by blending into transparent black
+pub fn supersampled(
+    (w, h): (u32, u32),
+    factor: u32,
+    colour: Pxl,
+    draw: impl FnOnce(&mut Image<Pxl>, i32),
+) -> Image<Pxl> {
+    let mut clear = colour;
+    clear.0[3] = 0;
+
+    let mut canvas = RgbaImage::from_pixel(w * factor, h * factor, clear);
+    draw(&mut canvas, factor as i32);
+    if factor == 1 {
+        return canvas;
+    }
+
+    imageops::resize(&canvas, w, h, imageops::FilterType::Triangle)
+}
+
+/// a marker icon that's just a dot of a colour in the middle of the cell, supersampled by `supersample`
+pub fn marker_dot(colour: Pxl, supersample: u32) -> Image<Pxl> {
+    let size = (ICON_SIZE, ICON_SIZE);
+    supersampled(size, supersample, colour, |img, factor| {
+        // scaled along with everything else, so the dot stays centred at any factor
+        let centre = (ICON_SIZE as i32 * factor) / 2;
+        draw_filled_circle_mut(img, (centre, centre), 9 * factor, colour);
+    })
+}
+
+/// the ring drawn around both ends of a portal, supersampled by `supersample`
+pub fn portal_ring(colour: Pxl, supersample: u32) -> Image<Pxl> {
+    let size = (ICON_SIZE, ICON_SIZE);
+    let mut hole = colour;
+    hole.0[3] = 0;
+
+    supersampled(size, supersample, colour, |img, factor| {
+        let centre = (ICON_SIZE as i32 * factor) / 2;
+        draw_filled_circle_mut(img, (centre, centre), 15 * factor, colour);
+        draw_filled_circle_mut(img, (centre, centre), 11 * factor, hole);
+    })
+}
+
+/// the plate drawn on a switch, a hollow square supersampled by `supersample`
+pub fn switch_plate(colour: Pxl, supersample: u32) -> Image<Pxl> {
+    let size = (ICON_SIZE, ICON_SIZE);
+    let mut hole = colour;
+    hole.0[3] = 0;
+
+    supersampled(size, supersample, colour, |img, factor| {
+        let (outer, inner) = (11 * factor, 7 * factor);
+        let centre = (ICON_SIZE as i32 * factor) / 2;
+        let square = |half: i32| {
+            Rect::at(centre - half, centre - half).of_size(2 * half as u32, 2 * half as u32)
+        };
+
+        draw_filled_rect_mut(img, square(outer), colour);
+        draw_filled_rect_mut(img, square(inner), hole);
+    })
+}
+
+/// the flag drawn on a checkpoint, a pole with a triangle flying off of it, supersampled by `supersample`
+pub fn checkpoint_flag(colour: Pxl, supersample: u32) -> Image<Pxl> {
+    let size = (ICON_SIZE, ICON_SIZE);
+    supersampled(size, supersample, colour, |img, factor| {
+        let centre = (ICON_SIZE as i32 * factor) / 2;
+        let (pole_x, top) = (centre - 8 * factor, centre - 12 * factor);
+        let pole = Rect::at(pole_x, top).of_size(3 * factor as u32, 24 * factor as u32);
+        draw_filled_rect_mut(img, pole, colour);
+
+        let flag = [
+            Vertex::new(pole_x + 3 * factor, top),
+            Vertex::new(centre + 10 * factor, top + 6 * factor),
+            Vertex::new(pole_x + 3 * factor, top + 12 * factor),
+        ];
+        draw_polygon_mut(img, &flag, colour);
+    })
+}
 
-    match image::open(format!("assets/{name}-{path}.png")) {
-        Ok(img) => img.into_rgba8(),
-        Err(_) => RgbaImage::from_pixel(37, 37, fallback_colour),
+/// which of the four player sprites (facing up, right, down, and left) to use for a direction
+pub const fn sprite_index(direction: (i32, i32)) -> Option<usize> {
+    match direction {
+        (0, -1) => Some(0),
+        (1, 0) => Some(1),
+        (0, 1) => Some(2),
+        (-1, 0) => Some(3),
+        _ => None,
     }
 }
 
+/// the four player sprites from a single icon facing up, turning it a quarter clockwise each time
+pub fn rotated_sprites(icon: &Image<Pxl>) -> [Image<Pxl>; 4] {
+    [
+        icon.clone(),
+        imageops::rotate90(icon),
+        imageops::rotate180(icon),
+        imageops::rotate270(icon),
+    ]
+}
+
+/// cuts a spritesheet into its four equally wide frames, facing up, right, down, and left (in that order)
+pub fn split_spritesheet(sheet: &Image<Pxl>) -> [Image<Pxl>; 4] {
+    let (frame_w, h) = (sheet.width() / 4, sheet.height());
+    std::array::from_fn(|i| imageops::crop_imm(sheet, frame_w * i as u32, 0, frame_w, h).to_image())
+}
+
 /// takes a `bytes` object from Python, and converts it to an `image::ImageBuffer`
+///
+/// the format is guessed from the data, so anything `image` can decode works (only the first frame of a GIF is used)
 pub fn bytes_to_image(bytes: &PyBytes, image_name: &str) -> PyResult<Image<Pxl>> {
-    match image::load_from_memory_with_format(bytes.as_bytes(), image::ImageFormat::Png) {
+    match image::load_from_memory(bytes.as_bytes()) {
+        Ok(img) => Ok(img.into_rgba8()),
+        Err(e) => Err(PyValueError::new_err(format!("{image_name} image: {e}"))),
+    }
+}
+
+/// same as `bytes_to_image`, but reads the image from a file instead
+///
+/// the format is guessed from the file's contents rather than trusting its extension
+pub fn path_to_image(path: &Path, image_name: &str) -> PyResult<Image<Pxl>> {
+    let reader = match Reader::open(path).and_then(Reader::with_guessed_format) {
+        Ok(reader) => reader,
+        Err(e) => {
+            let msg = format!("{image_name} image: could not open {}: {e}", path.display());
+            return Err(PyIOError::new_err(msg));
+        }
+    };
+
+    match reader.decode() {
         Ok(img) => Ok(img.into_rgba8()),
         Err(e) => Err(PyValueError::new_err(format!("{image_name} image: {e}"))),
     }
 }
+
+/// renders a black and white version of the maze meant for printing, ignoring its colours and icons
+///
+/// cells are `cell_size` pixels across with thin walls on the lines between them, a full border with
+/// openings at the start and the goal, and `solution` (if given) drawn as a dotted line through the cells
+pub fn print_image(
+    walls: &EdgeSet,
+    solution: Option<&[Point]>,
+    cell_size: u32,
+    width: i32,
+    height: i32,
+) -> Image<Pxl> {
+    const BLACK: Pxl = Rgba([0, 0, 0, 255]);
+    const WHITE: Pxl = Rgba([255, 255, 255, 255]);
+
+    let c = cell_size as i32;
+    let thickness = (c / 20).max(1);
+    let margin = c / 2;
+    let (w, h) = (width * c + 2 * margin, height * c + 2 * margin);
+    let mut img = RgbaImage::from_pixel(w as u32, h as u32, WHITE);
+
+    // a line along the grid from one corner of a cell to the next, overlapping at the corners
+    let grid_line = |x: i32, y: i32, horizontal: bool| {
+        let (gx, gy) = (
+            margin + x * c - thickness / 2,
+            margin + y * c - thickness / 2,
+        );
+        let length = (c + thickness) as u32;
+        if horizontal {
+            Rect::at(gx, gy).of_size(length, thickness as u32)
+        } else {
+            Rect::at(gx, gy).of_size(thickness as u32, length)
+        }
+    };
+
+    for (node1, node2) in walls {
+        let cell = (*node1).min(*node2);
+        let rect = if node1.0 == node2.0 {
+            grid_line(cell.0, cell.1 + 1, true)
+        } else {
+            grid_line(cell.0 + 1, cell.1, false)
+        };
+
+        draw_filled_rect_mut(&mut img, rect, BLACK);
+    }
+
+    for x in 0..width {
+        // the openings are above the start and below the goal
+        if x != 0 {
+            draw_filled_rect_mut(&mut img, grid_line(x, 0, true), BLACK);
+        }
+
+        if x != width - 1 {
+            draw_filled_rect_mut(&mut img, grid_line(x, height, true), BLACK);
+        }
+    }
+
+    for y in 0..height {
+        draw_filled_rect_mut(&mut img, grid_line(0, y, false), BLACK);
+        draw_filled_rect_mut(&mut img, grid_line(width, y, false), BLACK);
+    }
+
+    if let Some(cells) = solution {
+        let (spacing, radius) = ((c / 4).max(2), (c / 16).max(1));
+        let centre = |xy: Point| (margin + xy.0 * c + c / 2, margin + xy.1 * c + c / 2);
+        for pair in cells.windows(2) {
+            let (from, to) = (centre(pair[0]), centre(pair[1]));
+            let steps = (from.0 - to.0).abs().max((from.1 - to.1).abs()) / spacing;
+            for i in 0..steps.max(1) {
+                let dot = (
+                    from.0 + (to.0 - from.0) * i / steps.max(1),
+                    from.1 + (to.1 - from.1) * i / steps.max(1),
+                );
+
+                draw_filled_circle_mut(&mut img, dot, radius, BLACK);
+            }
+        }
+
+        if let Some(&last) = cells.last() {
+            draw_filled_circle_mut(&mut img, centre(last), radius, BLACK);
+        }
+    }
+
+    img
+}
+
+/// renders the maze at evenly spaced points while it's being carved out,
+/// starting from a full grid of walls and ending with the finished maze
+///
+/// `carved` are the paths in the order they were carved out
+#[allow(clippy::too_many_arguments)] // mirrors `maze_image`, plus the carving order
+pub fn generation_frames(
+    walls: &EdgeSet,
+    carved: &EdgeVec,
+    n_frames: usize,
+    bg: &Background,
+    fill: &WallFill,
+    style: DrawStyle,
+    end_icon: &Image<Pxl>,
+    width: i32,
+    height: i32,
+) -> Vec<Image<Pxl>> {
+    let last = n_frames.saturating_sub(1).max(1);
+    (0..n_frames)
+        .map(|i| {
+            let n_carved = carved.len() * i / last;
+            let mut frame_walls = walls.clone();
+            frame_walls.extend(carved[n_carved..].iter().copied());
+
+            maze_image(&frame_walls, bg, fill, style, end_icon, width, height)
+        })
+        .collect()
+}
+
+/// progressively draws the solution onto the base image, `per_frame` segments at a time
+///
+/// the solution is expected to go from the start of the maze to the end
+pub fn solution_frames(
+    base: Image<Pxl>,
+    solution: &EdgeVec,
+    paint: PathPaint,
+    per_frame: usize,
+) -> Vec<Image<Pxl>> {
+    let mut frames = vec![base.clone()];
+    let mut current = base;
+    for (i, segments) in solution.chunks(per_frame).enumerate() {
+        let first = i * per_frame;
+        current = solution_segments_image(current, segments, first, solution.len(), paint);
+        frames.push(current.clone());
+    }
+
+    frames
+}
+
+/// one copy of the base image per step, each with the icon for that step drawn on top of its cell
+pub fn replay_frames(base: &Image<Pxl>, steps: &[(Point, &Image<Pxl>)]) -> Vec<Image<Pxl>> {
+    steps
+        .par_iter()
+        .map(|(xy, player_icon)| {
+            let mut frame = base.clone();
+            let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
+            imageops::overlay(&mut frame, *player_icon, x, y);
+            frame
+        })
+        .collect()
+}
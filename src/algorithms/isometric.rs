@@ -0,0 +1,124 @@
+use super::char_grid::{block_grid, block_of, Block};
+use super::image_gen::lerp_colour;
+use crate::types::{EdgeSet, Point, Pxl};
+
+use image::imageops;
+use imageproc::{definitions::Image, drawing::draw_polygon_mut, point::Point as Vertex};
+
+/// how many pixels across the screen one cell's worth of the grid goes, diamonds come out twice this wide
+const UNIT: f32 = 20.0;
+/// how tall walls are extruded, in pixels
+const WALL_HEIGHT: f32 = 16.0;
+/// how thick walls are, as a fraction of a cell
+const WALL_THICKNESS: f32 = 0.15;
+/// how big the icons standing on the floor are
+const ICON_SIZE: u32 = 26;
+const PADDING: f32 = 4.0;
+
+/// where a row or column of blocks starts and ends on the floor, in cells
+fn block_span(i: usize) -> (f32, f32) {
+    let start = (i / 2) as f32 * (1.0 + WALL_THICKNESS) + (i % 2) as f32 * WALL_THICKNESS;
+    let size = if i % 2 == 1 { 1.0 } else { WALL_THICKNESS };
+    (start, start + size)
+}
+
+/// projects floor coordinates (in cells) to the screen, `lift` pixels off the ground
+struct Screen {
+    origin: (f32, f32),
+}
+
+impl Screen {
+    fn point(&self, (x, y): (f32, f32), lift: f32) -> Vertex<i32> {
+        Vertex::new(
+            (self.origin.0 + (x - y) * UNIT).round() as i32,
+            (self.origin.1 + (x + y) * UNIT / 2.0 - lift).round() as i32,
+        )
+    }
+}
+
+/// renders the maze as a 2.5D isometric view, with walls extruded into blocks and `icons` standing on the floor
+/// of their cells, for exporting in a different style than the usual top-down view
+///
+/// the floor is `bg_colour` and the walls are `wall_colour`, with their sides shaded part of the way to the floor's colour;
+/// everything outside the floor is transparent
+pub fn isometric_image(
+    walls: &EdgeSet,
+    bg_colour: Pxl,
+    wall_colour: Pxl,
+    icons: &[(Point, &Image<Pxl>)],
+    width: i32,
+    height: i32,
+) -> Image<Pxl> {
+    let blocks = block_grid(walls, None, width, height);
+    let floor_w = block_span(width as usize * 2).1;
+    let floor_h = block_span(height as usize * 2).1;
+    let top_room = WALL_HEIGHT.max(ICON_SIZE as f32) + PADDING;
+
+    let img_w = ((floor_w + floor_h) * UNIT + PADDING * 2.0).ceil() as u32;
+    let img_h = ((floor_w + floor_h) * UNIT / 2.0 + top_room + PADDING).ceil() as u32;
+    let mut img = Image::new(img_w, img_h);
+    let proj = Screen {
+        origin: (floor_h * UNIT + PADDING, top_room),
+    };
+
+    let floor = [
+        (0.0, 0.0),
+        (floor_w, 0.0),
+        (floor_w, floor_h),
+        (0.0, floor_h),
+    ];
+    draw_polygon_mut(&mut img, &floor.map(|p| proj.point(p, 0.0)), bg_colour);
+
+    let icons: Vec<_> = icons
+        .iter()
+        .map(|(xy, icon)| {
+            let icon =
+                imageops::resize(*icon, ICON_SIZE, ICON_SIZE, imageops::FilterType::Triangle);
+            (block_of(*xy), icon)
+        })
+        .collect();
+
+    // the sides are shaded towards the floor rather than darkened, so that black walls still look solid
+    let side = lerp_colour(wall_colour, bg_colour, 0.25);
+    let front = lerp_colour(wall_colour, bg_colour, 0.45);
+    let mut order: Vec<_> = (0..blocks.len())
+        .flat_map(|by| (0..blocks[by].len()).map(move |bx| (bx, by)))
+        .collect();
+
+    // back to front, so that whatever's nearer the bottom of the screen is drawn over what's behind it
+    order.sort_by_key(|(bx, by)| bx + by);
+    for (bx, by) in order {
+        let ((x0, x1), (y0, y1)) = (block_span(bx), block_span(by));
+
+        if blocks[by][bx] == Block::Wall {
+            let face = |corners: [(f32, f32); 2]| {
+                let [a, b] = corners;
+                [
+                    proj.point(a, 0.0),
+                    proj.point(b, 0.0),
+                    proj.point(b, WALL_HEIGHT),
+                    proj.point(a, WALL_HEIGHT),
+                ]
+            };
+
+            draw_polygon_mut(&mut img, &face([(x1, y0), (x1, y1)]), side);
+            draw_polygon_mut(&mut img, &face([(x0, y1), (x1, y1)]), front);
+
+            let top = [(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+            draw_polygon_mut(
+                &mut img,
+                &top.map(|p| proj.point(p, WALL_HEIGHT)),
+                wall_colour,
+            );
+        }
+
+        for (_, icon) in icons.iter().filter(|(at, _)| *at == (bx, by)) {
+            let feet = proj.point(((x0 + x1) / 2.0, (y0 + y1) / 2.0), 0.0);
+            let x = i64::from(feet.x) - i64::from(ICON_SIZE / 2);
+            let y = i64::from(feet.y) - i64::from(ICON_SIZE) + 4;
+            imageops::overlay(&mut img, icon, x, y);
+        }
+    }
+
+    img
+}
@@ -1,5 +1,5 @@
-use crate::types::{EdgeSet, Point};
-use crate::util::partial_neighbours;
+use crate::types::{EdgeSet, EdgeVec, Point};
+use crate::util::{partial_neighbours, wall_between};
 
 use std::{
     collections::{HashMap, HashSet},
@@ -86,8 +86,8 @@ impl<T: Debug + Hash + Eq + Copy> Graph<T> {
 
 /// generates an MST with `width * height` nodes, using Kruskal's Algorithm
 ///
-/// returns a tuple `(walls, paths)` of the maze
-pub fn generate_edges(width: i32, height: i32) -> (EdgeSet, EdgeSet) {
+/// returns a tuple `(walls, paths)` of the maze, with the paths in the order they were carved out
+pub fn generate_edges(width: i32, height: i32) -> (EdgeSet, EdgeVec) {
     // flattened collection of every xy coordinate in the maze
     let nodes: Vec<Point> = (0..width)
         .flat_map(|x| (0..height).map(move |y| (x, y)))
@@ -105,20 +105,58 @@ pub fn generate_edges(width: i32, height: i32) -> (EdgeSet, EdgeSet) {
 
     let mut graph: Graph<Point> = Graph::new(nodes);
 
-    // let mut paths = HashSet::with_capacity(edges.len() / 2);
+    let mut paths = Vec::with_capacity(edges.len() / 2);
     let mut walls = HashSet::with_capacity(edges.len() / 2);
     for edge in edges.iter().copied() {
         let no_loop = graph.union_subtrees(edge.0, edge.1);
-        // if no_loop {
-        //     paths.insert(edge);
-        // } else {
-        //     walls.insert(edge);
-        // }
-        if !no_loop {
+        if no_loop {
+            paths.push(edge);
+        } else {
             walls.insert(edge);
         }
     }
 
-    // (walls, paths)
-    (walls, HashSet::new())
+    (walls, paths)
+}
+
+/// knocks down just enough walls to join every part of the maze that's been cut off from the rest,
+/// like Kruskal's would, so a maze that was a tree before being split up is one again
+pub fn connect_regions(walls: &mut EdgeSet, width: i32, height: i32) {
+    let nodes = (0..width).flat_map(|x| (0..height).map(move |y| (x, y)));
+    let mut graph: Graph<Point> = Graph::new(nodes.clone());
+    for node in nodes {
+        for nbour in partial_neighbours(node, width, height) {
+            if !wall_between(walls, node, nbour) {
+                graph.union_subtrees(node, nbour);
+            }
+        }
+    }
+
+    let bridges: Vec<_> = walls
+        .iter()
+        .copied()
+        .filter(|edge| graph.union_subtrees(edge.0, edge.1))
+        .collect();
+
+    for edge in bridges {
+        walls.remove(&edge);
+    }
+}
+
+/// the passages that close off a loop, walling all of them up would leave the maze without any loops
+///
+/// which passage of a loop gets the blame is arbitrary, it's whichever one is looked at last
+pub fn loop_passages(walls: &EdgeSet, width: i32, height: i32) -> EdgeVec {
+    let nodes = (0..width).flat_map(|x| (0..height).map(move |y| (x, y)));
+    let mut graph: Graph<Point> = Graph::new(nodes.clone());
+    let mut loops = vec![];
+    for node in nodes {
+        for nbour in partial_neighbours(node, width, height) {
+            if !wall_between(walls, node, nbour) && !graph.union_subtrees(node, nbour) {
+                loops.push((node, nbour));
+            }
+        }
+    }
+
+    loops
 }
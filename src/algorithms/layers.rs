@@ -0,0 +1,175 @@
+use crate::types::Pxl;
+
+use image::{Pixel, Rgba, RgbaImage};
+use imageproc::{definitions::Image, drawing::draw_filled_rect_mut, rect::Rect};
+
+use rayon::prelude::*;
+
+/// the layers drawn over the base maze image, from bottom to top
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Terrain,
+    Solution,
+    Trail,
+    Markers,
+    Text,
+    Fog,
+    Player,
+}
+
+impl Layer {
+    const COUNT: usize = 7;
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+const TRANSPARENT: Pxl = Rgba([0, 0, 0, 0]);
+
+/// the maze image split up into a base render (walls and endzone), with transparent layers on top of it
+///
+/// each layer can be drawn on or wiped by itself, and then composited into the final image,
+/// either all at once or just the region that changed
+#[derive(Clone)]
+pub struct Layers {
+    base: Image<Pxl>,
+    overlays: [Image<Pxl>; Layer::COUNT],
+}
+
+impl Layers {
+    /// starts off with every layer empty
+    pub fn new(base: Image<Pxl>) -> Self {
+        let (w, h) = base.dimensions();
+        Self {
+            base,
+            overlays: std::array::from_fn(|_| RgbaImage::from_pixel(w, h, TRANSPARENT)),
+        }
+    }
+
+    /// swaps out the base render, keeping everything drawn on the other layers
+    pub fn set_base(&mut self, base: Image<Pxl>) {
+        self.base = base;
+    }
+
+    pub fn base_mut(&mut self) -> &mut Image<Pxl> {
+        &mut self.base
+    }
+
+    pub fn layer_mut(&mut self, layer: Layer) -> &mut Image<Pxl> {
+        &mut self.overlays[layer.index()]
+    }
+
+    /// takes a layer out to be drawn on by value, leaving an empty image in its place until it's put back
+    pub fn take(&mut self, layer: Layer) -> Image<Pxl> {
+        std::mem::take(&mut self.overlays[layer.index()])
+    }
+
+    pub fn put_back(&mut self, layer: Layer, img: Image<Pxl>) {
+        self.overlays[layer.index()] = img;
+    }
+
+    /// wipes a whole layer back to being transparent
+    pub fn clear(&mut self, layer: Layer) {
+        self.fill(layer, TRANSPARENT);
+    }
+
+    /// paints a whole layer a single colour
+    pub fn fill(&mut self, layer: Layer, colour: Pxl) {
+        let overlay = self.layer_mut(layer);
+        overlay.pixels_mut().for_each(|px| *px = colour);
+    }
+
+    /// wipes a part of a layer back to being transparent
+    pub fn clear_rect(&mut self, layer: Layer, rect: Rect) {
+        draw_filled_rect_mut(self.layer_mut(layer), rect, TRANSPARENT);
+    }
+
+    /// flattens every layer into `out`, which has to be the same size as the base
+    ///
+    /// `out` is written to in place, and only inside of `region` if one is given
+    pub fn composite_into(&self, out: &mut Image<Pxl>, region: Option<Rect>) {
+        self.blend_into(out, region, None);
+    }
+
+    /// flattens every layer except for `skip` into a new image
+    pub fn flatten_without(&self, skip: Layer) -> Image<Pxl> {
+        let mut out = self.base.clone();
+        self.blend_into(&mut out, None, Some(skip));
+        out
+    }
+
+    fn blend_into(&self, out: &mut Image<Pxl>, region: Option<Rect>, skip: Option<Layer>) {
+        let (w, h) = self.base.dimensions();
+        let (x0, y0, x1, y1) = match region {
+            None => (0, 0, w, h),
+            Some(rect) => (
+                rect.left().clamp(0, w as i32) as u32,
+                rect.top().clamp(0, h as i32) as u32,
+                (rect.right() + 1).clamp(0, w as i32) as u32,
+                (rect.bottom() + 1).clamp(0, h as i32) as u32,
+            ),
+        };
+
+        let overlays: Vec<&Image<Pxl>> = self
+            .overlays
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| skip.map(Layer::index) != Some(*i))
+            .map(|(_, overlay)| overlay)
+            .collect();
+
+        let row_len = w as usize * 4;
+        out.par_chunks_mut(row_len)
+            .enumerate()
+            .skip(y0 as usize)
+            .take(y1.saturating_sub(y0) as usize)
+            .for_each(|(y, row)| {
+                let y = y as u32;
+                for x in x0..x1 {
+                    let mut px = *self.base.get_pixel(x, y);
+                    for overlay in &overlays {
+                        let top = overlay.get_pixel(x, y);
+                        if top.0[3] != 0 {
+                            px.blend(top);
+                        }
+                    }
+
+                    let i = x as usize * 4;
+                    row[i..i + 4].copy_from_slice(&px.0);
+                }
+            });
+    }
+}
+
+/// the layers along with the image they're flattened into
+#[derive(Clone)]
+pub struct Canvas {
+    /// every layer composited together, which is what gets encoded and exposed to Python
+    ///
+    /// Python can hold views into this through the buffer protocol,
+    /// so draw onto it in place rather than replacing it whenever possible
+    pub image: Image<Pxl>,
+    /// what actually gets drawn on, `image` is re-composited from these after every change
+    pub layers: Layers,
+}
+
+impl Canvas {
+    pub fn new(base: Image<Pxl>) -> Self {
+        Self {
+            layers: Layers::new(base.clone()),
+            image: base,
+        }
+    }
+
+    /// starts over from a new base with every layer empty, `image` stays where it is so views into it
+    /// keep working, but it isn't updated until the next composite
+    pub fn reset(&mut self, base: Image<Pxl>) {
+        self.layers = Layers::new(base);
+    }
+
+    /// flattens the layers into `image` (in place, see the note on it), only inside of `region` if it's given
+    pub fn composite(&mut self, region: Option<Rect>) {
+        self.layers.composite_into(&mut self.image, region);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::types::{EdgeSet, Move, Point, Portal, Switch, Terrain};
+use crate::util::{ordered_edge, out_of_bounds, wall_between};
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// which switches have been pressed an odd amount of times, one bit per switch in the order they were added
+pub type SwitchMask = u64;
+
+/// the most switches a maze can have
+///
+/// the solver goes through every cell once for each combination of switches pressed, which doubles with every switch,
+/// so this is kept small enough that solving (and anything else that searches the maze) stays quick
+pub const MAX_SWITCHES: usize = 8;
+
+/// the cells that change how moving around works (portals, switches, and terrain), set up to be looked up quickly
+///
+/// walls that switches toggle are looked up relative to the maze's current walls, with a `SwitchMask`
+/// saying which switches have been pressed since then, so that the solver can try out pressing them
+/// without copying the walls for every combination
+#[derive(Debug, Default)]
+pub struct Mechanics {
+    /// where stepping onto each portal cell takes the player, both ways around
+    portals: HashMap<Point, Point>,
+    /// the bit of each switch in a `SwitchMask`
+    switches: HashMap<Point, SwitchMask>,
+    /// every wall that's toggled by a switch, and the bits of all of the switches that toggle it
+    toggles: HashMap<(Point, Point), SwitchMask>,
+    /// every cell that isn't normal ground
+    terrain: HashMap<Point, Terrain>,
+}
+
+/// where a single move took the player
+#[derive(Clone, Debug)]
+pub struct Travel {
+    /// every cell the player stepped onto in order, with the other end of a portal right after the portal
+    pub visited: Vec<Point>,
+    /// the last cell the player stepped onto, which is only different from `position` if it was a portal
+    pub entry: Point,
+    /// where the player ended up
+    pub position: Point,
+    /// which switches are pressed afterwards
+    pub pressed: SwitchMask,
+    /// how many moves it counts as on top of the move itself, one for every mud cell stepped onto
+    pub extra_cost: i32,
+    /// whether the player was sliding by the end, either from a max move or from stepping onto ice
+    pub slid: bool,
+    /// whether a wall got in the way before a regular move stepped as far as it was meant to
+    pub collided: bool,
+}
+
+impl Mechanics {
+    pub fn new(portals: &[Portal], switches: &[Switch], terrain: &HashMap<Point, Terrain>) -> Self {
+        let mut mechanics = Self {
+            portals: portals
+                .iter()
+                .flat_map(|p| [p.ends, (p.ends.1, p.ends.0)])
+                .collect(),
+            terrain: terrain.clone(),
+            ..Self::default()
+        };
+
+        for (i, switch) in switches.iter().enumerate() {
+            let bit = 1 << i;
+            mechanics.switches.insert(switch.xy, bit);
+            for edge in &switch.walls {
+                *mechanics.toggles.entry(ordered_edge(*edge)).or_default() ^= bit;
+            }
+        }
+
+        mechanics
+    }
+
+    /// whether there's nothing that changes how moving works, so plain old walls are all there is
+    pub fn is_empty(&self) -> bool {
+        self.portals.is_empty() && self.switches.is_empty() && self.terrain.is_empty()
+    }
+
+    pub fn terrain_at(&self, xy: Point) -> Terrain {
+        self.terrain.get(&xy).copied().unwrap_or_default()
+    }
+
+    /// whether `xy` is either end of a portal or a switch
+    pub fn occupied(&self, xy: Point) -> bool {
+        self.portals.contains_key(&xy) || self.switches.contains_key(&xy)
+    }
+
+    /// whether there's a wall between two neighbouring cells once the switches in `pressed` have been pressed
+    pub fn wall_between(&self, walls: &EdgeSet, a: Point, b: Point, pressed: SwitchMask) -> bool {
+        let flips = self
+            .toggles
+            .get(&ordered_edge((a, b)))
+            .map_or(0, |bits| (bits & pressed).count_ones());
+
+        wall_between(walls, a, b) ^ (flips % 2 == 1)
+    }
+
+    /// whether a single step from `from` by `(dx, dy)` stays inside the maze without going through a wall
+    pub fn can_step(
+        &self,
+        walls: &EdgeSet,
+        from: Point,
+        (dx, dy): (i32, i32),
+        pressed: SwitchMask,
+        width: i32,
+        height: i32,
+    ) -> bool {
+        let to = (from.0 + dx, from.1 + dy);
+        !out_of_bounds(from, width, height)
+            && !out_of_bounds(to, width, height)
+            && !self.wall_between(walls, from, to, pressed)
+    }
+
+    /// where stepping onto `xy` puts the player (the other end if it's a portal),
+    /// and which switches are pressed afterwards (flipping the one at `xy`, if there is one)
+    pub fn land(&self, xy: Point, pressed: SwitchMask) -> (Point, SwitchMask) {
+        let landed = self.portals.get(&xy).copied().unwrap_or(xy);
+        let bit = self.switches.get(&landed).copied().unwrap_or(0);
+        (landed, pressed ^ bit)
+    }
+
+    /// makes a single move from `from`, stepping `amount` cells or sliding until a wall is in the way for a max move
+    ///
+    /// stepping onto a portal puts the player on its other end, and stepping onto a switch presses it;
+    /// both of those stop a slide, and so does passing over `stop` (unless that's where it started)
+    ///
+    /// stepping onto ice sends the player sliding the rest of the way, the same as a max move,
+    /// and walking into mud costs an extra move
+    #[allow(clippy::too_many_arguments)] // the same ones as `can_step`, plus the move and where to stop
+    pub fn travel(
+        &self,
+        walls: &EdgeSet,
+        from: Point,
+        m: Move,
+        pressed: SwitchMask,
+        stop: Option<Point>,
+        width: i32,
+        height: i32,
+    ) -> Travel {
+        let mut travel = Travel {
+            visited: vec![],
+            entry: from,
+            position: from,
+            pressed,
+            extra_cost: 0,
+            slid: m.is_max,
+            collided: false,
+        };
+
+        let mut remaining = m.amount;
+        while travel.slid || remaining > 0 {
+            let current = travel.position;
+            if !self.can_step(walls, current, m.direction, travel.pressed, width, height) {
+                travel.collided = !travel.slid;
+                break;
+            }
+
+            let n = (current.0 + m.direction.0, current.1 + m.direction.1);
+            let (landed, now_pressed) = self.land(n, travel.pressed);
+            travel.visited.push(n);
+            if landed != n {
+                travel.visited.push(landed);
+            }
+
+            if self.terrain_at(n) == Terrain::Mud {
+                travel.extra_cost += 1;
+            }
+
+            let interrupted = landed != n || now_pressed != travel.pressed;
+            (travel.entry, travel.position, travel.pressed) = (n, landed, now_pressed);
+            remaining -= 1;
+            if travel.slid && (interrupted || Some(n) == stop) {
+                break;
+            }
+
+            if !interrupted && self.terrain_at(landed) == Terrain::Ice {
+                travel.slid = true;
+            }
+        }
+
+        travel
+    }
+
+    /// every cell the player can pass through from `start`, trying out every order of pressing the switches
+    pub fn reachable(
+        &self,
+        walls: &EdgeSet,
+        start: Point,
+        width: i32,
+        height: i32,
+    ) -> HashSet<Point> {
+        const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+        let mut seen = HashSet::from([(start, 0)]);
+        let mut cells = HashSet::from([start]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((xy, pressed)) = queue.pop_front() {
+            // a max move passes through the same cells as single steps in a row would, so those are all that's tried
+            for d in DIRECTIONS {
+                let m = Move::new(d, 1, false);
+                let travel = self.travel(walls, xy, m, pressed, None, width, height);
+                cells.extend(&travel.visited);
+
+                let next = (travel.position, travel.pressed);
+                if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        cells
+    }
+}
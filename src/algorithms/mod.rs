@@ -1,7 +1,31 @@
 mod a_star;
+mod char_grid;
+mod describe;
+mod encode;
 mod image_gen;
+mod isometric;
 mod kruskal;
+mod layers;
+mod mechanics;
+mod raycast;
+mod simulate;
+mod stitch;
+mod svg;
+mod text;
+mod transform;
 
 pub use a_star::*;
+pub use char_grid::*;
+pub use describe::*;
+pub use encode::*;
 pub use image_gen::*;
+pub use isometric::*;
 pub use kruskal::*;
+pub use layers::*;
+pub use mechanics::*;
+pub use raycast::*;
+pub use simulate::*;
+pub use stitch::*;
+pub use svg::*;
+pub use text::*;
+pub use transform::*;
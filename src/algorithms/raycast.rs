@@ -0,0 +1,137 @@
+use super::image_gen::lerp_colour;
+use crate::types::{EdgeSet, Point, Pxl};
+use crate::util::{out_of_bounds, wall_between};
+
+use image::Rgba;
+use imageproc::definitions::Image;
+
+/// where the first-person view is looked at from
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub xy: Point,
+    /// one of up, down, left, or right
+    pub facing: (i32, i32),
+    /// the horizontal field of view, in degrees
+    pub fov: f32,
+}
+
+/// the colours the first-person view is painted with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ViewColours {
+    /// the ceiling, and what walls fade into with distance
+    pub bg: Pxl,
+    pub wall: Pxl,
+    /// the walls around the goal, so it can be spotted from afar
+    pub goal: Pxl,
+}
+
+/// how many cells away a wall has to be to fade out as far as it goes
+const FADE_DISTANCE: f32 = 8.0;
+
+/// what a single ray ran into
+struct Hit {
+    distance: f32,
+    /// the cell the ray was in when it hit the wall
+    cell: Point,
+    /// whether the wall runs across (facing up or down), these get shaded a little darker
+    across: bool,
+}
+
+/// casts a ray from the middle of `from` until it runs into a wall or the edge of the maze,
+/// stepping from cell to cell along the grid (a digital differential analyser)
+fn cast(walls: &EdgeSet, from: Point, ray: (f32, f32), width: i32, height: i32) -> Hit {
+    let step = (ray.0.signum() as i32, ray.1.signum() as i32);
+    // how far along the ray it takes to cross a whole cell in each direction
+    let delta = (1.0 / ray.0.abs(), 1.0 / ray.1.abs());
+    // starting from the middle of the cell, the first boundary is always half a cell away
+    let mut next = (delta.0 / 2.0, delta.1 / 2.0);
+    let mut cell = from;
+
+    loop {
+        let across = next.1 < next.0;
+        let (distance, neighbour) = if across {
+            next.1 += delta.1;
+            (next.1 - delta.1, (cell.0, cell.1 + step.1))
+        } else {
+            next.0 += delta.0;
+            (next.0 - delta.0, (cell.0 + step.0, cell.1))
+        };
+
+        if out_of_bounds(neighbour, width, height) || wall_between(walls, cell, neighbour) {
+            return Hit {
+                distance,
+                cell,
+                across,
+            };
+        }
+
+        cell = neighbour;
+    }
+}
+
+/// renders a pseudo-3D view of the corridors around `camera`, like an old-school first-person shooter
+///
+/// every wall is a full cell tall, with walls further away fading into the background colour,
+/// and the floor is a mix of the background and wall colours
+pub fn first_person_view(
+    walls: &EdgeSet,
+    camera: Camera,
+    colours: ViewColours,
+    goal: Point,
+    (img_w, img_h): (u32, u32),
+    width: i32,
+    height: i32,
+) -> Image<Pxl> {
+    let floor = lerp_colour(colours.bg, colours.wall, 0.2);
+    let mut img = Image::from_fn(
+        img_w,
+        img_h,
+        |_, y| {
+            if y < img_h / 2 {
+                colours.bg
+            } else {
+                floor
+            }
+        },
+    );
+
+    let dir = (camera.facing.0 as f32, camera.facing.1 as f32);
+    // the camera plane is perpendicular to the direction, and as wide as the field of view
+    let half_width = (camera.fov.to_radians() / 2.0).tan();
+    let plane = (-dir.1 * half_width, dir.0 * half_width);
+
+    for x in 0..img_w {
+        let offset = 2.0 * (x as f32 + 0.5) / img_w as f32 - 1.0;
+        let ray = (dir.0 + plane.0 * offset, dir.1 + plane.1 * offset);
+        let hit = cast(walls, camera.xy, ray, width, height);
+
+        // the distance is measured straight out from the camera plane rather than along the ray,
+        // which keeps walls flat instead of bulging out in a fisheye
+        let distance = hit.distance.max(0.01);
+        let wall_height = (img_h as f32 / distance).min(img_h as f32 * 4.0);
+        let top = ((img_h as f32 - wall_height) / 2.0).max(0.0) as u32;
+        let bottom = ((img_h as f32 + wall_height) / 2.0).min(img_h as f32) as u32;
+
+        let base = if hit.cell == goal {
+            colours.goal
+        } else {
+            colours.wall
+        };
+
+        let shaded = if hit.across {
+            let Rgba([r, g, b, a]) = base;
+            let darker = |c: u8| (f32::from(c) * 0.8) as u8;
+            Rgba([darker(r), darker(g), darker(b), a])
+        } else {
+            base
+        };
+
+        let fade = (distance / FADE_DISTANCE).min(1.0) * 0.8;
+        let colour = lerp_colour(shaded, colours.bg, fade);
+        for y in top..bottom {
+            img.put_pixel(x, y, colour);
+        }
+    }
+
+    img
+}
@@ -0,0 +1,57 @@
+use super::mechanics::Mechanics;
+use crate::types::{EdgeSet, Move, Point};
+
+/// what happened when playing back a sequence of moves
+pub struct Playback {
+    /// where the player ended up
+    pub position: Point,
+    /// every cell the player passed through in order, starting with where they started
+    pub visited: Vec<Point>,
+    /// the index of the move that ran into a wall (if one did), playback stops right there
+    pub collided_at: Option<usize>,
+    /// how many moves the run counts as, with max moves counting as 1 and every step into mud as 1 more
+    pub cost: i32,
+}
+
+/// plays back a sequence of moves from a starting point
+///
+/// max moves slide until there's a wall in the way, like `Maze.move_max` does,
+/// while regular moves step exactly `amount` cells and count as a collision if a wall blocks any of them
+///
+/// stepping onto a portal puts the player on its other end and stepping onto a switch toggles its walls,
+/// both of which also stop a max move; stepping onto ice turns the rest of a move into a slide
+pub fn play_moves(
+    walls: &EdgeSet,
+    mechanics: &Mechanics,
+    start: Point,
+    moves: &[Move],
+    width: i32,
+    height: i32,
+) -> Playback {
+    let mut current = start;
+    let mut visited = vec![start];
+    let mut pressed = 0;
+    let mut cost = 0;
+
+    for (idx, m) in moves.iter().enumerate() {
+        let travel = mechanics.travel(walls, current, *m, pressed, None, width, height);
+        visited.extend(&travel.visited);
+        (current, pressed) = (travel.position, travel.pressed);
+        cost += m.cost() + travel.extra_cost;
+        if travel.collided {
+            return Playback {
+                position: current,
+                visited,
+                collided_at: Some(idx),
+                cost,
+            };
+        }
+    }
+
+    Playback {
+        position: current,
+        visited,
+        collided_at: None,
+        cost,
+    }
+}
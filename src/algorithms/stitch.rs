@@ -0,0 +1,80 @@
+use crate::types::{EdgeSet, EdgeVec, Point};
+use crate::util::partial_neighbours;
+
+use std::collections::BTreeMap;
+
+/// one of the mazes being stitched together, `offset` being where its top left cell ends up
+pub struct Piece<'a> {
+    pub walls: &'a EdgeSet,
+    pub offset: Point,
+    pub size: (i32, i32),
+}
+
+/// the seams between pieces of a stitched maze, and which piece each of its cells came from
+pub struct Seams {
+    /// indexed by `y * width + x`
+    owners: Vec<usize>,
+    width: i32,
+    /// every pair of neighbouring cells on either side of a seam, grouped by the two pieces they belong to
+    pub edges: BTreeMap<(usize, usize), EdgeVec>,
+}
+
+impl Seams {
+    /// `pieces` have to cover the whole `width` by `height` grid without overlapping
+    pub fn new(pieces: &[Piece], width: i32, height: i32) -> Self {
+        let mut owners = vec![0; (width * height) as usize];
+        for (i, piece) in pieces.iter().enumerate() {
+            let ((x0, y0), (w, h)) = (piece.offset, piece.size);
+            for y in y0..y0 + h {
+                for x in x0..x0 + w {
+                    owners[(y * width + x) as usize] = i;
+                }
+            }
+        }
+
+        let mut seams = Self {
+            owners,
+            width,
+            edges: BTreeMap::new(),
+        };
+
+        for y in 0..height {
+            for x in 0..width {
+                for n in partial_neighbours((x, y), width, height) {
+                    let (a, b) = (seams.owner((x, y)), seams.owner(n));
+                    if a != b {
+                        let key = (a.min(b), a.max(b));
+                        seams.edges.entry(key).or_default().push(((x, y), n));
+                    }
+                }
+            }
+        }
+
+        seams
+    }
+
+    /// which piece a cell came from
+    pub fn owner(&self, (x, y): Point) -> usize {
+        self.owners[(y * self.width + x) as usize]
+    }
+
+    /// whether `a` and `b` are neighbours on either side of a seam
+    pub fn crosses(&self, a: Point, b: Point) -> bool {
+        (a.0 - b.0).abs() + (a.1 - b.1).abs() == 1 && self.owner(a) != self.owner(b)
+    }
+
+    /// every piece's walls moved into place, with the seams walled off everywhere but at `doorways`
+    pub fn stitch_walls(&self, pieces: &[Piece], doorways: &EdgeSet) -> EdgeSet {
+        let mut walls = EdgeSet::new();
+        for piece in pieces {
+            let (dx, dy) = piece.offset;
+            let shift = |(x, y): Point| (x + dx, y + dy);
+            walls.extend(piece.walls.iter().map(|(a, b)| (shift(*a), shift(*b))));
+        }
+
+        let open = |(a, b)| doorways.contains(&(a, b)) || doorways.contains(&(b, a));
+        walls.extend(self.edges.values().flatten().copied().filter(|e| !open(*e)));
+
+        walls
+    }
+}
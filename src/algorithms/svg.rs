@@ -0,0 +1,78 @@
+use crate::types::{EdgeSet, Point, Pxl};
+
+use std::fmt::Write;
+
+/// `#rrggbb` hex code and 0-1 opacity for an RGBA colour
+fn hex_and_opacity(colour: Pxl) -> (String, f32) {
+    let [r, g, b, a] = colour.0;
+    (format!("#{r:02x}{g:02x}{b:02x}"), f32::from(a) / 255.0)
+}
+
+/// generates an SVG document of the maze, mirroring the layout of `maze_image`
+///
+/// walls are drawn as rects and the solution (if given) as a polyline through the middle of each cell
+pub fn maze_svg(
+    walls: &EdgeSet,
+    bg_colour: Pxl,
+    wall_colour: Pxl,
+    solution: Option<(&[Point], Pxl)>,
+    width: i32,
+    height: i32,
+) -> String {
+    // subtract 1 from width and height as the coordinates are zero-indexed
+    let (w, h) = ((width - 1) * 40 + 37, (height - 1) * 40 + 37);
+    let mut svg = String::with_capacity(walls.len() * 64 + 256);
+
+    let (bg, bg_opacity) = hex_and_opacity(bg_colour);
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#
+    );
+    let _ = write!(
+        svg,
+        r#"<rect width="{w}" height="{h}" fill="{bg}" fill-opacity="{bg_opacity}"/>"#
+    );
+
+    let (wall, wall_opacity) = hex_and_opacity(wall_colour);
+    let _ = write!(svg, r#"<g fill="{wall}" fill-opacity="{wall_opacity}">"#);
+
+    // sorted so that the same maze always gives the same document
+    let mut sorted: Vec<_> = walls.iter().collect();
+    sorted.sort_unstable();
+    for (node1, node2) in sorted {
+        let (x, y) = ((node1.0 + 1) * 40, (node1.1 + 1) * 40);
+        let (rx, ry, rw, rh) = if node1.0 == node2.0 {
+            (x - 43, y - 3, 43, 3)
+        } else {
+            (x - 3, y - 43, 3, 43)
+        };
+
+        let _ = write!(
+            svg,
+            r#"<rect x="{rx}" y="{ry}" width="{rw}" height="{rh}"/>"#
+        );
+    }
+
+    svg.push_str("</g>");
+
+    if let Some((cells, colour)) = solution {
+        let (stroke, stroke_opacity) = hex_and_opacity(colour);
+        // split up wherever the path jumps through a portal, so that there's no line across the maze
+        let runs = cells.chunk_by(|a, b| (a.0 - b.0).abs() + (a.1 - b.1).abs() == 1);
+        for run in runs {
+            let points: Vec<String> = run
+                .iter()
+                .map(|(x, y)| format!("{},{}", x * 40 + 19, y * 40 + 19))
+                .collect();
+
+            let _ = write!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{stroke}" stroke-opacity="{stroke_opacity}" stroke-width="6" stroke-linecap="square" stroke-linejoin="miter"/>"#,
+                points.join(" ")
+            );
+        }
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
@@ -0,0 +1,128 @@
+use crate::types::{Corner, Point, Pxl, Stamp, Watermark};
+
+use image::imageops;
+use imageproc::{
+    definitions::Image,
+    drawing::{draw_text_mut, text_size},
+    rect::Rect,
+};
+use rusttype::{Font, Scale};
+
+use std::sync::OnceLock;
+
+/// baked into the binary for the same reason as the fallback icons
+const FONT_BYTES: &[u8] = include_bytes!("../../assets/DejaVuSansMono-Bold.ttf");
+
+static FONT: OnceLock<Font<'static>> = OnceLock::new();
+
+/// the embedded font, only parsed the first time it's needed
+pub fn font() -> &'static Font<'static> {
+    FONT.get_or_init(|| Font::try_from_bytes(FONT_BYTES).expect("embedded font should be valid"))
+}
+
+/// draws text centred on the middle of a cell, with each line of it stacked on top of each other
+///
+/// returns the area the text covers, which may spill out of the cell if the text is too big
+pub fn draw_cell_text(img: &mut Image<Pxl>, xy: Point, text: &str, colour: Pxl, size: f32) -> Rect {
+    let centre = (xy.0 * 40 + 18, xy.1 * 40 + 18);
+    draw_text_centred(img, centre, text, colour, size)
+}
+
+/// labels the columns along the top of the maze and the rows down its left side, inside the border cells
+///
+/// the labels are the coordinates as they're used everywhere else, so they start at 0
+pub fn draw_coordinate_labels(
+    img: &mut Image<Pxl>,
+    colour: Pxl,
+    size: f32,
+    width: i32,
+    height: i32,
+) {
+    let (font, scale) = (font(), Scale::uniform(size));
+    let half_line = (size / 2.0).ceil() as i32;
+
+    for x in 0..width {
+        let centre = (x * 40 + 18, half_line + 1);
+        draw_text_centred(img, centre, &x.to_string(), colour, size);
+    }
+
+    for y in 0..height {
+        let label = y.to_string();
+        let half_w = text_size(scale, font, &label).0 / 2;
+        draw_text_centred(img, (half_w + 2, y * 40 + 18), &label, colour, size);
+    }
+}
+
+/// writes each cell's index (`y * width + x`, the order cells are read in) in the middle of it
+pub fn draw_cell_indices(img: &mut Image<Pxl>, colour: Pxl, size: f32, width: i32, height: i32) {
+    for y in 0..height {
+        for x in 0..width {
+            draw_cell_text(img, (x, y), &(y * width + x).to_string(), colour, size);
+        }
+    }
+}
+
+/// how much space a (possibly multi-line) block of text takes up, at least 1x1 pixels
+pub fn text_block_size(text: &str, size: f32) -> (i32, i32) {
+    let (font, scale) = (font(), Scale::uniform(size));
+    let widest = text.lines().map(|l| text_size(scale, font, l).0).max();
+    let line_count = text.lines().count() as i32;
+
+    (
+        widest.unwrap_or(0).max(1),
+        (size.ceil() as i32 * line_count).max(1),
+    )
+}
+
+/// same as `draw_cell_text`, but centred on any pixel
+pub fn draw_text_centred(
+    img: &mut Image<Pxl>,
+    centre: (i32, i32),
+    text: &str,
+    colour: Pxl,
+    size: f32,
+) -> Rect {
+    let (font, scale) = (font(), Scale::uniform(size));
+    let line_height = size.ceil() as i32;
+    let lines: Vec<&str> = text.lines().collect();
+    let widths: Vec<i32> = lines.iter().map(|l| text_size(scale, font, l).0).collect();
+
+    let (total_w, total_h) = text_block_size(text, size);
+    let top = centre.1 - total_h / 2;
+
+    for (i, (line, w)) in lines.iter().zip(&widths).enumerate() {
+        let y = top + line_height * i as i32;
+        draw_text_mut(img, colour, centre.0 - w / 2, y, scale, font, line);
+    }
+
+    Rect::at(centre.0 - total_w / 2, top).of_size(total_w as u32, total_h as u32)
+}
+
+/// stamps a watermark into its corner of an image
+pub fn stamp_watermark(img: &mut Image<Pxl>, watermark: &Watermark) {
+    let (w, h) = stamp_size(&watermark.stamp);
+    let inset = watermark.inset as i32;
+    let (left, top) = match watermark.corner {
+        Corner::TopLeft => (inset, inset),
+        Corner::TopRight => (img.width() as i32 - w - inset, inset),
+        Corner::BottomLeft => (inset, img.height() as i32 - h - inset),
+        Corner::BottomRight => (
+            img.width() as i32 - w - inset,
+            img.height() as i32 - h - inset,
+        ),
+    };
+
+    match &watermark.stamp {
+        Stamp::Text { text, colour, size } => {
+            draw_text_centred(img, (left + w / 2, top + h / 2), text, *colour, *size);
+        }
+        Stamp::Image(stamp) => imageops::overlay(img, stamp, i64::from(left), i64::from(top)),
+    }
+}
+
+fn stamp_size(stamp: &Stamp) -> (i32, i32) {
+    match stamp {
+        Stamp::Text { text, size, .. } => text_block_size(text, *size),
+        Stamp::Image(img) => (img.width() as i32, img.height() as i32),
+    }
+}
@@ -0,0 +1,93 @@
+use crate::types::{EdgeSet, Point, Pxl};
+
+use image::imageops;
+use imageproc::definitions::Image;
+
+/// a way of turning or flipping the grid that keeps every cell's neighbours the same
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// this many quarter turns clockwise, from 1 to 3
+    Rotate(u8),
+    /// left to right
+    MirrorHorizontal,
+    /// top to bottom
+    MirrorVertical,
+    /// across the diagonal from the top left to the bottom right, swapping rows and columns
+    Transpose,
+}
+
+impl Transform {
+    /// the size of a `width` by `height` grid after the transform
+    pub const fn size(self, width: i32, height: i32) -> (i32, i32) {
+        match self {
+            Self::Rotate(1 | 3) | Self::Transpose => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// where a cell in a `width` by `height` grid ends up
+    pub const fn point(self, (x, y): Point, width: i32, height: i32) -> Point {
+        match self {
+            Self::Rotate(1) => (height - 1 - y, x),
+            Self::Rotate(2) => (width - 1 - x, height - 1 - y),
+            Self::Rotate(3) => (y, width - 1 - x),
+            Self::Rotate(_) => (x, y),
+            Self::MirrorHorizontal => (width - 1 - x, y),
+            Self::MirrorVertical => (x, height - 1 - y),
+            Self::Transpose => (y, x),
+        }
+    }
+
+    /// which way a direction points after the transform
+    pub const fn direction(self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        match self {
+            Self::Rotate(1) => (-dy, dx),
+            Self::Rotate(2) => (-dx, -dy),
+            Self::Rotate(3) => (dy, -dx),
+            Self::Rotate(_) => (dx, dy),
+            Self::MirrorHorizontal => (-dx, dy),
+            Self::MirrorVertical => (dx, -dy),
+            Self::Transpose => (dy, dx),
+        }
+    }
+
+    /// moves both ends of an edge, keeping the top/left cell first like the generator does
+    pub fn edge(self, (a, b): (Point, Point), width: i32, height: i32) -> (Point, Point) {
+        let (a, b) = (self.point(a, width, height), self.point(b, width, height));
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    pub fn walls(self, walls: &EdgeSet, width: i32, height: i32) -> EdgeSet {
+        walls.iter().map(|e| self.edge(*e, width, height)).collect()
+    }
+
+    /// the same transform applied to the pixels of an image, for things that are already fit to the maze like its background
+    pub fn image(self, img: &Image<Pxl>) -> Image<Pxl> {
+        match self {
+            Self::Rotate(1) => imageops::rotate90(img),
+            Self::Rotate(2) => imageops::rotate180(img),
+            Self::Rotate(3) => imageops::rotate270(img),
+            Self::Rotate(_) => img.clone(),
+            Self::MirrorHorizontal => imageops::flip_horizontal(img),
+            Self::MirrorVertical => imageops::flip_vertical(img),
+            Self::Transpose => imageops::flip_horizontal(&imageops::rotate90(img)),
+        }
+    }
+}
+
+/// the walls inside of the `(x, y, width, height)` rectangle `rect`, moved so that its top left corner is `(0, 0)`
+pub fn clip_walls(walls: &EdgeSet, rect: (i32, i32, i32, i32)) -> EdgeSet {
+    let (x0, y0, w, h) = rect;
+    let inside = |(x, y): Point| x >= x0 && y >= y0 && x < x0 + w && y < y0 + h;
+    let shift = |(x, y): Point| (x - x0, y - y0);
+
+    walls
+        .iter()
+        .filter(|(a, b)| inside(*a) && inside(*b))
+        .map(|(a, b)| (shift(*a), shift(*b)))
+        .collect()
+}
@@ -0,0 +1,85 @@
+use crate::direction::Direction;
+use crate::point::NamedPoint;
+use crate::types::Point;
+use crate::Maze;
+
+use pyo3::prelude::*;
+
+/// a single cell of a maze, as handed over to Python by `Maze.__getitem__`
+///
+/// it keeps the maze it came from alive, so it always sees the maze's current walls
+#[pyclass(module = "maze", frozen)]
+pub struct Cell {
+    maze: Py<Maze>,
+    xy: Point,
+}
+
+impl Cell {
+    pub(crate) const fn new(maze: Py<Maze>, xy: Point) -> Self {
+        Self { maze, xy }
+    }
+
+    /// the directions that aren't blocked off by a wall or the edge of the maze, looked up in one go
+    ///
+    /// the maze can be in the middle of being changed on another thread, so it's only borrowed if it can be
+    fn open(&self, py: Python) -> PyResult<Vec<Direction>> {
+        let maze = self.maze.try_borrow(py)?;
+        Ok(Direction::open_from(
+            &maze.walls,
+            self.xy,
+            maze.width,
+            maze.height,
+        ))
+    }
+}
+
+#[pymethods]
+impl Cell {
+    /// where the cell is in the maze
+    #[getter]
+    fn xy(&self) -> NamedPoint {
+        self.xy.into()
+    }
+
+    /// the maze the cell is in
+    #[getter]
+    fn maze(&self, py: Python) -> Py<Maze> {
+        self.maze.clone_ref(py)
+    }
+
+    /// the directions (`UP`, `DOWN`, `LEFT`, or `RIGHT`) that the player can move in from here, in that order
+    fn open_directions(&self, py: Python) -> PyResult<Vec<Direction>> {
+        self.open(py)
+    }
+
+    /// whether there's only one way out of the cell
+    #[getter]
+    fn is_dead_end(&self, py: Python) -> PyResult<bool> {
+        Ok(self.open(py)?.len() == 1)
+    }
+
+    /// whether there are three or more ways out of the cell, so there's a choice to be made
+    #[getter]
+    fn is_junction(&self, py: Python) -> PyResult<bool> {
+        Ok(self.open(py)?.len() >= 3)
+    }
+
+    /// the cells that can be moved to in a single step from here, in the same order as `.open_directions()`
+    fn neighbours(&self, py: Python) -> PyResult<Vec<Self>> {
+        let (x, y) = self.xy;
+        let neighbours = self
+            .open(py)?
+            .into_iter()
+            .map(|direction| {
+                let (dx, dy) = direction.delta();
+                Self::new(self.maze.clone_ref(py), (x + dx, y + dy))
+            })
+            .collect();
+
+        Ok(neighbours)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Cell(xy={:?})", self.xy)
+    }
+}
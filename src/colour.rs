@@ -0,0 +1,163 @@
+use crate::types::{Pxl, Terrain};
+
+use image::Rgba;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyLong, PyString};
+
+/// a colour passed in from Python, which can be any of
+/// - an RGB or RGBA tuple/list
+/// - a `"#RRGGBB"` or `"#RRGGBBAA"` hex string
+/// - a `0xRRGGBB` int
+/// - a CSS colour name like `"red"` or `"darkgrey"`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Colour(pub Pxl);
+
+impl From<Colour> for Pxl {
+    fn from(colour: Colour) -> Self {
+        colour.0
+    }
+}
+
+impl<'a> FromPyObject<'a> for Colour {
+    fn extract(obj: &'a PyAny) -> PyResult<Self> {
+        if let Ok(s) = obj.downcast::<PyString>() {
+            let s = s.to_str()?;
+            return parse_colour(s).map(Self).ok_or_else(|| {
+                let msg = format!(
+                    "expected a \"#RRGGBB\" or \"#RRGGBBAA\" hex code or a colour name; got {s:?}"
+                );
+                PyValueError::new_err(msg)
+            });
+        }
+
+        // bools are ints too in Python, but `True` being a colour would just hide mistakes
+        if obj.is_instance_of::<PyLong>() && !obj.is_instance_of::<PyBool>() {
+            return match obj.extract::<u32>() {
+                Ok(n) if n <= 0xFF_FF_FF => {
+                    let [_, r, g, b] = n.to_be_bytes();
+                    Ok(Self(Rgba([r, g, b, 255])))
+                }
+                _ => {
+                    let msg =
+                        format!("colour ints have to be between 0x000000 and 0xFFFFFF; got {obj}");
+                    Err(PyValueError::new_err(msg))
+                }
+            };
+        }
+
+        let values: Vec<u8> = match obj.extract() {
+            Ok(values) => values,
+            Err(_) => {
+                let msg = format!(
+                    "expected an RGB(A) collection, hex string, int, or colour name; got {}",
+                    obj.repr()?
+                );
+                return Err(PyTypeError::new_err(msg));
+            }
+        };
+
+        match values[..] {
+            [r, g, b] => Ok(Self(Rgba([r, g, b, 255]))),
+            [r, g, b, a] => Ok(Self(Rgba([r, g, b, a]))),
+            _ => {
+                let msg = format!(
+                    "colour collections need 3 (RGB) or 4 (RGBA) values; got {}",
+                    obj.repr()?
+                );
+                Err(PyValueError::new_err(msg))
+            }
+        }
+    }
+}
+
+/// what portals get drawn in if they aren't given a colour, going round in order as more are added
+pub const PORTAL_COLOURS: [Pxl; 4] = [
+    Rgba([168, 85, 247, 255]),
+    Rgba([6, 182, 212, 255]),
+    Rgba([245, 158, 11, 255]),
+    Rgba([236, 72, 153, 255]),
+];
+
+/// what switches get drawn in if they aren't given a colour
+pub const SWITCH_COLOUR: Pxl = Rgba([250, 204, 21, 255]);
+
+/// what checkpoints get drawn in, and how see-through they're drawn once the player has reached them
+pub const CHECKPOINT_COLOUR: Pxl = Rgba([34, 197, 94, 255]);
+pub const REACHED_CHECKPOINT_ALPHA: u8 = 96;
+
+/// the translucent tint drawn over a cell for its terrain, normal ground doesn't get one
+pub const fn terrain_tint(terrain: Terrain) -> Option<Pxl> {
+    match terrain {
+        Terrain::Normal => None,
+        Terrain::Ice => Some(Rgba([125, 211, 252, 96])),
+        Terrain::Mud => Some(Rgba([120, 72, 32, 112])),
+    }
+}
+
+/// parses a `"#RRGGBB"` or `"#RRGGBBAA"` hex code, or a colour name (case-insensitive)
+pub fn parse_colour(s: &str) -> Option<Pxl> {
+    let s = s.trim();
+    match s.strip_prefix('#') {
+        Some(hex) => parse_hex(hex),
+        None => named_colour(&s.to_ascii_lowercase()),
+    }
+}
+
+/// the `"#rrggbbaa"` hex code for a colour, which `parse_colour` reads back in
+pub fn hex_colour(Rgba([r, g, b, a]): Pxl) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}{a:02x}")
+}
+
+fn parse_hex(hex: &str) -> Option<Pxl> {
+    if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let mut arr = [255u8; 4];
+    for (i, channel) in arr.iter_mut().enumerate().take(hex.len() / 2) {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+
+    Some(Rgba(arr))
+}
+
+/// the more commonly used CSS colour names, plus `transparent`
+fn named_colour(name: &str) -> Option<Pxl> {
+    let [r, g, b] = match name {
+        "transparent" => return Some(Rgba([0, 0, 0, 0])),
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "lime" => [0, 255, 0],
+        "green" => [0, 128, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" | "aqua" => [0, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255],
+        "grey" | "gray" => [128, 128, 128],
+        "darkgrey" | "darkgray" => [169, 169, 169],
+        "lightgrey" | "lightgray" => [211, 211, 211],
+        "silver" => [192, 192, 192],
+        "maroon" => [128, 0, 0],
+        "olive" => [128, 128, 0],
+        "navy" => [0, 0, 128],
+        "purple" => [128, 0, 128],
+        "teal" => [0, 128, 128],
+        "orange" => [255, 165, 0],
+        "pink" => [255, 192, 203],
+        "brown" => [165, 42, 42],
+        "gold" => [255, 215, 0],
+        "indigo" => [75, 0, 130],
+        "violet" => [238, 130, 238],
+        "beige" => [245, 245, 220],
+        "coral" => [255, 127, 80],
+        "crimson" => [220, 20, 60],
+        "salmon" => [250, 128, 114],
+        "turquoise" => [64, 224, 208],
+        _ => return None,
+    };
+
+    Some(Rgba([r, g, b, 255]))
+}
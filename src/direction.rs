@@ -0,0 +1,179 @@
+use crate::types::{EdgeSet, Point};
+use crate::util::can_step;
+
+use pyo3::prelude::*;
+
+use pyo3::{
+    exceptions::{PyIndexError, PyTypeError},
+    pyclass::CompareOp,
+    types::{PyIterator, PyTuple},
+};
+
+/// one of the four ways the player can move, exported as `UP`, `DOWN`, `LEFT`, and `RIGHT`
+///
+/// these used to be plain `(dx, dy)` tuples, so it still unpacks, indexes, compares,
+/// and hashes like one to keep older code working
+#[pyclass(module = "maze", frozen)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Direction {
+    delta: (i32, i32),
+}
+
+impl Direction {
+    pub const UP: Self = Self { delta: (0, -1) };
+    pub const DOWN: Self = Self { delta: (0, 1) };
+    pub const LEFT: Self = Self { delta: (-1, 0) };
+    pub const RIGHT: Self = Self { delta: (1, 0) };
+
+    /// up, down, left, and right, in the same order as the exported constants
+    pub const ALL: [Self; 4] = [Self::UP, Self::DOWN, Self::LEFT, Self::RIGHT];
+
+    pub const fn delta(&self) -> (i32, i32) {
+        self.delta
+    }
+
+    /// the directions that a single step can be taken in from `xy`, in the same order as `ALL`
+    pub fn open_from(walls: &EdgeSet, xy: Point, width: i32, height: i32) -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|direction| can_step(walls, xy, direction.delta, width, height))
+            .collect()
+    }
+
+    const fn name(&self) -> &'static str {
+        match self.delta {
+            (0, -1) => "UP",
+            (0, 1) => "DOWN",
+            (-1, 0) => "LEFT",
+            _ => "RIGHT",
+        }
+    }
+}
+
+#[pymethods]
+impl Direction {
+    #[classattr]
+    #[pyo3(name = "UP")]
+    const fn up() -> Self {
+        Self::UP
+    }
+
+    #[classattr]
+    #[pyo3(name = "DOWN")]
+    const fn down() -> Self {
+        Self::DOWN
+    }
+
+    #[classattr]
+    #[pyo3(name = "LEFT")]
+    const fn left() -> Self {
+        Self::LEFT
+    }
+
+    #[classattr]
+    #[pyo3(name = "RIGHT")]
+    const fn right() -> Self {
+        Self::RIGHT
+    }
+
+    /// `(dx, dy)` how far a single step this way moves the player
+    #[getter(delta)]
+    const fn get_delta(&self) -> (i32, i32) {
+        self.delta
+    }
+
+    /// `"UP"`, `"DOWN"`, `"LEFT"`, or `"RIGHT"`
+    #[getter(name)]
+    const fn get_name(&self) -> &'static str {
+        self.name()
+    }
+
+    /// the direction pointing the other way
+    const fn opposite(&self) -> Self {
+        let (dx, dy) = self.delta;
+        Self { delta: (-dx, -dy) }
+    }
+
+    /// the direction a quarter turn anticlockwise from this one (`UP` turns into `LEFT`)
+    const fn turn_left(&self) -> Self {
+        let (dx, dy) = self.delta;
+        Self { delta: (dy, -dx) }
+    }
+
+    /// the direction a quarter turn clockwise from this one (`UP` turns into `RIGHT`)
+    const fn turn_right(&self) -> Self {
+        let (dx, dy) = self.delta;
+        Self { delta: (-dy, dx) }
+    }
+
+    fn __richcmp__(&self, py: Python, other: &PyAny, op: CompareOp) -> PyObject {
+        let Ok(other) = other.extract::<AnyDirection>() else {
+            return py.NotImplemented();
+        };
+
+        match op {
+            CompareOp::Eq => (self.delta == other.0).into_py(py),
+            CompareOp::Ne => (self.delta != other.0).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    /// the same as the `(dx, dy)` tuple's hash, since the two compare equal
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        PyTuple::new(py, [self.delta.0, self.delta.1]).hash()
+    }
+
+    fn __len__(&self) -> usize {
+        2
+    }
+
+    fn __getitem__(&self, idx: isize) -> PyResult<i32> {
+        match idx {
+            0 | -2 => Ok(self.delta.0),
+            1 | -1 => Ok(self.delta.1),
+            _ => Err(PyIndexError::new_err("direction index out of range")),
+        }
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        PyIterator::from_object(PyTuple::new(py, [self.delta.0, self.delta.1]))
+    }
+
+    /// pickles (and copies) as a reference to the exported constant of the same name
+    fn __reduce__(&self) -> &'static str {
+        self.name()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Direction.{}", self.name())
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.name()
+    }
+}
+
+/// a direction from Python, either a `Direction` or a plain `(dx, dy)` tuple
+///
+/// it isn't checked to be one of the four directions, since the places that take one
+/// each have their own way of handling anything else
+pub struct AnyDirection(pub (i32, i32));
+
+impl<'source> FromPyObject<'source> for AnyDirection {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(direction) = ob.downcast::<PyCell<Direction>>() {
+            return Ok(Self(direction.get().delta));
+        }
+
+        ob.extract().map(Self).map_err(|_| {
+            let msg = format!(
+                "expected a Direction or a (dx, dy) tuple; got {}",
+                ob.get_type().name().unwrap_or("?")
+            );
+            PyTypeError::new_err(msg)
+        })
+    }
+}
+
+/// `(direction, amount, is_max)` a move as it's handed over from Python
+pub type MoveArg = (AnyDirection, i32, bool);
@@ -1,230 +1,4281 @@
 mod algorithms;
+mod cell;
+mod colour;
+mod direction;
+mod point;
+mod pyio;
+mod save;
+mod solution;
+mod theme;
 mod types;
 mod util;
+mod validation;
 
 use algorithms::{
-    a_star_solution, bytes_to_image, fallback_image, generate_edges, maze_image, solution_image,
+    a_star_solution, average_colour, bytes_to_image, cell_rect, checkpoint_flag, clip_walls,
+    collect_all_solution, connect_regions, crop_to_cells, cut_tile, describe_moves,
+    describe_surroundings, draw_cell_indices, draw_cell_text, draw_coordinate_labels, encode_gif,
+    encode_image, encode_png, encode_to_vec, faded_trail_colour, fallback_image, first_person_view,
+    fit_background, fit_icon, framed, generate_edges, generation_frames, isometric_image,
+    load_fallback_icons, loop_passages, marker_dot, maze_ansi, maze_emoji, maze_image,
+    maze_image_size, maze_svg, maze_text, mechanics_solution, path_to_image, play_moves,
+    png_compression_from_name, png_filter_from_name, portal_ring, print_image, redraw_maze_region,
+    replay_frames, resize_filter_from_name, rotated_sprites, solution_frames, solution_image,
+    split_spritesheet, sprite_index, stamp_watermark, surrounding_rect, switch_plate, tile_grid,
+    trail_rect, AnsiColours, AssetSettings, Camera, Canvas, EmojiSet, Layer, Marks, Mechanics,
+    OutputFormat, Piece, Seams, SwitchMask, TextStyle, Transform, ViewColours, ASSET_SETTINGS,
+    MAX_SWITCHES,
 };
 
-use types::{EdgeVec, Point, Pxl};
-use util::{out_of_bounds, wall_between};
+use cell::Cell;
+use colour::{
+    hex_colour, parse_colour, terrain_tint, Colour, CHECKPOINT_COLOUR, PORTAL_COLOURS,
+    REACHED_CHECKPOINT_ALPHA, SWITCH_COLOUR,
+};
+use direction::{AnyDirection, Direction, MoveArg};
+use point::{AnyPoint, NamedPoint};
+use pyio::{fill_image_view, release_image_view, PyFileWriter};
+use save::{Look, SavedMaze};
+use solution::Solution;
+use theme::{override_style, Theme};
+use types::{
+    Background, BackgroundFit, Chaser, Corner, DirectionLabels, DrawStyle, EdgeSet, EdgeVec, Fog,
+    Frame, Marker, Move, MoveTuple, PathPaint, Player, Point, Portal, Projection, Pxl,
+    SimulationTuple, SolutionData, Stamp, Switch, Terrain, WallFill, Watermark,
+};
+use util::{
+    can_step, cells_between, cells_cut_off, cells_within, chunk_lines, connected_regions,
+    csr_adjacency, line_of_sight, open_neighbours, ordered_edge, out_of_bounds, passages,
+    passages_around, reachable_cells, same_walls, shortest_path, step_distances, structure_hash,
+    wall_between, wall_grids,
+};
+use validation::Validation;
 
-use image::{imageops, ImageOutputFormat, Rgba};
+use image::{imageops, ImageResult};
 use imageproc::{definitions::Image, drawing::draw_filled_rect_mut, rect::Rect};
 
-use std::{collections::HashSet, io::Cursor, sync::Arc};
+use std::{
+    borrow::Cow,
+    collections::{
+        hash_map::{DefaultHasher, RandomState},
+        HashMap, HashSet,
+    },
+    ffi::OsStr,
+    fs::File,
+    hash::{BuildHasher, Hash, Hasher},
+    io::{BufWriter, Write},
+    os::raw::c_int,
+    path::PathBuf,
+};
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+use pyo3::{
+    create_exception,
+    exceptions::{
+        PyBufferError, PyException, PyIOError, PyIndexError, PyNotADirectoryError, PyRuntimeError,
+        PyTypeError, PyValueError,
+    },
+    ffi,
+    pyclass::CompareOp,
+    types::{IntoPyDict, PyBytes, PyDict, PyFrozenSet, PyIterator, PyList, PyLong, PyTuple},
+};
+
+create_exception!(maze, SolutionNotFound, PyException);
+
+/// the tag of the markers that `.place_items()` puts down
+const ITEM_TAG: &str = "item";
+
+/// bundles elements representing a maze
+#[pyclass(module = "maze")]
+#[derive(Clone)]
+struct Maze {
+    width: i32,
+    height: i32,
+    background: Background,
+    wall_fill: WallFill,
+    solution_colour: Pxl,
+    /// the colour the solution path fades into by the time it reaches the goal, if it's drawn as a gradient
+    solution_gradient: Option<Pxl>,
+    solution: Option<SolutionData>,
+    solution_drawn: bool,
+    direction_labels: DirectionLabels,
+    /// the maze image and the layers it's made of, only built the first time something needs it
+    ///
+    /// movement, the trail, fog, etc. still keep track of their state without one, they just aren't drawn
+    /// until it's built (see `.rendered()`)
+    canvas: Option<Canvas>,
+    /// whether the maze was generated with `render=False`, in which case the canvas is never built
+    headless: bool,
+    player_icon: Image<Pxl>,
+    /// facing up, right, down, and left, only set if the player turns to face where it's going
+    player_sprites: Option<[Image<Pxl>; 4]>,
+    /// the direction the player is facing, which picks the sprite to draw
+    player_facing: (i32, i32),
+    player_pos: Option<Point>,
+    /// when the player was last drawn, see `Player::drawn_at`
+    player_drawn_at: u64,
+    /// players added with `.add_player()`, in the order they were added
+    players: Vec<Player>,
+    /// chasers added with `.add_chaser()`, in the order they were added
+    chasers: Vec<Chaser>,
+    /// how many times a player or chaser has been drawn, which is what `Player::drawn_at` counts in
+    draws: u64,
+    /// every marker drawn on the maze, in the order they were drawn
+    markers: Vec<Marker>,
+    /// where each item the player picked up was, in the order they were picked up
+    collected_items: Vec<Point>,
+    /// pairs of linked cells, in the order they were added
+    portals: Vec<Portal>,
+    /// cells that toggle walls when they're stepped on, in the order they were added
+    switches: Vec<Switch>,
+    /// every cell that isn't normal ground, tinted on the image
+    terrain: HashMap<Point, Terrain>,
+    /// cells the player has to get to in order on its way to the goal, in the order they were added
+    checkpoints: Vec<Point>,
+    /// how many moves into its history the player was when it reached each checkpoint, in order
+    checkpoints_reached: Vec<usize>,
+    /// `(from, to)` of every move the player has made
+    history: Vec<(Point, Point)>,
+    /// drawn behind the player as it moves, if it's set
+    trail_colour: Option<Pxl>,
+    /// `(from, to)` of every move drawn as part of the trail
+    trail: Vec<(Point, Point)>,
+    /// how many moves it takes for a trail segment to fade out completely, 0 if it never does
+    trail_fade: u32,
+    fog: Option<Fog>,
+    draw_style: DrawStyle,
+    frame: Frame,
+    watermark: Option<Watermark>,
+    /// the colour `enable_fog` uses if it isn't given one, from the theme the maze was generated with
+    fog_colour: Option<Pxl>,
+    end_icon: Image<Pxl>,
+    walls: HashSet<(Point, Point)>,
+    /// bumped whenever the walls change, so that a cached solution knows when it's gone stale
+    walls_version: u64,
+    /// the paths in the order they were carved out, only kept if the maze was generated with `record_generation`
+    carve_order: Option<EdgeVec>,
+    /// how many buffer views of the image are still alive, the image can't be swapped out for one of a different size while any are
+    views: usize,
+}
+
+/// private methods (not exposed to the Python)
+impl Maze {
+    /// recomputes the cached solution if the walls have changed since it was determined
+    ///
+    /// raises `SolutionNotFound` if the walls have since cut the goal off from the start
+    fn refresh_solution(&mut self, py: Python) -> PyResult<()> {
+        let version = self.walls_version;
+        if self
+            .solution
+            .as_ref()
+            .is_some_and(|d| d.walls_version != version)
+        {
+            self.solve(py, false)?;
+        }
+
+        Ok(())
+    }
+
+    /// the cached solution, or a `SolutionNotFound` error if it hasn't been computed yet
+    ///
+    /// make sure to call `.refresh_solution()` beforehand so that this isn't stale
+    fn cached_solution(&self) -> PyResult<&SolutionData> {
+        const MSG: &str = "make sure to call `.compute_solution()` first";
+        self.solution
+            .as_ref()
+            .ok_or_else(|| SolutionNotFound::new_err(MSG))
+    }
+
+    /// runs the solver and caches the result
+    ///
+    /// if a solution path from before the walls last changed is drawn on the image,
+    /// the image is re-rendered and the new path is drawn in its place
+    ///
+    /// if the goal can't be reached, the cached solution (and its path on the image) is dropped
+    /// and `SolutionNotFound` is raised
+    fn solve(&mut self, py: Python, draw_path: bool) -> PyResult<&SolutionData> {
+        let version = self.walls_version;
+        let outdated_path = self.solution_drawn
+            && self
+                .solution
+                .as_ref()
+                .is_some_and(|d| d.walls_version != version);
+
+        let (w, h) = (self.width, self.height);
+        let mechanics = self.mechanics();
+        let solved = if mechanics.is_empty() {
+            a_star_solution(&self.walls, w, h)
+        } else {
+            mechanics_solution(&self.walls, &mechanics, w, h)
+        };
+
+        let Some((move_count, moves, path)) = solved else {
+            self.solution = None;
+            if self.solution_drawn {
+                self.redraw_base(py);
+            }
+
+            let msg = "the goal can't be reached from the start";
+            return Err(SolutionNotFound::new_err(msg));
+        };
+
+        if outdated_path {
+            self.redraw_base(py);
+        }
+
+        if draw_path || outdated_path {
+            self.draw_solution(py, &path);
+        }
+
+        Ok(self.solution.insert(SolutionData {
+            move_count,
+            moves,
+            path,
+            walls_version: version,
+        }))
+    }
+
+    /// re-renders the maze image from its walls, wiping the solution path but keeping the player, markers, and text
+    fn redraw_base(&mut self, py: Python) {
+        self.solution_drawn = false;
+        if self.canvas.is_none() {
+            return;
+        }
+
+        let fresh = self.base_image(py);
+        let canvas = self.canvas.as_mut().expect("checked right above");
+        canvas.layers.set_base(fresh);
+        canvas.layers.clear(Layer::Solution);
+        canvas.layers.clear(Layer::Player);
+        self.draw_players();
+        self.composite(py, None);
+    }
+
+    /// the canvas, building it first if nothing has needed it yet,
+    /// or an error if the maze was generated with `render=False`
+    fn rendered(&mut self, py: Python) -> PyResult<&mut Canvas> {
+        if self.headless {
+            let msg = "the maze was generated with `render=False`, so it doesn't have an image";
+            return Err(PyRuntimeError::new_err(msg));
+        }
+
+        if self.canvas.is_none() {
+            let base = self.base_image(py);
+            self.canvas = Some(Canvas::new(base));
+            self.draw_state(py);
+        }
+
+        Ok(self.canvas.as_mut().expect("built right above"))
+    }
+
+    /// renders just the walls, background, and endzone
+    fn base_image(&self, py: Python) -> Image<Pxl> {
+        let (w, h) = (self.width, self.height);
+        let (bg, wall, end_icon) = (&self.background, &self.wall_fill, &self.end_icon);
+        let style = self.draw_style;
+
+        // screw the GIL
+        py.allow_threads(|| maze_image(&self.walls, bg, wall, style, end_icon, w, h))
+    }
+
+    /// draws everything the maze has been keeping track of onto a freshly built canvas,
+    /// the solution path (if it was drawn), trail, markers, fog, and player
+    fn draw_state(&mut self, py: Python) {
+        let paint = self.solution_paint();
+        let icons = self.mechanic_icons();
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+
+        if let (true, Some(data)) = (self.solution_drawn, &self.solution) {
+            let layer = canvas.layers.take(Layer::Solution);
+            let layer = py.allow_threads(|| solution_image(layer, &data.path, paint));
+            canvas.layers.put_back(Layer::Solution, layer);
+        }
+
+        for (xy, terrain) in &self.terrain {
+            if let Some(tint) = terrain_tint(*terrain) {
+                draw_filled_rect_mut(
+                    canvas.layers.layer_mut(Layer::Terrain),
+                    cell_rect(*xy),
+                    tint,
+                );
+            }
+        }
+
+        for (xy, icon) in &icons {
+            let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
+            imageops::overlay(canvas.layers.layer_mut(Layer::Markers), icon, x, y);
+        }
+
+        for m in &self.markers {
+            let (x, y) = (i64::from(m.xy.0) * 40, i64::from(m.xy.1) * 40);
+            imageops::overlay(canvas.layers.layer_mut(Layer::Markers), &m.icon, x, y);
+        }
+
+        if let Some(fog) = &self.fog {
+            canvas.layers.fill(Layer::Fog, fog.colour);
+            for cell in &fog.revealed {
+                canvas
+                    .layers
+                    .clear_rect(Layer::Fog, surrounding_rect(*cell));
+            }
+        }
+
+        self.paint_trail();
+        self.draw_players();
+
+        self.composite(py, None);
+    }
+
+    /// flattens the layers into the maze image, only inside of `region` if it's given
+    fn composite(&mut self, py: Python, region: Option<Rect>) {
+        if let Some(canvas) = &mut self.canvas {
+            py.allow_threads(|| canvas.composite(region));
+        }
+    }
+
+    /// the player icon facing whichever way it last moved
+    fn player_sprite(&self) -> &Image<Pxl> {
+        self.sprite_facing(self.player_facing)
+    }
+
+    /// the player icon turned to face `direction`, or just the icon if the player doesn't turn
+    fn sprite_facing(&self, direction: (i32, i32)) -> &Image<Pxl> {
+        match (&self.player_sprites, sprite_index(direction)) {
+            (Some(sprites), Some(i)) => &sprites[i],
+            _ => &self.player_icon,
+        }
+    }
+
+    /// moves a player (see `.player_index()`) from `old` to `new` after it went `direction`, redrawing it,
+    /// picking up items along the way, and recording the move in its history
+    ///
+    /// the player the maze was generated with also gets its trail drawn and lifts the fog along the way
+    ///
+    /// if `new` is a portal that put the player on `exit`, that's where it's drawn and where the move ends up,
+    /// though the trail still stops at the portal it went into
+    fn move_player(
+        &mut self,
+        py: Python,
+        who: Option<usize>,
+        old: Point,
+        new: Point,
+        direction: (i32, i32),
+        exit: Option<Point>,
+    ) {
+        let passed: Vec<Point> = cells_between(old, new).into_iter().chain(exit).collect();
+        let end = exit.unwrap_or(new);
+        if let Some(i) = who {
+            let player = &mut self.players[i];
+            if player.pos == Some(old) {
+                player.pos = None;
+            }
+
+            player.history.push((old, end));
+            self.redraw_players_at(old);
+            self.pick_up_items(&passed);
+            self.draw_player(who, end);
+            return;
+        }
+
+        if self.player_pos == Some(old) {
+            self.player_pos = None;
+        }
+
+        self.redraw_players_at(old);
+        if let (Some(colour), true) = (self.trail_colour, old != new) {
+            self.trail.push((old, new));
+            if self.trail_fade > 0 {
+                // every older segment just got a move older, so they all need redrawing
+                self.drop_faded_trail();
+                self.redraw_trail(py);
+            } else if let Some(canvas) = &mut self.canvas {
+                let rect = trail_rect(old, new);
+                draw_filled_rect_mut(canvas.layers.layer_mut(Layer::Trail), rect, colour);
+                canvas.composite(Some(rect));
+            }
+        }
+
+        if sprite_index(direction).is_some() {
+            self.player_facing = direction;
+        }
+
+        // the fog lifts along the whole way, not just where the player ends up
+        for cell in &passed {
+            self.reveal_around(*cell);
+        }
+
+        self.pick_up_items(&passed);
+        self.draw_player(None, end);
+        self.history.push((old, end));
+        self.reach_checkpoints(&passed[1..]);
+    }
+
+    /// ticks off the checkpoints the player reached on `cells`, the ones it just passed through in order,
+    /// as long as it reached every one before them first
+    fn reach_checkpoints(&mut self, cells: &[Point]) {
+        let mut reached = HashSet::new();
+        for xy in cells {
+            let next = self.checkpoints.get(self.checkpoints_reached.len());
+            if next == Some(xy) {
+                self.checkpoints_reached.push(self.history.len());
+                reached.insert(*xy);
+            }
+        }
+
+        self.redraw_markers_at(&reached);
+    }
+
+    /// picks up every item on `cells`, the ones the player just passed through in order, erasing them from the image
+    ///
+    /// moves pass in the cell they start from as well, so an item the player was already standing on
+    /// (like one on the start) is picked up on the way out, the same as `.solve_collect_all()` counts it
+    fn pick_up_items(&mut self, cells: &[Point]) {
+        let is_item = |m: &Marker| m.tag.as_deref() == Some(ITEM_TAG);
+        let picked: Vec<Point> = cells
+            .iter()
+            .copied()
+            .filter(|xy| self.markers.iter().any(|m| is_item(m) && m.xy == *xy))
+            .collect();
+
+        if picked.is_empty() {
+            return;
+        }
+
+        self.markers
+            .retain(|m| !(is_item(m) && picked.contains(&m.xy)));
+        self.collected_items.extend(&picked);
+        self.redraw_markers_at(&picked.into_iter().collect());
+    }
+
+    /// the portals, switches, and terrain, set up for moving around and solving
+    fn mechanics(&self) -> Mechanics {
+        Mechanics::new(&self.portals, &self.switches, &self.terrain)
+    }
+
+    /// toggles the walls of every switch in `pressed` after the player stepped onto them
+    fn press_switches(&mut self, py: Python, pressed: SwitchMask) {
+        if pressed == 0 {
+            return;
+        }
+
+        let pressed = self
+            .switches
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| pressed & (1 << i) != 0);
+        for (_, switch) in pressed {
+            for edge in &switch.walls {
+                if !self.walls.remove(edge) {
+                    self.walls.insert(*edge);
+                }
+            }
+        }
+
+        self.walls_changed(py);
+    }
+
+    /// tints a cell on the terrain layer (or wipes it, for normal ground)
+    fn draw_terrain_at(&mut self, xy: Point) {
+        let tint = terrain_tint(self.terrain.get(&xy).copied().unwrap_or_default());
+        if let Some(canvas) = &mut self.canvas {
+            canvas.layers.clear_rect(Layer::Terrain, cell_rect(xy));
+            if let Some(tint) = tint {
+                draw_filled_rect_mut(canvas.layers.layer_mut(Layer::Terrain), cell_rect(xy), tint);
+            }
+        }
+    }
+
+    /// the rings around both ends of every portal, the plates on every switch, and the flags on every checkpoint
+    /// (faded out once they've been reached), with where they go
+    fn mechanic_icons(&self) -> Vec<(Point, Image<Pxl>)> {
+        let supersample = self.draw_style.supersample;
+        let portals = self.portals.iter().flat_map(|p| {
+            let ring = portal_ring(p.colour, supersample);
+            [(p.ends.0, ring.clone()), (p.ends.1, ring)]
+        });
+        let switches = self
+            .switches
+            .iter()
+            .map(|s| (s.xy, switch_plate(s.colour, supersample)));
+
+        let checkpoints = self.checkpoints.iter().enumerate().map(|(i, xy)| {
+            let mut colour = CHECKPOINT_COLOUR;
+            if i < self.checkpoints_reached.len() {
+                colour.0[3] = REACHED_CHECKPOINT_ALPHA;
+            }
+
+            (*xy, checkpoint_flag(colour, supersample))
+        });
+
+        portals.chain(switches).chain(checkpoints).collect()
+    }
+
+    /// draws (or wipes) the markers layer on just these cells from the portals, switches, and markers on them
+    fn redraw_markers_at(&mut self, cells: &HashSet<Point>) {
+        let icons = self.mechanic_icons();
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+
+        for xy in cells {
+            canvas.layers.clear_rect(Layer::Markers, cell_rect(*xy));
+        }
+
+        for (xy, icon) in icons.iter().filter(|(xy, _)| cells.contains(xy)) {
+            let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
+            imageops::overlay(canvas.layers.layer_mut(Layer::Markers), icon, x, y);
+        }
+
+        for m in self.markers.iter().filter(|m| cells.contains(&m.xy)) {
+            let (x, y) = (i64::from(m.xy.0) * 40, i64::from(m.xy.1) * 40);
+            imageops::overlay(canvas.layers.layer_mut(Layer::Markers), &m.icon, x, y);
+        }
+
+        for xy in cells {
+            canvas.composite(Some(cell_rect(*xy)));
+        }
+    }
+
+    /// draws a player (see `.player_index()`) at a given XY coordinate, on top of any others already there
+    ///
+    /// the player the maze was generated with faces whichever way it last moved, and lifts the fog around it
+    fn draw_player(&mut self, who: Option<usize>, xy: Point) {
+        self.draws += 1;
+        match who {
+            None => {
+                self.player_pos = Some(xy);
+                self.player_drawn_at = self.draws;
+                self.reveal_around(xy);
+            }
+            Some(i) => {
+                self.players[i].pos = Some(xy);
+                self.players[i].drawn_at = self.draws;
+            }
+        }
+
+        self.redraw_players_at(xy);
+    }
+
+    /// redraws the player layer on a single cell from every player and chaser standing on it,
+    /// with whichever one was drawn last on top
+    fn redraw_players_at(&mut self, xy: Point) {
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+
+        let mut icons: Vec<(u64, &Image<Pxl>)> = self
+            .players
+            .iter()
+            .filter(|p| p.pos == Some(xy))
+            .map(|p| (p.drawn_at, &p.icon))
+            .chain(
+                self.chasers
+                    .iter()
+                    .filter(|c| c.pos == xy)
+                    .map(|c| (c.drawn_at, &c.icon)),
+            )
+            .collect();
+
+        if self.player_pos == Some(xy) {
+            let icon = match (&self.player_sprites, sprite_index(self.player_facing)) {
+                (Some(sprites), Some(i)) => &sprites[i],
+                _ => &self.player_icon,
+            };
+            icons.push((self.player_drawn_at, icon));
+        }
+
+        icons.sort_by_key(|(drawn_at, _)| *drawn_at);
+        let rect = cell_rect(xy);
+        let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
+        canvas.layers.clear_rect(Layer::Player, rect);
+        for (_, icon) in icons {
+            imageops::overlay(canvas.layers.layer_mut(Layer::Player), icon, x, y);
+        }
+
+        canvas.composite(Some(rect));
+    }
+
+    /// draws every player and chaser onto the player layer, which should've just been cleared
+    fn draw_players(&mut self) {
+        let cells: HashSet<Point> = self
+            .player_pos
+            .into_iter()
+            .chain(self.players.iter().filter_map(|p| p.pos))
+            .chain(self.chasers.iter().map(|c| c.pos))
+            .collect();
+
+        for xy in cells {
+            self.redraw_players_at(xy);
+        }
+    }
+
+    /// where the player a chaser is after is, or `None` if it isn't drawn (or was removed)
+    fn chaser_target(&self, target: Option<&str>) -> Option<Point> {
+        match target {
+            None => self.player_pos,
+            Some(id) => self.players.iter().find(|p| p.id == id)?.pos,
+        }
+    }
+
+    /// which of `.players` an id from Python means, or `None` for the player the maze was generated with
+    fn player_index(&self, id: Option<&str>) -> PyResult<Option<usize>> {
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        match self.players.iter().position(|p| p.id == id) {
+            Some(i) => Ok(Some(i)),
+            None => Err(PyValueError::new_err(format!(
+                "there's no player with the id {id:?}"
+            ))),
+        }
+    }
+
+    /// lifts the fog (if there is any) off of every cell within its radius of `xy`
+    fn reveal_around(&mut self, xy: Point) {
+        let Some(fog) = &mut self.fog else {
+            return;
+        };
+
+        let nearby = cells_within(xy, fog.radius, self.width, self.height);
+        for cell in nearby.into_iter().filter(|c| fog.revealed.insert(*c)) {
+            if let Some(canvas) = &mut self.canvas {
+                let rect = surrounding_rect(cell);
+                canvas.layers.clear_rect(Layer::Fog, rect);
+                canvas.composite(Some(rect));
+            }
+        }
+    }
+
+    /// the maze image with its frame put around it and resized by `scale` for exporting,
+    /// or just the image itself if there's no frame and the scale is left at 1
+    ///
+    /// isometric exports are rendered from scratch, and neither those nor cropped ones get a frame
+    fn exported_image(
+        &mut self,
+        py: Python,
+        projection: &str,
+        crop: Option<&PyAny>,
+        scale: f32,
+        filter: &str,
+    ) -> PyResult<Cow<'_, Image<Pxl>>> {
+        let filter = extract_resize_filter(filter, "a scale")?;
+        if !(scale > 0.0 && scale.is_finite()) {
+            let msg = format!("scale must be a positive number; got {scale}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let Some(projection) = Projection::from_name(projection) else {
+            let msg = format!(
+                "expected one of \"top-down\" or \"isometric\" as a projection; got {projection:?}"
+            );
+            return Err(PyValueError::new_err(msg));
+        };
+
+        let crop = match crop {
+            None => None,
+            Some(_) if projection == Projection::Isometric => {
+                let msg = "isometric exports can't be cropped";
+                return Err(PyValueError::new_err(msg));
+            }
+            Some(crop) => Some(extract_crop(crop, self.width, self.height)?),
+        };
+
+        // the flat projection is drawn from the canvas, so it has to be built first
+        if projection == Projection::TopDown {
+            self.rendered(py)?;
+        }
+
+        let img = match (projection, &self.canvas) {
+            (Projection::TopDown, Some(canvas)) if crop.is_some() => {
+                let cells = crop.expect("checked by the match guard");
+                Cow::Owned(py.allow_threads(|| crop_to_cells(&canvas.image, cells)))
+            }
+            (Projection::TopDown, Some(canvas)) if self.frame == Frame::default() => {
+                Cow::Borrowed(&canvas.image)
+            }
+            (Projection::TopDown, Some(canvas)) => {
+                let (frame, bg, w, h) =
+                    (self.frame, self.background.colour, self.width, self.height);
+                Cow::Owned(py.allow_threads(|| framed(&canvas.image, frame, bg, w, h)))
+            }
+            (Projection::TopDown, None) => unreachable!("the canvas was built right above"),
+            (Projection::Isometric, _) => {
+                let (w, h) = (self.width, self.height);
+                let mut icons = vec![((w - 1, h - 1), &self.end_icon)];
+                icons.extend(self.players.iter().filter_map(|p| Some((p.pos?, &p.icon))));
+                icons.extend(self.chasers.iter().map(|c| (c.pos, &c.icon)));
+                if let Some(xy) = self.player_pos {
+                    icons.push((xy, self.player_sprite()));
+                }
+
+                let (bg, wall) = (average_colour(&self.background), self.wall_fill.colour);
+                Cow::Owned(
+                    py.allow_threads(|| isometric_image(&self.walls, bg, wall, &icons, w, h)),
+                )
+            }
+        };
+
+        let img = if scale == 1.0 {
+            img
+        } else {
+            let (w, h) = img.dimensions();
+            let new_w = ((w as f32 * scale).round() as u32).max(1);
+            let new_h = ((h as f32 * scale).round() as u32).max(1);
+            Cow::Owned(py.allow_threads(|| imageops::resize(&*img, new_w, new_h, filter)))
+        };
+
+        // stamped on last so that it comes out the same size no matter how the maze is scaled
+        match &self.watermark {
+            None => Ok(img),
+            Some(watermark) => {
+                let mut img = img.into_owned();
+                stamp_watermark(&mut img, watermark);
+                Ok(Cow::Owned(img))
+            }
+        }
+    }
+
+    /// forgets the trail segments that have faded out completely, so a fading trail doesn't keep growing
+    fn drop_faded_trail(&mut self) {
+        if self.trail_fade > 0 {
+            let faded = self.trail.len().saturating_sub(self.trail_fade as usize);
+            self.trail.drain(..faded);
+        }
+    }
+
+    /// wipes the trail layer and draws every trail segment on it again
+    fn redraw_trail(&mut self, py: Python) {
+        self.paint_trail();
+        self.composite(py, None);
+    }
+
+    /// the drawing part of `.redraw_trail()`, without compositing it into the maze image
+    fn paint_trail(&mut self) {
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+
+        canvas.layers.clear(Layer::Trail);
+        if let Some(colour) = self.trail_colour {
+            let (layer, fade) = (canvas.layers.layer_mut(Layer::Trail), self.trail_fade);
+            // oldest first, so that newer segments are drawn over the older ones they cross
+            for (age, (from, to)) in self.trail.iter().rev().enumerate().rev() {
+                if let Some(colour) = faded_trail_colour(colour, age, fade) {
+                    draw_filled_rect_mut(layer, trail_rect(*from, *to), colour);
+                }
+            }
+        }
+    }
+
+    /// renders the maze at evenly spaced points while it was being carved out
+    fn generation_frames(&self, py: Python, n_frames: usize) -> PyResult<Vec<Image<Pxl>>> {
+        const MSG: &str = "the maze has to be generated with `record_generation=True` first";
+        let carved = self
+            .carve_order
+            .as_ref()
+            .ok_or_else(|| PyValueError::new_err(MSG))?;
+
+        if n_frames < 2 {
+            return Err(PyValueError::new_err("frames must be at least 2"));
+        }
+
+        let (w, h) = (self.width, self.height);
+        let (bg, wall, end_icon) = (&self.background, &self.wall_fill, &self.end_icon);
+        let style = self.draw_style;
+        Ok(py.allow_threads(|| {
+            generation_frames(
+                &self.walls,
+                carved,
+                n_frames,
+                bg,
+                wall,
+                style,
+                end_icon,
+                w,
+                h,
+            )
+        }))
+    }
+
+    /// where the player (if it's been drawn) and goal are, for the text renderers to mark, if `markers` is set
+    fn marks(&self, markers: bool) -> Marks {
+        if !markers {
+            return Marks::default();
+        }
+
+        Marks {
+            player: self.player_pos,
+            goal: Some((self.width - 1, self.height - 1)),
+        }
+    }
+
+    fn solution_paint(&self) -> PathPaint {
+        match self.solution_gradient {
+            None => PathPaint::Solid(self.solution_colour),
+            Some(end) => PathPaint::Gradient(self.solution_colour, end),
+        }
+    }
+
+    /// wipes the solution layer and draws the cached solution path on it again, if it's meant to be drawn
+    fn redraw_solution(&mut self, py: Python) {
+        let path = match &self.solution {
+            Some(data) if self.solution_drawn => data.path.clone(),
+            _ => return,
+        };
+
+        if let Some(canvas) = &mut self.canvas {
+            canvas.layers.clear(Layer::Solution);
+        }
+
+        self.draw_solution(py, &path);
+    }
+
+    /// draws the solution path onto its layer
+    fn draw_solution(&mut self, py: Python, solution: &EdgeVec) {
+        self.solution_drawn = true;
+        let paint = self.solution_paint();
+        let Some(canvas) = &mut self.canvas else {
+            return;
+        };
+
+        let layer = canvas.layers.take(Layer::Solution);
+        let layer = py.allow_threads(|| solution_image(layer, solution, paint));
+        canvas.layers.put_back(Layer::Solution, layer);
+        self.composite(py, None);
+    }
+
+    /// marks a cached solution as stale and re-renders the image after the walls were edited,
+    /// drawing the new solution path in place of the old one if it was drawn
+    fn walls_changed(&mut self, py: Python) {
+        self.walls_version += 1;
+        if self.solution_drawn {
+            // a goal that's been cut off just leaves the path off of the image
+            let _ = self.refresh_solution(py);
+        } else {
+            self.redraw_base(py);
+        }
+    }
+
+    /// marks a cached solution as stale after portals or switches were added or removed, drawing the new solution path
+    /// in place of the old one if it was drawn (the walls are the same, so nothing else needs redrawing)
+    fn mechanics_changed(&mut self, py: Python) {
+        self.walls_version += 1;
+        if self.solution_drawn {
+            let _ = self.refresh_solution(py);
+        }
+    }
+
+    /// the walls, colours, and game state that get saved by `.to_json()`
+    fn saved(&self) -> SavedMaze {
+        SavedMaze {
+            width: self.width,
+            height: self.height,
+            walls: self.walls.clone(),
+            bg_colour: self.background.colour,
+            wall_colour: self.wall_fill.colour,
+            solution_colour: self.solution_colour,
+            solution_gradient: self.solution_gradient,
+            trail_colour: self.trail_colour,
+            wall_seed: self.wall_fill.seed,
+            player_pos: self.player_pos,
+            player_facing: self.player_facing,
+            history: self.history.clone(),
+            trail: self.trail.clone(),
+            fog: self.fog.clone(),
+            portals: self.portals.clone(),
+            switches: self.switches.clone(),
+            terrain: self.terrain.clone(),
+            checkpoints: self.checkpoints.clone(),
+            checkpoints_reached: self.checkpoints_reached.clone(),
+            solved: self.solution.is_some(),
+            solution_drawn: self.solution_drawn,
+        }
+    }
+
+    /// puts a saved maze's walls and game state onto a freshly generated maze of the same size,
+    /// which already has the saved colours
+    fn load_saved(&mut self, py: Python, saved: SavedMaze) {
+        self.walls = saved.walls;
+        self.walls_version += 1;
+        self.carve_order = None;
+        self.wall_fill.seed = saved.wall_seed;
+        self.player_pos = saved.player_pos;
+        self.player_facing = saved.player_facing;
+        self.history = saved.history;
+        self.trail = saved.trail;
+        self.fog = saved.fog;
+        self.portals = saved.portals;
+        self.switches = saved.switches;
+        self.terrain = saved.terrain;
+        self.checkpoints = saved.checkpoints;
+        self.checkpoints_reached = saved.checkpoints_reached;
+        if saved.solved {
+            // a maze that was saved with its goal cut off just comes back unsolved
+            let _ = self.solve(py, saved.solution_drawn);
+        }
+    }
+
+    /// everything about how the maze looks that isn't part of `.saved()`
+    fn look(&self) -> Look {
+        Look {
+            bg_image: self.background.image.clone(),
+            wall_jitter: self.wall_fill.jitter,
+            wall_texture: self.wall_fill.texture.clone(),
+            player_icon: self.player_icon.clone(),
+            player_sprites: self.player_sprites.clone(),
+            end_icon: self.end_icon.clone(),
+            trail_fade: self.trail_fade,
+            draw_style: self.draw_style,
+            frame: self.frame,
+            watermark: self.watermark.clone(),
+            fog_colour: self.fog_colour,
+            direction_labels: self.direction_labels.clone(),
+            markers: self.markers.clone(),
+            players: self.players.clone(),
+            chasers: self.chasers.clone(),
+            collected_items: self.collected_items.clone(),
+            carve_order: self.carve_order.clone(),
+            headless: self.headless,
+        }
+    }
+
+    /// a fresh maze with the same look as this one but different walls, with no player, markers, trail, etc.
+    ///
+    /// `background` has to already be fit to the new size
+    fn with_walls(&self, walls: EdgeSet, width: i32, height: i32, background: Background) -> Self {
+        Self {
+            walls,
+            walls_version: 0,
+            carve_order: None,
+            views: 0,
+            canvas: None,
+            headless: self.headless,
+            width,
+            height,
+            background,
+            wall_fill: self.wall_fill.clone(),
+            player_icon: self.player_icon.clone(),
+            player_sprites: self.player_sprites.clone(),
+            player_facing: (0, -1),
+            player_pos: None,
+            player_drawn_at: 0,
+            players: vec![],
+            chasers: vec![],
+            draws: 0,
+            markers: vec![],
+            collected_items: vec![],
+            portals: vec![],
+            switches: vec![],
+            terrain: HashMap::new(),
+            checkpoints: vec![],
+            checkpoints_reached: vec![],
+            history: vec![],
+            trail_colour: self.trail_colour,
+            trail: vec![],
+            trail_fade: self.trail_fade,
+            fog: None,
+            draw_style: self.draw_style,
+            frame: self.frame,
+            watermark: self.watermark.clone(),
+            fog_colour: self.fog_colour,
+            end_icon: self.end_icon.clone(),
+            solution_colour: self.solution_colour,
+            solution_gradient: self.solution_gradient,
+            solution: None,
+            solution_drawn: false,
+            direction_labels: self.direction_labels.clone(),
+        }
+    }
+
+    /// moves the walls and everything that's kept track of on top of them to where `transform` puts them,
+    /// then re-renders the image (or drops it to be rebuilt later, if it changes size)
+    fn apply_transform(&mut self, py: Python, transform: Transform) -> PyResult<()> {
+        let (w, h) = (self.width, self.height);
+        let (new_w, new_h) = transform.size(w, h);
+        let resized = (new_w, new_h) != (w, h);
+        if resized && self.canvas.is_some() && self.views > 0 {
+            let msg = "the maze image can't change size while a buffer view of it is still alive";
+            return Err(PyBufferError::new_err(msg));
+        }
+
+        let point = |xy| transform.point(xy, w, h);
+        let step = |(a, b)| (point(a), point(b));
+
+        self.walls = transform.walls(&self.walls, w, h);
+        self.walls_version += 1;
+        if let Some(paths) = &mut self.carve_order {
+            paths.iter_mut().for_each(|e| *e = step(*e));
+        }
+
+        self.player_pos = self.player_pos.map(point);
+        for player in &mut self.players {
+            player.pos = player.pos.map(point);
+            player.history.iter_mut().for_each(|e| *e = step(*e));
+        }
+        self.chasers.iter_mut().for_each(|c| c.pos = point(c.pos));
+        self.player_facing = transform.direction(self.player_facing);
+        self.markers.iter_mut().for_each(|m| m.xy = point(m.xy));
+        self.collected_items
+            .iter_mut()
+            .for_each(|xy| *xy = point(*xy));
+        self.portals.iter_mut().for_each(|p| p.ends = step(p.ends));
+        for switch in &mut self.switches {
+            switch.xy = point(switch.xy);
+            switch.walls = switch
+                .walls
+                .iter()
+                .map(|e| transform.edge(*e, w, h))
+                .collect();
+        }
+        self.terrain = self.terrain.drain().map(|(xy, t)| (point(xy), t)).collect();
+        self.checkpoints.iter_mut().for_each(|xy| *xy = point(*xy));
+        self.history.iter_mut().for_each(|e| *e = step(*e));
+        self.trail.iter_mut().for_each(|e| *e = step(*e));
+        if let Some(fog) = &mut self.fog {
+            fog.revealed = fog.revealed.iter().map(|xy| point(*xy)).collect();
+        }
+
+        if let Some(img) = &mut self.background.image {
+            *img = transform.image(img);
+        }
+
+        (self.width, self.height) = (new_w, new_h);
+
+        // the solution is recomputed without touching the image, since it's about to be rebuilt anyway
+        // the new corners might not be connected in a maze that had walls added,
+        // in which case there's no path left to draw
+        let path_drawn = std::mem::take(&mut self.solution_drawn);
+        self.solution_drawn = path_drawn && self.refresh_solution(py).is_ok();
+
+        if resized {
+            self.canvas = None;
+        } else if self.canvas.is_some() {
+            let fresh = self.base_image(py);
+            self.canvas
+                .as_mut()
+                .expect("checked right above")
+                .reset(fresh);
+            self.draw_state(py);
+        }
+
+        Ok(())
+    }
+}
+
+/// public methods (exposed to the Python)
+#[pymethods]
+impl Maze {
+    /// exposes the raw RGBA pixels of the maze image through the buffer protocol,
+    /// as a read-only `(height, width, 4)` array of bytes
+    ///
+    /// this lets `memoryview`, numpy, Pillow, etc. wrap the image without copying or encoding it;
+    /// the view is live, so anything drawn onto the maze afterwards shows up in it too
+    unsafe fn __getbuffer__(
+        slf: &PyCell<Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        slf.borrow_mut().rendered(slf.py())?;
+        let maze = slf.borrow();
+        let img = &maze.canvas.as_ref().expect("rendered right above").image;
+        fill_image_view(view, flags, img.as_raw(), img.dimensions(), slf)?;
+        drop(maze);
+        slf.borrow_mut().views += 1;
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&mut self, view: *mut ffi::Py_buffer) {
+        release_image_view(view);
+        self.views -= 1;
+    }
+
+    /// mazes aren't generated from a seed, so the `.canonical_hash()` of the walls is shown to tell them apart
+    fn __repr__(&self) -> String {
+        format!(
+            "Maze(width={}, height={}, walls={}, solved={}, hash='{}')",
+            self.width,
+            self.height,
+            self.walls.len(),
+            if self.solution.is_some() {
+                "True"
+            } else {
+                "False"
+            },
+            self.canonical_hash(false)
+        )
+    }
+
+    fn __str__(&self) -> String {
+        let solved = match self.solution {
+            Some(_) => "solved",
+            None => "not solved yet",
+        };
+
+        format!(
+            "{}x{} maze with {} walls, {solved} ({})",
+            self.width,
+            self.height,
+            self.walls.len(),
+            self.canonical_hash(false)
+        )
+    }
+
+    /// how many cells the maze has
+    fn __len__(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    /// goes over every cell's `(x, y)`, row by row from the top left
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        let (w, h) = (self.width, self.height);
+        let cells: Vec<Point> = (0..h).flat_map(|y| (0..w).map(move |x| (x, y))).collect();
+        PyIterator::from_object(PyList::new(py, cells))
+    }
+
+    /// the cell at `maze[x, y]`, negative coordinates count back from the right and bottom edges like they do for lists
+    ///
+    /// raises an `IndexError` if the cell is outside of the maze
+    fn __getitem__(slf: &PyCell<Self>, key: &PyAny) -> PyResult<Cell> {
+        let Ok(AnyPoint((x, y))) = key.extract() else {
+            let msg = format!(
+                "maze indices must be (x, y) tuples of ints; got {}",
+                key.repr()?
+            );
+            return Err(PyTypeError::new_err(msg));
+        };
+
+        let (w, h) = {
+            let maze = slf.borrow();
+            (maze.width, maze.height)
+        };
+
+        let xy = (if x < 0 { x + w } else { x }, if y < 0 { y + h } else { y });
+        if out_of_bounds(xy, w, h) {
+            let msg = format!("{:?} is outside of the {w}x{h} maze", (x, y));
+            return Err(PyIndexError::new_err(msg));
+        }
+
+        Ok(Cell::new(slf.into(), xy))
+    }
+
+    /// two mazes are equal if they're the same size and have the same walls, no matter how they look
+    /// or what's going on in them
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        let py = other.py();
+        let Ok(other) = other.extract::<PyRef<Self>>() else {
+            return py.NotImplemented();
+        };
+
+        let equal = (self.width, self.height) == (other.width, other.height)
+            && same_walls(&self.walls, &other.walls);
+        match op {
+            CompareOp::Eq => equal.into_py(py),
+            CompareOp::Ne => (!equal).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    /// hashes the size and walls like `.canonical_hash()`, so that equal mazes hash the same
+    ///
+    /// editing the walls of a maze changes its hash, so don't do that while it's in a set or used as a dict key
+    fn __hash__(&self) -> u64 {
+        structure_hash(&self.walls, self.width, self.height)
+    }
+
+    /// an independent copy of the maze, image and all (text drawn on it included), along with the player,
+    /// markers, trail, fog, and solution, so a game can be snapshotted before trying out some moves
+    /// or several players can start from the same maze
+    ///
+    /// nothing done to the copy affects the original, and the other way around
+    fn clone(&self) -> Self {
+        Self {
+            views: 0,
+            ..Clone::clone(self)
+        }
+    }
+
+    /// the same as `.clone()`, there's nothing shared between mazes for a shallow copy to share
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    #[pyo3(signature = (_memo, /))]
+    fn __deepcopy__(&self, _memo: &PyAny) -> Self {
+        self.clone()
+    }
+
+    /// pickles the maze as the bytes from `.to_bytes()` (loaded with `maze.from_bytes()`), plus everything
+    /// about how it looks from `.__getstate__()`, so the whole maze comes back other than text drawn on it
+    fn __reduce__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<(&'py PyAny, (&'py PyBytes,), &'py PyDict)> {
+        let from_bytes = py.import("maze")?.getattr("from_bytes")?;
+        Ok((from_bytes, (self.to_bytes(py),), self.__getstate__(py)?))
+    }
+
+    /// everything about how the maze looks that `.to_bytes()` leaves out, like its icons and images
+    fn __getstate__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        self.look().to_dict(py)
+    }
+
+    /// puts back what `.__getstate__()` returned, re-rendering the image if it's been built
+    fn __setstate__(&mut self, py: Python, state: &PyDict) -> PyResult<()> {
+        let look = Look::from_dict(state)?;
+        look.check(self.width, self.height)?;
+        let size = maze_image_size(self.width, self.height);
+        if look
+            .bg_image
+            .as_ref()
+            .is_some_and(|img| img.dimensions() != size)
+        {
+            let msg = "invalid saved maze: the background image isn't the same size as the maze";
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if look.headless && self.canvas.is_some() && self.views > 0 {
+            let msg = "the maze image can't be dropped while a buffer view of it is still alive";
+            return Err(PyBufferError::new_err(msg));
+        }
+
+        self.background.image = look.bg_image;
+        self.wall_fill.jitter = look.wall_jitter;
+        self.wall_fill.texture = look.wall_texture;
+        self.player_icon = look.player_icon;
+        self.player_sprites = look.player_sprites;
+        self.end_icon = look.end_icon;
+        self.trail_fade = look.trail_fade;
+        self.draw_style = look.draw_style;
+        self.frame = look.frame;
+        self.watermark = look.watermark;
+        self.fog_colour = look.fog_colour;
+        self.direction_labels = look.direction_labels;
+        self.markers = look.markers;
+        self.players = look.players;
+        self.chasers = look.chasers;
+        self.collected_items = look.collected_items;
+        self.carve_order = look.carve_order;
+        self.headless = look.headless;
+        if self.headless {
+            self.canvas = None;
+        }
+
+        self.redraw(py);
+        Ok(())
+    }
+
+    /// whether or not two points are blocked off by a wall
+    #[pyo3(signature = (a, b, /))]
+    fn has_wall_between(&self, a: AnyPoint, b: AnyPoint) -> bool {
+        let a = a.0;
+        let b = b.0;
+        let (w, h) = (self.width, self.height);
+        wall_between(&self.walls, a, b) || out_of_bounds(b, w, h) || out_of_bounds(a, w, h)
+    }
+
+    /// whether the player can take a single step from `xy` in `direction` (`UP`, `DOWN`, `LEFT`, or `RIGHT`),
+    /// which it can't if there's a wall in the way or it would step off the edge of the maze
+    #[pyo3(signature = (xy, direction, /))]
+    fn can_move(&self, xy: AnyPoint, direction: AnyDirection) -> PyResult<bool> {
+        let direction = direction.0;
+        if sprite_index(direction).is_none() {
+            let msg =
+                format!("expected UP, DOWN, LEFT, or RIGHT as a direction; got {direction:?}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        Ok(can_step(
+            &self.walls,
+            xy.0,
+            direction,
+            self.width,
+            self.height,
+        ))
+    }
+
+    /// every cell that can be seen from `xy` in a straight line up, down, left, or right,
+    /// stopping wherever a wall blocks the view
+    ///
+    /// `xy` itself is included first, followed by each direction's cells from nearest to furthest
+    #[pyo3(signature = (xy, /))]
+    fn visible_cells(&self, xy: AnyPoint) -> PyResult<Vec<Point>> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        Ok(line_of_sight(&self.walls, xy, self.width, self.height))
+    }
+
+    /// the directions (`UP`, `DOWN`, `LEFT`, or `RIGHT`) that the player can take a step in from `xy`, in that order
+    ///
+    /// a direction is left out if there's a wall that way, or if it leads off the edge of the maze
+    #[pyo3(signature = (xy, /))]
+    fn legal_moves(&self, xy: AnyPoint) -> PyResult<Vec<Direction>> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        Ok(Direction::open_from(
+            &self.walls,
+            xy,
+            self.width,
+            self.height,
+        ))
+    }
+
+    /// describes what can be seen from `xy` in plain English, so the maze can be played with a screen reader
+    ///
+    /// this covers which ways are open and how far until the next wall in each of them,
+    /// which ways are walled off, and whether the goal is in a straight line of sight
+    #[pyo3(signature = (xy, /))]
+    fn describe(&self, xy: AnyPoint) -> PyResult<String> {
+        let xy = xy.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(xy, w, h) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        Ok(describe_surroundings(&self.walls, xy, (w - 1, h - 1), w, h))
+    }
+
+    /// removes the player (if it exists) at an XY coodinate, or the player with the id `player` if it's given
+    ///
+    /// only the player layer gets wiped there, so whatever was underneath
+    /// (the solution path, the endzone, etc.) shows through again; any other players on the cell stay drawn
+    #[pyo3(signature = (xy, /, *, player = None))]
+    fn undraw_at(&mut self, xy: AnyPoint, player: Option<&str>) -> PyResult<()> {
+        let xy = xy.0;
+        match self.player_index(player)? {
+            None if self.player_pos == Some(xy) => self.player_pos = None,
+            Some(i) if self.players[i].pos == Some(xy) => self.players[i].pos = None,
+            _ => {}
+        }
+
+        self.redraw_players_at(xy);
+        Ok(())
+    }
+
+    /// draws a marker at an XY coordinate, for checkpoints, items, other players, etc.
+    ///
+    /// `image_or_colour` is either an RGB(A) tuple/list, which gets drawn as a dot in the middle of the cell,
+    /// or an icon in anything `generate_maze` takes for `player`; markers go above the solution path but under the player
+    #[pyo3(signature = (xy, image_or_colour, /, tag = None))]
+    fn draw_marker_at(
+        &mut self,
+        xy: AnyPoint,
+        image_or_colour: &PyAny,
+        tag: Option<String>,
+    ) -> PyResult<()> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        let icon = extract_marker(image_or_colour, self.draw_style.supersample)?;
+        if let Some(canvas) = &mut self.canvas {
+            let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
+            imageops::overlay(canvas.layers.layer_mut(Layer::Markers), &icon, x, y);
+            canvas.composite(Some(cell_rect(xy)));
+        }
+
+        self.markers.push(Marker { xy, tag, icon });
+        Ok(())
+    }
+
+    /// removes every marker with the given tag, and returns how many were removed
+    ///
+    /// any other markers sharing a cell with a removed one are kept
+    #[pyo3(signature = (tag, /))]
+    fn remove_marker(&mut self, tag: &str) -> usize {
+        let (removed, kept): (Vec<Marker>, Vec<Marker>) = std::mem::take(&mut self.markers)
+            .into_iter()
+            .partition(|m| m.tag.as_deref() == Some(tag));
+
+        self.markers = kept;
+        let cleared: HashSet<Point> = removed.iter().map(|m| m.xy).collect();
+        self.redraw_markers_at(&cleared);
+        removed.len()
+    }
+
+    /// adds another player with its own id and icon, for races and other games with more than one player,
+    /// drawing it at `xy` if it's given
+    ///
+    /// `icon` is anything `.draw_marker_at()` takes; passing `player=id` to `.move_max()`, `.move_step()`,
+    /// `.draw_player_at()`, `.undraw_at()`, and `.get_history()` then works with this player instead,
+    /// and players sharing a cell are drawn with whichever one moved last on top
+    #[pyo3(signature = (id, icon, /, xy = None))]
+    fn add_player(&mut self, id: String, icon: &PyAny, xy: Option<AnyPoint>) -> PyResult<()> {
+        if self.players.iter().any(|p| p.id == id) {
+            let msg = format!("there's already a player with the id {id:?}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let xy = xy.map(|xy| xy.0);
+        if let Some(xy) = xy.filter(|xy| out_of_bounds(*xy, self.width, self.height)) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        let icon = extract_marker(icon, self.draw_style.supersample)?;
+        self.players.push(Player {
+            id,
+            icon,
+            pos: None,
+            history: vec![],
+            drawn_at: 0,
+        });
+
+        if let Some(xy) = xy {
+            self.draw_player(Some(self.players.len() - 1), xy);
+        }
+
+        Ok(())
+    }
+
+    /// removes the player with the given id (undrawing it), and returns whether there was one
+    #[pyo3(signature = (id, /))]
+    fn remove_player(&mut self, id: &str) -> bool {
+        let Some(idx) = self.players.iter().position(|p| p.id == id) else {
+            return false;
+        };
+
+        let removed = self.players.remove(idx);
+        if let Some(xy) = removed.pos {
+            self.redraw_players_at(xy);
+        }
+
+        true
+    }
+
+    /// every player added with `.add_player()`, as a dict mapping its id to where it is (`None` if it isn't drawn)
+    #[getter]
+    fn players(&self) -> HashMap<String, Option<NamedPoint>> {
+        self.players
+            .iter()
+            .map(|p| (p.id.clone(), p.pos.map(NamedPoint::from)))
+            .collect()
+    }
+
+    /// adds a chaser with its own id and icon at `xy`, which walks towards a player every time `.move_chasers()`
+    /// is called, for pac-man style games
+    ///
+    /// `icon` is anything `.draw_marker_at()` takes, `target` is the id of the player it's after (`None` for the
+    /// player the maze was generated with), and `speed` is how many cells it walks each time
+    ///
+    /// chasers only ever walk one cell at a time along the walls as they are right then, so they don't go
+    /// through portals, press switches, or slide on ice
+    #[pyo3(signature = (id, icon, xy, /, *, target = None, speed = 1))]
+    fn add_chaser(
+        &mut self,
+        id: String,
+        icon: &PyAny,
+        xy: AnyPoint,
+        target: Option<String>,
+        speed: u32,
+    ) -> PyResult<()> {
+        if self.chasers.iter().any(|c| c.id == id) {
+            let msg = format!("there's already a chaser with the id {id:?}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        if speed == 0 {
+            return Err(PyValueError::new_err("speed must be at least 1"));
+        }
+
+        self.player_index(target.as_deref())?;
+        let icon = extract_marker(icon, self.draw_style.supersample)?;
+        self.draws += 1;
+        self.chasers.push(Chaser {
+            id,
+            icon,
+            pos: xy,
+            target,
+            speed,
+            drawn_at: self.draws,
+        });
+
+        self.redraw_players_at(xy);
+        Ok(())
+    }
+
+    /// removes the chaser with the given id (undrawing it), and returns whether there was one
+    #[pyo3(signature = (id, /))]
+    fn remove_chaser(&mut self, id: &str) -> bool {
+        let Some(idx) = self.chasers.iter().position(|c| c.id == id) else {
+            return false;
+        };
+
+        let removed = self.chasers.remove(idx);
+        self.redraw_players_at(removed.pos);
+        true
+    }
+
+    /// every chaser added with `.add_chaser()`, as a dict mapping its id to where it is
+    #[getter]
+    fn chasers(&self) -> HashMap<String, NamedPoint> {
+        self.chasers
+            .iter()
+            .map(|c| (c.id.clone(), c.pos.into()))
+            .collect()
+    }
+
+    /// walks every chaser up to its speed in cells along the shortest way to its target,
+    /// and returns the ids of the chasers that have caught their target
+    ///
+    /// the way is worked out again every time, so chasers keep up with players moving around and walls changing;
+    /// a chaser stops as soon as it reaches its target, and stays put if its target isn't drawn or it can't get there
+    fn move_chasers(&mut self) -> Vec<String> {
+        let mut caught = vec![];
+        for i in 0..self.chasers.len() {
+            let chaser = &self.chasers[i];
+            let Some(target) = self.chaser_target(chaser.target.as_deref()) else {
+                continue;
+            };
+
+            let (from, speed) = (chaser.pos, chaser.speed as usize);
+            let path = shortest_path(&self.walls, from, target, self.width, self.height)
+                .unwrap_or_default();
+            if let Some(&to) = path.iter().take(speed).next_back() {
+                self.draws += 1;
+                let chaser = &mut self.chasers[i];
+                (chaser.pos, chaser.drawn_at) = (to, self.draws);
+                self.redraw_players_at(from);
+                self.redraw_players_at(to);
+            }
+
+            if self.chasers[i].pos == target {
+                caught.push(self.chasers[i].id.clone());
+            }
+        }
+
+        caught
+    }
+
+    /// whether a chaser is on the same cell as the player it's after, either the player with the given id
+    /// (see `.add_player()`) or the player the maze was generated with
+    #[pyo3(signature = (*, player = None))]
+    fn caught(&self, player: Option<&str>) -> PyResult<bool> {
+        self.player_index(player)?;
+        let Some(xy) = self.chaser_target(player) else {
+            return Ok(false);
+        };
+
+        Ok(self
+            .chasers
+            .iter()
+            .any(|c| c.target.as_deref() == player && c.pos == xy))
+    }
+
+    /// scatters `n` collectible items over random cells that the player can get to from the start,
+    /// and returns where they went
+    ///
+    /// `icon` is anything `.draw_marker_at()` takes, and each item is a marker tagged `"item"`; the player picks one up
+    /// (erasing it from the image) by moving onto or over it, see `.items` and `.collected_items`
+    ///
+    /// any items placed before are replaced, and `ValueError` is raised if there aren't `n` cells free for them
+    /// (the start, the goal, where the player is, portals, and switches are left alone)
+    #[pyo3(signature = (n, icon, /))]
+    fn place_items(&mut self, n: usize, icon: &PyAny) -> PyResult<Vec<NamedPoint>> {
+        let (w, h) = (self.width, self.height);
+        let icon = extract_marker(icon, self.draw_style.supersample)?;
+        let mechanics = self.mechanics();
+        let mut free: Vec<Point> = mechanics
+            .reachable(&self.walls, (0, 0), w, h)
+            .into_iter()
+            .filter(|xy| *xy != (0, 0) && *xy != (w - 1, h - 1))
+            .filter(|xy| self.player_pos != Some(*xy) && !mechanics.occupied(*xy))
+            .collect();
+
+        if free.len() < n {
+            let msg = format!("only {} cells are free for items; got {n}", free.len());
+            return Err(PyValueError::new_err(msg));
+        }
+
+        self.remove_marker(ITEM_TAG);
+        self.collected_items.clear();
+
+        let random = RandomState::new();
+        free.sort_unstable_by_key(|xy| random.hash_one(xy));
+        free.truncate(n);
+        free.sort_unstable();
+
+        for &xy in &free {
+            let tag = Some(ITEM_TAG.to_string());
+            self.markers.push(Marker {
+                xy,
+                tag,
+                icon: icon.clone(),
+            });
+        }
+
+        self.redraw_markers_at(&free.iter().copied().collect());
+        Ok(free.into_iter().map(NamedPoint::from).collect())
+    }
+
+    /// a random cell that the player can get to from the start, for placing players fairly in multiplayer games
+    ///
+    /// `min_distance_from` is an `(xy, d)` pair, which only picks from cells at least `d` single steps away
+    /// from `xy` going around the walls; passing `rng_seed` always picks the same cell for the same maze and seed,
+    /// and a `ValueError` is raised if no cell is far enough away
+    #[pyo3(signature = (*, min_distance_from = None, rng_seed = None))]
+    fn random_cell(
+        &self,
+        min_distance_from: Option<(AnyPoint, usize)>,
+        rng_seed: Option<u64>,
+    ) -> PyResult<NamedPoint> {
+        let (w, h) = (self.width, self.height);
+        let mut cells: Vec<Point> = self
+            .mechanics()
+            .reachable(&self.walls, (0, 0), w, h)
+            .into_iter()
+            .collect();
+
+        if let Some((xy, d)) = min_distance_from {
+            let xy = xy.0;
+            if out_of_bounds(xy, w, h) {
+                return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+            }
+
+            let distances = step_distances(&self.walls, xy, w, h);
+            cells.retain(|cell| distances.get(cell).is_some_and(|steps| *steps >= d));
+            if cells.is_empty() {
+                let msg = format!("no cell is at least {d} steps away from {xy:?}");
+                return Err(PyValueError::new_err(msg));
+            }
+        }
+
+        let random = match rng_seed {
+            None => RandomState::new().hash_one(0),
+            Some(seed) => {
+                let mut hasher = DefaultHasher::new();
+                seed.hash(&mut hasher);
+                hasher.finish()
+            }
+        };
+
+        // sorted first so that the same seed picks the same cell, no matter what order they were found in
+        cells.sort_unstable();
+        Ok(cells[(random % cells.len() as u64) as usize].into())
+    }
+
+    /// every item that's yet to be picked up, in the order they were placed
+    #[getter]
+    fn items(&self) -> Vec<NamedPoint> {
+        self.markers
+            .iter()
+            .filter(|m| m.tag.as_deref() == Some(ITEM_TAG))
+            .map(|m| m.xy.into())
+            .collect()
+    }
+
+    /// where every item the player picked up was, in the order they were picked up
+    #[getter]
+    fn collected_items(&self) -> Vec<NamedPoint> {
+        self.collected_items.iter().map(|&xy| xy.into()).collect()
+    }
+
+    /// links two cells as a portal, stepping onto either one (while moving, or in the solver) puts the player on the other
+    ///
+    /// both ends get a ring drawn around them, in `colour` if it's given (otherwise the next of a few that go round);
+    /// the start, the goal, and cells that are already part of a portal or are a switch can't be used
+    #[pyo3(signature = (a, b, /, colour = None))]
+    fn add_portal(
+        &mut self,
+        py: Python,
+        a: AnyPoint,
+        b: AnyPoint,
+        colour: Option<Colour>,
+    ) -> PyResult<()> {
+        let (a, b) = (a.0, b.0);
+        let (w, h) = (self.width, self.height);
+        if let Some(xy) = [a, b].into_iter().find(|xy| out_of_bounds(*xy, w, h)) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        if a == b {
+            return Err(PyValueError::new_err("a portal can't lead to itself"));
+        }
+
+        let taken = self.mechanics();
+        for xy in [a, b] {
+            if xy == (0, 0) || xy == (w - 1, h - 1) {
+                let msg = format!("{xy:?} is the start or the goal, so it can't be a portal");
+                return Err(PyValueError::new_err(msg));
+            }
+
+            if taken.occupied(xy) {
+                let msg = format!("{xy:?} is already a portal or a switch");
+                return Err(PyValueError::new_err(msg));
+            }
+        }
+
+        let colour = colour.map_or(
+            PORTAL_COLOURS[self.portals.len() % PORTAL_COLOURS.len()],
+            Pxl::from,
+        );
+        self.portals.push(Portal {
+            ends: (a, b),
+            colour,
+        });
+        self.redraw_markers_at(&HashSet::from([a, b]));
+        self.mechanics_changed(py);
+        Ok(())
+    }
+
+    /// removes the portal that `xy` is one end of, and returns whether there was one
+    #[pyo3(signature = (xy, /))]
+    fn remove_portal(&mut self, py: Python, xy: AnyPoint) -> bool {
+        let xy = xy.0;
+        let Some(idx) = self
+            .portals
+            .iter()
+            .position(|p| p.ends.0 == xy || p.ends.1 == xy)
+        else {
+            return false;
+        };
+
+        let removed = self.portals.remove(idx);
+        self.redraw_markers_at(&HashSet::from([removed.ends.0, removed.ends.1]));
+        self.mechanics_changed(py);
+        true
+    }
+
+    /// every portal as an `(a, b)` tuple of the two cells it links, in the order they were added
+    #[getter]
+    fn portals(&self) -> Vec<(Point, Point)> {
+        self.portals.iter().map(|p| p.ends).collect()
+    }
+
+    /// makes `xy` a switch, which toggles each of `walls` (a list of `(a, b)` pairs of neighbouring cells)
+    /// whenever the player steps onto it, opening the ones that are closed and closing the ones that are open
+    ///
+    /// the switch gets a plate drawn on it, in `colour` if it's given; a slide stops on a switch, just like on a portal,
+    /// and the solver works out when to press them; the start, the goal, portals, and other switches can't be used,
+    /// and there can be up to 8 switches
+    #[pyo3(signature = (xy, walls, /, colour = None))]
+    fn add_switch(
+        &mut self,
+        py: Python,
+        xy: AnyPoint,
+        walls: Vec<(AnyPoint, AnyPoint)>,
+        colour: Option<Colour>,
+    ) -> PyResult<()> {
+        let xy = xy.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(xy, w, h) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        if xy == (0, 0) || xy == (w - 1, h - 1) {
+            let msg = format!("{xy:?} is the start or the goal, so it can't be a switch");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if self.mechanics().occupied(xy) {
+            let msg = format!("{xy:?} is already a portal or a switch");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if self.switches.len() == MAX_SWITCHES {
+            let msg = format!("a maze can't have more than {MAX_SWITCHES} switches");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let mut toggled = vec![];
+        for (a, b) in walls {
+            check_neighbours(a.0, b.0, w, h)?;
+            let edge = ordered_edge((a.0, b.0));
+            if !toggled.contains(&edge) {
+                toggled.push(edge);
+            }
+        }
+
+        self.switches.push(Switch {
+            xy,
+            walls: toggled,
+            colour: colour.map_or(SWITCH_COLOUR, Pxl::from),
+        });
+        self.redraw_markers_at(&HashSet::from([xy]));
+        self.mechanics_changed(py);
+        Ok(())
+    }
+
+    /// removes the switch at `xy`, and returns whether there was one
+    ///
+    /// the walls it toggles are left the way they are
+    #[pyo3(signature = (xy, /))]
+    fn remove_switch(&mut self, py: Python, xy: AnyPoint) -> bool {
+        let xy = xy.0;
+        let Some(idx) = self.switches.iter().position(|s| s.xy == xy) else {
+            return false;
+        };
+
+        self.switches.remove(idx);
+        self.redraw_markers_at(&HashSet::from([xy]));
+        self.mechanics_changed(py);
+        true
+    }
+
+    /// every switch, as a dict mapping its cell to the `(a, b)` walls it toggles
+    #[getter]
+    fn switches(&self) -> HashMap<Point, EdgeVec> {
+        self.switches
+            .iter()
+            .map(|s| (s.xy, s.walls.clone()))
+            .collect()
+    }
+
+    /// adds a checkpoint at `xy`, which gets a flag drawn on it; the player has to reach the checkpoints in the order
+    /// they were added, and each one's flag fades out once it's been reached (see `.checkpoint_splits`)
+    ///
+    /// the start and the goal can't be checkpoints, and neither can a cell that already is one
+    #[pyo3(signature = (xy, /))]
+    fn add_checkpoint(&mut self, xy: AnyPoint) -> PyResult<()> {
+        let xy = xy.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(xy, w, h) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        if xy == (0, 0) || xy == (w - 1, h - 1) {
+            let msg = format!("{xy:?} is the start or the goal, so it can't be a checkpoint");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if self.checkpoints.contains(&xy) {
+            return Err(PyValueError::new_err(format!(
+                "{xy:?} is already a checkpoint"
+            )));
+        }
+
+        self.checkpoints.push(xy);
+        self.redraw_markers_at(&HashSet::from([xy]));
+        Ok(())
+    }
+
+    /// removes the checkpoint at `xy` (erasing its flag), and returns whether there was one
+    ///
+    /// if the player had already reached it, it's forgotten from `.checkpoint_splits` too
+    #[pyo3(signature = (xy, /))]
+    fn remove_checkpoint(&mut self, xy: AnyPoint) -> bool {
+        let xy = xy.0;
+        let Some(idx) = self.checkpoints.iter().position(|c| *c == xy) else {
+            return false;
+        };
+
+        self.checkpoints.remove(idx);
+        if idx < self.checkpoints_reached.len() {
+            self.checkpoints_reached.remove(idx);
+        }
+
+        // the ones after it might've just been moved up into the reached ones, or out of them
+        let cells = self.checkpoints[idx..]
+            .iter()
+            .copied()
+            .chain([xy])
+            .collect();
+        self.redraw_markers_at(&cells);
+        true
+    }
+
+    /// every checkpoint, in the order the player has to reach them
+    #[getter]
+    fn checkpoints(&self) -> Vec<NamedPoint> {
+        self.checkpoints.iter().map(|xy| (*xy).into()).collect()
+    }
+
+    /// the checkpoints the player has reached so far, in order
+    #[getter]
+    fn reached_checkpoints(&self) -> Vec<NamedPoint> {
+        self.checkpoints[..self.checkpoints_reached.len()]
+            .iter()
+            .map(|xy| (*xy).into())
+            .collect()
+    }
+
+    /// how many moves the player took to get from the start to the first checkpoint, then from there to the next,
+    /// and so on, for timing runs and leaderboards
+    ///
+    /// there's one for every checkpoint reached so far, plus one more from the last checkpoint to the goal
+    /// once the player is on the goal having reached all of them; moves are counted from `.get_history()`,
+    /// so `.clear_history()` starts the splits over too
+    #[getter]
+    fn checkpoint_splits(&self) -> Vec<usize> {
+        let (w, h) = (self.width, self.height);
+        let finished = self.checkpoints_reached.len() == self.checkpoints.len()
+            && self.player_pos == Some((w - 1, h - 1));
+        let ends = self
+            .checkpoints_reached
+            .iter()
+            .copied()
+            .chain(finished.then_some(self.history.len()));
+
+        let mut last = 0;
+        ends.map(|end| end - std::mem::replace(&mut last, end))
+            .collect()
+    }
+
+    /// sets what the ground of a cell is like, one of `"normal"`, `"ice"`, or `"mud"`
+    ///
+    /// stepping onto ice sends the player sliding until something stops them, like `.move_max()` does,
+    /// and stepping onto mud costs 2 moves instead of 1; both change how moves are played back and solved,
+    /// and get a tint drawn over the cell
+    #[pyo3(signature = (xy, terrain, /))]
+    fn set_terrain(&mut self, py: Python, xy: AnyPoint, terrain: &str) -> PyResult<()> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        let Some(terrain) = Terrain::from_name(terrain) else {
+            let msg = format!(
+                "expected one of \"normal\", \"ice\", or \"mud\" as terrain; got {terrain:?}"
+            );
+            return Err(PyValueError::new_err(msg));
+        };
+
+        let old = match terrain {
+            Terrain::Normal => self.terrain.remove(&xy),
+            _ => self.terrain.insert(xy, terrain),
+        };
+
+        if old.unwrap_or_default() != terrain {
+            self.draw_terrain_at(xy);
+            self.composite(py, Some(cell_rect(xy)));
+            self.mechanics_changed(py);
+        }
+
+        Ok(())
+    }
+
+    /// what the ground of a cell is like, `"normal"`, `"ice"`, or `"mud"`
+    #[pyo3(signature = (xy, /))]
+    fn terrain_at(&self, xy: AnyPoint) -> PyResult<&'static str> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        Ok(self.terrain.get(&xy).copied().unwrap_or_default().name())
+    }
+
+    /// every cell that isn't normal ground, as a dict mapping it to `"ice"` or `"mud"`
+    #[getter]
+    fn terrain(&self) -> HashMap<Point, &'static str> {
+        self.terrain.iter().map(|(xy, t)| (*xy, t.name())).collect()
+    }
+
+    /// writes text centred on a cell, with newlines stacking lines on top of each other
+    ///
+    /// `size` is the height of a line in pixels, text that's too big for the cell spills over into its neighbours
+    #[pyo3(signature = (xy, text, colour, size = 18.0))]
+    fn draw_text_at(
+        &mut self,
+        py: Python,
+        xy: AnyPoint,
+        text: &str,
+        colour: Colour,
+        size: f32,
+    ) -> PyResult<()> {
+        let xy = xy.0;
+        let colour = colour.into();
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        if !(size > 0.0 && size.is_finite()) {
+            let msg = format!("text size must be a positive number; got {size}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let canvas = self.rendered(py)?;
+        let covered = draw_cell_text(canvas.layers.layer_mut(Layer::Text), xy, text, colour, size);
+        canvas.composite(Some(covered));
+        Ok(())
+    }
+
+    /// labels the column numbers along the top of the maze and the row numbers down its left side,
+    /// so spectators can follow along with coordinates
+    ///
+    /// with `cell_indices`, every cell also gets its index (`y * width + x`) written in the middle of it
+    #[pyo3(signature = (colour, *, size = 12.0, cell_indices = false))]
+    fn draw_coordinate_labels(
+        &mut self,
+        py: Python,
+        colour: Colour,
+        size: f32,
+        cell_indices: bool,
+    ) -> PyResult<()> {
+        let colour = colour.into();
+        if !(size > 0.0 && size.is_finite()) {
+            let msg = format!("text size must be a positive number; got {size}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let (w, h) = (self.width, self.height);
+        let layer = self.rendered(py)?.layers.layer_mut(Layer::Text);
+        py.allow_threads(|| {
+            draw_coordinate_labels(layer, colour, size, w, h);
+            if cell_indices {
+                draw_cell_indices(layer, colour, size, w, h);
+            }
+        });
+
+        self.composite(py, None);
+        Ok(())
+    }
+
+    /// draws the player at a given XY coordinate, or the player with the id `player` if it's given,
+    /// on top of any other players already there
+    ///
+    /// if the maze was generated with a `player_facing` mode other than `"fixed"`,
+    /// `direction` turns the player to face that way (otherwise it keeps facing its last direction);
+    /// players added with `.add_player()` only have the one icon, so they don't turn
+    #[pyo3(signature = (xy, /, direction = None, *, player = None))]
+    fn draw_player_at(
+        &mut self,
+        xy: AnyPoint,
+        direction: Option<AnyDirection>,
+        player: Option<&str>,
+    ) -> PyResult<()> {
+        let xy = xy.0;
+        let who = self.player_index(player)?;
+        if let Some(AnyDirection(direction)) = direction {
+            if sprite_index(direction).is_none() {
+                let msg =
+                    format!("expected UP, DOWN, LEFT, or RIGHT as a direction; got {direction:?}");
+                return Err(PyValueError::new_err(msg));
+            }
+
+            if who.is_none() {
+                self.player_facing = direction;
+            }
+        }
+
+        self.draw_player(who, xy);
+        Ok(())
+    }
+
+    /// moves a player straight to `xy`, for power-ups and fixing up a game by hand, where `player` is the id
+    /// of a player added with `.add_player()`, or `None` for the player the maze was generated with
+    ///
+    /// the player has to be drawn on the maze already, and the jump is recorded in its history like any other move;
+    /// it picks up an item or reaches a checkpoint at `xy` like it walked there, but it doesn't leave a trail,
+    /// go through a portal, or press a switch on the way
+    #[pyo3(signature = (player, xy, /))]
+    fn teleport_player(&mut self, player: Option<&str>, xy: AnyPoint) -> PyResult<()> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        let who = self.player_index(player)?;
+        let current = match who {
+            None => self.player_pos,
+            Some(i) => self.players[i].pos,
+        };
+        let Some(old) = current else {
+            let msg =
+                "the player isn't on the maze, use `.draw_player_at()` to put it somewhere first";
+            return Err(PyValueError::new_err(msg));
+        };
+
+        match who {
+            None => {
+                self.player_pos = None;
+                self.history.push((old, xy));
+            }
+            Some(i) => {
+                self.players[i].pos = None;
+                self.players[i].history.push((old, xy));
+            }
+        }
+
+        self.redraw_players_at(old);
+        self.pick_up_items(&[xy]);
+        self.draw_player(who, xy);
+        if who.is_none() {
+            self.reach_checkpoints(&[xy]);
+        }
+
+        Ok(())
+    }
+
+    /// determines the solution to the maze, along with a set of "perfect moves"
+    ///
+    /// on the Discord bot, there is a button to move the furthest distance possible in a direction
+    /// this will count the moves in a solution, with the above condition in mind
+    ///
+    /// this will store the solution in an internal field, and also return it
+    /// so that a separate `.get_solution_expensively()` call isn't needed right after
+    ///
+    /// raises `SolutionNotFound` if walls have been added that cut the goal off from the start
+    #[pyo3(signature = (*, draw_path))]
+    fn compute_solution(&mut self, py: Python, draw_path: bool) -> PyResult<Solution> {
+        self.solve(py, draw_path)?;
+        self.get_solution_expensively(py)
+    }
+
+    /// returns the maze's solution if one has already been determined, otherwise raise `SolutionNotFound`
+    ///
+    /// the solution is a `Solution` object holding how many moves a "perfect run" would take,
+    /// a list of human-readable directions (e.g "2 right", "3 left"), and the cells along the path
+    ///
+    /// this call clones a Rust object and converts it to Python,
+    /// which introduces a significant amount of overhead (use it sparingly!)
+    ///
+    /// if the walls have changed since the solution was determined, it's recomputed first
+    fn get_solution_expensively(&mut self, py: Python) -> PyResult<Solution> {
+        self.refresh_solution(py)?;
+        let data = self.cached_solution()?;
+        Ok(Solution::new(data, &self.direction_labels))
+    }
+
+    /// returns the maze's perfect run as a list of `(direction, amount, is_max)` tuples,
+    /// raising `SolutionNotFound` if the solution hasn't been determined yet
+    ///
+    /// `direction` is one of the exported `UP`, `DOWN`, `LEFT`, or `RIGHT` constants,
+    /// and `amount` is how many cells the move covers (max moves always count as 1)
+    ///
+    /// useful for rendering the moves in a different way than `.get_solution_expensively()` does
+    fn get_moves_expensively(&mut self, py: Python) -> PyResult<Vec<MoveTuple>> {
+        self.refresh_solution(py)?;
+        let data = self.cached_solution()?;
+        Ok(data.moves.iter().map(Move::as_tuple).collect())
+    }
+
+    /// works out a route from the start that picks up every item still on the maze (see `.place_items()`)
+    /// and then ends on the goal, returning a tuple `(moves, move_count)`
+    ///
+    /// `moves` are the same `(direction, amount, is_max)` tuples that `.get_moves_expensively()` returns;
+    /// with up to 8 items the route is the shortest there is, while with more it heads for the closest item
+    /// each time, which is near the best but isn't always it
+    ///
+    /// items on the start are already picked up by the time the route leaves it, so they don't take any moves
+    ///
+    /// raises `SolutionNotFound` if one of the items or the goal can't be reached
+    fn solve_collect_all(&self, py: Python) -> PyResult<(Vec<MoveTuple>, i32)> {
+        let (w, h) = (self.width, self.height);
+        let items: Vec<Point> = self
+            .markers
+            .iter()
+            .filter(|m| m.tag.as_deref() == Some(ITEM_TAG))
+            .map(|m| m.xy)
+            .collect();
+
+        let (walls, mechanics) = (&self.walls, self.mechanics());
+        let solved = py.allow_threads(|| collect_all_solution(walls, &mechanics, &items, w, h));
+        let Some((move_count, moves, _)) = solved else {
+            let msg = "an item or the goal can't be reached from the start";
+            return Err(SolutionNotFound::new_err(msg));
+        };
+
+        Ok((moves.iter().map(Move::as_tuple).collect(), move_count))
+    }
+
+    /// returns the solution's human-readable directions split into newline-separated chunks
+    /// of at most `max_len` characters, raising `SolutionNotFound` if it hasn't been determined yet
+    ///
+    /// chunks are only split on line boundaries, so each one can be sent as its own Discord message
+    #[pyo3(signature = (*, max_len = 2000))]
+    fn get_directions_chunked(&mut self, py: Python, max_len: usize) -> PyResult<Vec<String>> {
+        if max_len == 0 {
+            return Err(PyValueError::new_err("max_len must be greater than 0"));
+        }
+
+        self.refresh_solution(py)?;
+        let data = self.cached_solution()?;
+        let directions = describe_moves(&data.moves, &self.direction_labels);
+        Ok(chunk_lines(&directions, max_len))
+    }
+
+    /// overrides the templates used for the human-readable directions of the solution
+    ///
+    /// takes a dict of keys (`"up"`, `"max_up"`, `"down"`, `"max_down"`, etc.) to templates,
+    /// any `{amount}` in a template is replaced with the amount of cells the move covers;
+    /// directions left out of the dict keep their default label, and passing `None` resets all of them
+    ///
+    /// with `ascii` set, the defaults are plain-ASCII (e.g "2 right", "MAX up") instead of using arrows
+    #[pyo3(signature = (labels, /, *, ascii = false))]
+    fn set_direction_labels(&mut self, labels: Option<&PyDict>, ascii: bool) -> PyResult<()> {
+        self.direction_labels = extract_direction_labels(labels, ascii)?;
+        Ok(())
+    }
+
+    /// plays back a sequence of moves from a starting point, returning a tuple `(legal, reaches_goal)`
+    ///
+    /// moves are `(direction, amount, is_max)` tuples, the same as `.get_moves_expensively()` returns;
+    /// max moves slide until there's a wall in the way, and any other move is illegal if a wall blocks it
+    ///
+    /// `reaches_goal` is whether the run ends on the bottom-right corner of the maze
+    #[pyo3(signature = (start, moves, /))]
+    fn validate_moves(&self, start: AnyPoint, moves: Vec<MoveArg>) -> PyResult<(bool, bool)> {
+        let start = start.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(start, w, h) {
+            return Err(PyValueError::new_err(format!(
+                "start {start:?} is out of bounds"
+            )));
+        }
+
+        let moves = extract_moves(moves)?;
+        let playback = play_moves(&self.walls, &self.mechanics(), start, &moves, w, h);
+        let legal = playback.collided_at.is_none();
+
+        Ok((legal, legal && playback.position == (w - 1, h - 1)))
+    }
+
+    /// plays back a sequence of moves from a starting point,
+    /// returning a tuple `(position, visited, collided_at)`
+    ///
+    /// `position` is where the run ended up, and `visited` is every cell it passed through in order
+    /// (including the starting point); moves are the same `(direction, amount, is_max)` tuples
+    /// that `.validate_moves()` takes
+    ///
+    /// if a move runs into a wall, playback stops right there and `collided_at` is that move's index,
+    /// otherwise it's `None`
+    #[pyo3(signature = (start, moves, /))]
+    fn simulate(&self, start: AnyPoint, moves: Vec<MoveArg>) -> PyResult<SimulationTuple> {
+        let start = start.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(start, w, h) {
+            return Err(PyValueError::new_err(format!(
+                "start {start:?} is out of bounds"
+            )));
+        }
+
+        let moves = extract_moves(moves)?;
+        let playback = play_moves(&self.walls, &self.mechanics(), start, &moves, w, h);
+
+        Ok((playback.position, playback.visited, playback.collided_at))
+    }
+
+    /// grades a run from the start of the maze against the perfect run,
+    /// returning a tuple `(efficiency, wasted_moves)`
+    ///
+    /// `efficiency` is a percentage of how close the run's move count is to the perfect run's,
+    /// and `wasted_moves` is how many more moves it took; max moves count as 1 and steps into mud count as 2,
+    /// like in the perfect run
+    ///
+    /// the solution is computed first if it hasn't been yet, and runs that are illegal or
+    /// don't end at the goal raise `ValueError`
+    #[pyo3(signature = (moves, /))]
+    fn grade_run(&mut self, py: Python, moves: Vec<MoveArg>) -> PyResult<(f64, i32)> {
+        let (w, h) = (self.width, self.height);
+        let moves = extract_moves(moves)?;
+        let playback = play_moves(&self.walls, &self.mechanics(), (0, 0), &moves, w, h);
+        if let Some(idx) = playback.collided_at {
+            let msg = format!("move {idx} runs into a wall");
+            return Err(PyValueError::new_err(msg));
+        } else if playback.position != (w - 1, h - 1) {
+            return Err(PyValueError::new_err("the run doesn't end at the goal"));
+        }
+
+        self.refresh_solution(py)?;
+        let perfect = match self.solution {
+            Some(ref data) => data.move_count,
+            None => self.solve(py, false)?.move_count,
+        };
+
+        let taken = playback.cost;
+        // a 1x1 maze starts on the goal, so there's nothing to be inefficient about
+        let efficiency = match perfect {
+            0 => 100.0,
+            _ => f64::from(perfect) / f64::from(taken.max(perfect)) * 100.0,
+        };
+
+        Ok((efficiency, (taken - perfect).max(0)))
+    }
+
+    /// builds the maze image right away, instead of when something first needs it
+    ///
+    /// mazes don't render anything until they're drawn on or exported, so that ones which only get
+    /// solved and thrown away never pay for an image; this gets the work out of the way up front,
+    /// e.g before a game starts, and does nothing if it's already been done
+    ///
+    /// raises `RuntimeError` if the maze was generated with `render=False`
+    fn render(&mut self, py: Python) -> PyResult<()> {
+        self.rendered(py)?;
+        Ok(())
+    }
+
+    /// clones the maze image into a `io.BytesIO` buffer in Python
+    ///
+    /// `format` can be one of `"png"`, `"jpeg"` (with a `quality` from 1 to 100), `"webp"`
+    /// (always lossless, and usually much smaller than PNG), or `"bmp"`
+    ///
+    /// PNGs can trade encoding time for file size with `png_compression` (`"fast"`, `"default"`,
+    /// or `"best"`) and `png_filter` (`"none"`, `"sub"`, `"up"`, `"avg"`, `"paeth"`, or `"adaptive"`)
+    ///
+    /// `scale` resizes the image before it's encoded (using `scale_filter`, one of `"nearest"`, `"triangle"`
+    /// or `"bilinear"`, `"catmullrom"` or `"bicubic"`, `"gaussian"`, or `"lanczos3"`),
+    /// for blowing up tiny mazes or shrinking huge ones
+    ///
+    /// the border and margin the maze was generated with (if any) get put around the image first,
+    /// and the watermark (if one's set) is stamped on last, they're only ever part of exported images
+    /// and not the buffer the maze exposes
+    ///
+    /// `crop` cuts the image down to just some of the cells, either an `(x, y, width, height)` rectangle of them,
+    /// or the smallest rectangle that fits every cell in a list of `(x, y)` cells (the border isn't included)
+    ///
+    /// `projection` can be `"isometric"` to export a 2.5D view instead, with the walls extruded into blocks
+    /// and the player (if it's been drawn) and endzone icons standing on the floor; markers, text, and the
+    /// solution aren't part of it, and neither is the border
+    ///
+    /// this call clones a Rust object and converts it to Python,
+    /// which introduces a significant amount of overhead (use it sparingly!)
+    #[pyo3(signature = (*, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive", scale = 1.0, scale_filter = "lanczos3", projection = "top-down", crop = None))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    fn get_image_expensively<'py>(
+        &mut self,
+        py: Python<'py>,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+        scale: f32,
+        scale_filter: &str,
+        projection: &str,
+        crop: Option<&PyAny>,
+    ) -> PyResult<&'py PyAny> {
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let img = self.exported_image(py, projection, crop, scale, scale_filter)?;
+        match py.allow_threads(|| encode_to_vec(&img, format)) {
+            Ok(buf) => into_bytes_io(py, buf),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// copies the maze image into a `(height, width, 4)` numpy array of `uint8` RGBA values
+    ///
+    /// numpy has to be installed for this; to wrap the image without copying it,
+    /// `numpy.asarray(maze)` works too, since the maze supports the buffer protocol
+    fn image_array(slf: &PyCell<Self>) -> PyResult<&PyAny> {
+        let numpy = slf.py().import("numpy")?;
+        numpy.getattr("array")?.call1((slf,))
+    }
+
+    /// builds a `PIL.Image.Image` in RGBA mode from a copy of the maze image
+    ///
+    /// Pillow has to be installed for this
+    #[allow(clippy::wrong_self_convention)] // `&mut` since the image might not have been rendered yet
+    fn to_pil<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        let pil_image = py.import("PIL.Image")?;
+        let img = &self.rendered(py)?.image;
+        let size = (img.width(), img.height());
+        let raw = PyBytes::new(py, img.as_raw());
+        pil_image.call_method1("frombytes", ("RGBA", size, raw))
+    }
+
+    /// the walls as two boolean numpy arrays, `(horizontal, vertical)`
+    ///
+    /// `horizontal[y, x]` is the wall between `(x, y)` and `(x, y + 1)`, and
+    /// `vertical[y, x]` is the wall between `(x, y)` and `(x + 1, y)`, the outer border is left out
+    fn wall_arrays<'py>(&self, py: Python<'py>) -> PyResult<(&'py PyAny, &'py PyAny)> {
+        let numpy = py.import("numpy")?;
+        let array = numpy.getattr("array")?;
+        let kwargs = [("dtype", "bool")].into_py_dict(py);
+        let (horizontal, vertical) = wall_grids(&self.walls, self.width, self.height);
+
+        let horizontal = array.call((horizontal,), Some(kwargs))?;
+        let vertical = array.call((vertical,), Some(kwargs))?;
+        Ok((horizontal, vertical))
+    }
+
+    /// the maze as a graph in compressed sparse row form, two `int32` numpy arrays `(indptr, indices)`
+    /// with cell `(x, y)` as node `y * width + x`, to build a `scipy.sparse` matrix without any per-edge Python objects:
+    ///
+    /// `scipy.sparse.csr_array((numpy.ones(len(indices)), indices, indptr), shape=(n, n))`
+    ///
+    /// numpy has to be installed for this
+    fn to_csr<'py>(&self, py: Python<'py>) -> PyResult<(&'py PyAny, &'py PyAny)> {
+        let frombuffer = py.import("numpy")?.getattr("frombuffer")?;
+        let kwargs = [("dtype", "int32")].into_py_dict(py);
+        let (indptr, indices) =
+            py.allow_threads(|| csr_adjacency(&self.walls, self.width, self.height));
+
+        let to_array = |values: Vec<i32>| {
+            let raw: Vec<u8> = values.into_iter().flat_map(i32::to_ne_bytes).collect();
+            // copied so the arrays are writable, instead of read-only views of the bytes
+            frombuffer
+                .call((PyBytes::new(py, &raw),), Some(kwargs))?
+                .call_method0("copy")
+        };
+
+        Ok((to_array(indptr)?, to_array(indices)?))
+    }
+
+    /// encodes the maze image into a `bytes` object, skipping the `io.BytesIO` wrapping
+    /// that `.get_image_expensively()` does (and the extra Python calls and copies that come with it)
+    ///
+    /// takes the same format and options as `.get_image_expensively()`
+    #[pyo3(signature = (*, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive", scale = 1.0, scale_filter = "lanczos3", projection = "top-down", crop = None))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    fn get_image_bytes<'py>(
+        &mut self,
+        py: Python<'py>,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+        scale: f32,
+        scale_filter: &str,
+        projection: &str,
+        crop: Option<&PyAny>,
+    ) -> PyResult<&'py PyBytes> {
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let img = self.exported_image(py, projection, crop, scale, scale_filter)?;
+        match py.allow_threads(|| encode_to_vec(&img, format)) {
+            Ok(buf) => Ok(PyBytes::new(py, &buf)),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// how many `(columns, rows)` of `tile_size` pixel square tiles it takes to cover the maze image
+    ///
+    /// a tile can't be any bigger than the longer side of the image (or the default of 256, for smaller images),
+    /// since a single one would already cover all of it
+    #[pyo3(signature = (tile_size = 256))]
+    fn tile_grid(&self, tile_size: u32) -> PyResult<(u32, u32)> {
+        let (w, h) = maze_image_size(self.width, self.height);
+        let largest = w.max(h).max(256);
+        if !(1..=largest).contains(&tile_size) {
+            let msg = format!("tile size must be between 1 and {largest}; got {tile_size}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        Ok(tile_grid((w, h), tile_size))
+    }
+
+    /// encodes a single `tile_size` pixel square tile of the maze image into a `bytes` object,
+    /// so that huge mazes can be served a piece at a time (only encoding the tiles that are actually looked at)
+    ///
+    /// `col` and `row` count tiles from the top left (see `.tile_grid()` for how many there are);
+    /// tiles on the right and bottom edges are padded out with transparent pixels
+    ///
+    /// takes the same format and options as `.get_image_expensively()`
+    #[pyo3(signature = (col, row, /, *, tile_size = 256, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive"))]
+    #[allow(clippy::too_many_arguments)] // all but `col` and `row` are keyword-only in Python
+    fn render_tile<'py>(
+        &mut self,
+        py: Python<'py>,
+        col: u32,
+        row: u32,
+        tile_size: u32,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+    ) -> PyResult<&'py PyBytes> {
+        let (cols, rows) = self.tile_grid(tile_size)?;
+        if col >= cols || row >= rows {
+            let msg =
+                format!("tile ({col}, {row}) is out of bounds, there are {cols}x{rows} tiles");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let img = &self.rendered(py)?.image;
+        let encoded = py.allow_threads(|| {
+            let tile = cut_tile(img, col, row, tile_size);
+            encode_to_vec(&tile, format)
+        });
+
+        match encoded {
+            Ok(buf) => Ok(PyBytes::new(py, &buf)),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// renders a first-person view from `xy` looking `facing` (`UP`, `DOWN`, `LEFT`, or `RIGHT`),
+    /// a pseudo-3D look down the corridors for exploring the maze from the inside,
+    /// and encodes it into a `bytes` object
+    ///
+    /// the view is `width` by `height` pixels and takes in `fov` degrees horizontally;
+    /// walls are drawn in the maze's wall colour (fading into the background with distance),
+    /// except the goal's, which are in the solution colour so it can be spotted
+    ///
+    /// takes the same format and options as `.get_image_expensively()`
+    #[pyo3(signature = (xy, facing, /, *, width = 320, height = 200, fov = 66.0, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive"))]
+    #[allow(clippy::too_many_arguments)] // all but `xy` and `facing` are keyword-only in Python
+    fn render_first_person<'py>(
+        &self,
+        py: Python<'py>,
+        xy: AnyPoint,
+        facing: AnyDirection,
+        width: u32,
+        height: u32,
+        fov: f32,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+    ) -> PyResult<&'py PyBytes> {
+        let xy = xy.0;
+        if out_of_bounds(xy, self.width, self.height) {
+            return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+        }
+
+        let facing = facing.0;
+        if sprite_index(facing).is_none() {
+            let msg = format!("expected UP, DOWN, LEFT, or RIGHT as a direction; got {facing:?}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if !(1..=4096).contains(&width) || !(1..=4096).contains(&height) {
+            let msg = format!(
+                "views must be between 1 and 4096 pixels on each side; got {width}x{height}"
+            );
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if !(fov > 0.0 && fov < 180.0) {
+            let msg = format!("fov must be between 0 and 180 degrees; got {fov}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let camera = Camera { xy, facing, fov };
+        let colours = ViewColours {
+            bg: average_colour(&self.background),
+            wall: self.wall_fill.colour,
+            goal: self.solution_colour,
+        };
+
+        let (w, h) = (self.width, self.height);
+        let goal = (w - 1, h - 1);
+        let encoded = py.allow_threads(|| {
+            let view = first_person_view(&self.walls, camera, colours, goal, (width, height), w, h);
+            encode_to_vec(&view, format)
+        });
+
+        match encoded {
+            Ok(buf) => Ok(PyBytes::new(py, &buf)),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// encodes the maze image and writes it straight to a file at `path`
+    ///
+    /// `format` is guessed from the file extension if it isn't given, and takes the same values
+    /// (along with the other options) as `.get_image_expensively()`
+    #[pyo3(signature = (path, format = None, *, quality = 80, png_compression = "fast", png_filter = "adaptive", scale = 1.0, scale_filter = "lanczos3", projection = "top-down", crop = None))]
+    #[allow(clippy::too_many_arguments)] // all but `path` and `format` are keyword-only in Python
+    fn save(
+        &mut self,
+        py: Python,
+        path: PathBuf,
+        format: Option<&str>,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+        scale: f32,
+        scale_filter: &str,
+        projection: &str,
+        crop: Option<&PyAny>,
+    ) -> PyResult<()> {
+        let ext = path.extension().and_then(OsStr::to_str);
+        let Some(format) = format.or(ext) else {
+            let msg = format!("could not guess the format of {path:?}, pass it in explicitly");
+            return Err(PyValueError::new_err(msg));
+        };
+
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let img = self.exported_image(py, projection, crop, scale, scale_filter)?;
+        let file = match File::create(&path) {
+            Ok(f) => f,
+            Err(e) => return Err(PyIOError::new_err(format!("could not open {path:?}: {e}"))),
+        };
+
+        let mut w = BufWriter::new(file);
+        py.allow_threads(|| match encode_image(&img, format, &mut w) {
+            Ok(()) => w
+                .flush()
+                .map_err(|e| PyIOError::new_err(format!("could not write image: {e}"))),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        })
+    }
+
+    /// encodes the maze image and streams it into a file-like object (anything with a `write` method),
+    /// like an open file or an upload stream, without building the whole image in Python first
+    ///
+    /// takes the same format and options as `.get_image_expensively()`
+    #[pyo3(signature = (file, /, *, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive", scale = 1.0, scale_filter = "lanczos3", projection = "top-down", crop = None))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    fn write_image(
+        &mut self,
+        py: Python,
+        file: &PyAny,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+        scale: f32,
+        scale_filter: &str,
+        projection: &str,
+        crop: Option<&PyAny>,
+    ) -> PyResult<()> {
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        let img = self.exported_image(py, projection, crop, scale, scale_filter)?;
+
+        // buffered so that `file.write` isn't called for every tiny chunk the encoder spits out
+        let mut w = BufWriter::with_capacity(64 * 1024, PyFileWriter::new(file));
+        let result = encode_image(&img, format, &mut w)
+            .map_err(|e| e.to_string())
+            .and_then(|()| w.flush().map_err(|e| e.to_string()));
+
+        match (result, w.into_parts()) {
+            (Ok(()), _) => Ok(()),
+            (Err(e), (writer, _)) => Err(writer.into_err(e)),
+        }
+    }
+
+    /// draws the maze out of text, for posting in code blocks or logging
+    ///
+    /// `style` is either `"unicode"` (box-drawing characters) or `"ascii"` (`+`, `-`, and `|`),
+    /// and with `markers`, the player (if it's been drawn) and the goal are marked on it too
+    #[pyo3(signature = (style = "unicode", *, markers = true))]
+    fn to_text(&self, style: &str, markers: bool) -> PyResult<String> {
+        let Some(style) = TextStyle::from_name(style) else {
+            let msg =
+                format!("expected one of \"unicode\" or \"ascii\" as a text style; got {style:?}");
+            return Err(PyValueError::new_err(msg));
+        };
+
+        let (w, h) = (self.width, self.height);
+        Ok(maze_text(&self.walls, style, self.marks(markers), w, h))
+    }
+
+    /// draws the maze with coloured blocks for terminals, in the colours it was generated with
+    ///
+    /// with `draw_path` set, the solution is coloured in as well (raising `SolutionNotFound`
+    /// if it hasn't been determined yet), and with `markers`, the player (if it's been drawn)
+    /// and the goal are marked on it too; `truecolor` can be turned off for terminals that only
+    /// support 256 colours
+    #[pyo3(signature = (*, draw_path = false, markers = true, truecolor = true))]
+    #[allow(clippy::wrong_self_convention)] // `&mut` since a stale solution gets recomputed
+    fn to_ansi(
+        &mut self,
+        py: Python,
+        draw_path: bool,
+        markers: bool,
+        truecolor: bool,
+    ) -> PyResult<String> {
+        let cells = if draw_path {
+            self.refresh_solution(py)?;
+            Some(self.cached_solution()?.cells())
+        } else {
+            None
+        };
+
+        let colours = AnsiColours {
+            bg: self.background.colour,
+            wall: self.wall_fill.colour,
+            path: self.solution_colour,
+            truecolor,
+        };
+
+        let (w, h) = (self.width, self.height);
+        let marks = self.marks(markers);
+        Ok(maze_ansi(
+            &self.walls,
+            colours,
+            cells.as_deref(),
+            marks,
+            w,
+            h,
+        ))
+    }
+
+    /// draws the maze as rows of emoji, for playing small mazes right in chat messages without uploading images
+    ///
+    /// every cell, wall, and corner gets an emoji of its own, so keep in mind that a maze `w` by `h` comes out
+    /// `2w + 1` emoji across and `2h + 1` down (Discord caps messages at 2000 characters, and custom emoji
+    /// like `"<:name:id>"` count for their full length)
+    ///
+    /// with `draw_path` set, the solution is drawn in `path` (raising `SolutionNotFound` if it hasn't been
+    /// determined yet), and with `markers`, the player (if it's been drawn) and the goal are drawn too
+    #[pyo3(signature = (*, wall = "⬛", open = "⬜", path = "🟥", player = "🙂", goal = "🏁", draw_path = false, markers = true))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    #[allow(clippy::wrong_self_convention)] // `&mut` since a stale solution gets recomputed
+    fn to_emoji(
+        &mut self,
+        py: Python,
+        wall: &str,
+        open: &str,
+        path: &str,
+        player: &str,
+        goal: &str,
+        draw_path: bool,
+        markers: bool,
+    ) -> PyResult<String> {
+        let cells = if draw_path {
+            self.refresh_solution(py)?;
+            Some(self.cached_solution()?.cells())
+        } else {
+            None
+        };
+
+        let emoji = EmojiSet {
+            wall: wall.to_owned(),
+            open: open.to_owned(),
+            path: path.to_owned(),
+            player: player.to_owned(),
+            goal: goal.to_owned(),
+        };
+
+        let (w, h) = (self.width, self.height);
+        let marks = self.marks(markers);
+        Ok(maze_emoji(
+            &self.walls,
+            &emoji,
+            cells.as_deref(),
+            marks,
+            w,
+            h,
+        ))
+    }
+
+    /// renders a high-contrast black and white version of the maze for printing, no matter what colours it was
+    /// generated with, and encodes it into a `bytes` object
+    ///
+    /// cells come out `cell_mm` millimetres across when printed at `dpi`, with thin walls, a border with openings
+    /// at the start and goal, and (with `draw_path`) the solution as a dotted line, raising `SolutionNotFound`
+    /// if it hasn't been determined yet
+    ///
+    /// `dpi` can be anywhere from 72 to 2400, and `ValueError` is raised if the image would be too big to render
+    ///
+    /// takes the same format and options as `.get_image_expensively()`
+    #[pyo3(signature = (*, draw_path = false, dpi = 300, cell_mm = 6.0, format = "png", quality = 80, png_compression = "fast", png_filter = "adaptive"))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    fn render_print<'py>(
+        &mut self,
+        py: Python<'py>,
+        draw_path: bool,
+        dpi: u32,
+        cell_mm: f32,
+        format: &str,
+        quality: u8,
+        png_compression: &str,
+        png_filter: &str,
+    ) -> PyResult<&'py PyBytes> {
+        // about a gigabyte of pixels, which is already far more than a printer needs
+        const MAX_PIXELS: u64 = 1 << 28;
+
+        let format = extract_format(format, quality, png_compression, png_filter)?;
+        if !(72..=2400).contains(&dpi) {
+            let msg = format!("dpi must be between 72 and 2400; got {dpi}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let cell_size = (cell_mm / 25.4 * dpi as f32).round();
+        if !(cell_size.is_finite() && (4.0..=1000.0).contains(&cell_size)) {
+            let msg = format!("cells would be {cell_size} pixels across, pick a dpi and cell_mm that give 4 to 1000");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        // the margin around the maze is half a cell on each side
+        let (w, h) = (self.width, self.height);
+        let (img_w, img_h) = (
+            (w as u64 + 1) * cell_size as u64,
+            (h as u64 + 1) * cell_size as u64,
+        );
+        if img_w * img_h > MAX_PIXELS {
+            let msg = format!("the printed maze would be {img_w}x{img_h} pixels, which is too big; pick a smaller dpi or cell_mm");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let cells = if draw_path {
+            self.refresh_solution(py)?;
+            Some(self.cached_solution()?.cells())
+        } else {
+            None
+        };
+
+        let encoded = py.allow_threads(|| {
+            let img = print_image(&self.walls, cells.as_deref(), cell_size as u32, w, h);
+            encode_to_vec(&img, format)
+        });
+
+        match encoded {
+            Ok(buf) => Ok(PyBytes::new(py, &buf)),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// renders the maze as an SVG document, for embedding on web pages and scaling losslessly
+    ///
+    /// with `draw_path` set, the solution is drawn as well (raising `SolutionNotFound`
+    /// if it hasn't been determined yet); the player and endzone icons aren't part of the SVG
+    #[pyo3(signature = (*, draw_path = false))]
+    #[allow(clippy::wrong_self_convention)] // `&mut` since a stale solution gets recomputed
+    fn to_svg(&mut self, py: Python, draw_path: bool) -> PyResult<String> {
+        let cells = if draw_path {
+            self.refresh_solution(py)?;
+            Some(self.cached_solution()?.cells())
+        } else {
+            None
+        };
+
+        let solution = cells.as_deref().map(|c| (c, self.solution_colour));
+        let (w, h) = (self.width, self.height);
+        Ok(maze_svg(
+            &self.walls,
+            self.background.colour,
+            self.wall_fill.colour,
+            solution,
+            w,
+            h,
+        ))
+    }
+
+    /// renders a run as an animated GIF in a `io.BytesIO` buffer, with the player
+    /// stepping through every cell it passes, and `frame_delay` milliseconds between each step
+    ///
+    /// moves are the same `(direction, amount, is_max)` tuples that `.simulate()` takes,
+    /// and any move that runs into a wall raises `ValueError`
+    ///
+    /// the player isn't drawn at its current position in the animation,
+    /// but anything else on the maze image (like the solution path) is; with `player_facing="rotate"`
+    /// or `"spritesheet"`, the player turns to face each step like it does when it's moved
+    #[pyo3(signature = (moves, frame_delay = 100, *, start = AnyPoint((0, 0))))]
+    fn render_replay_gif<'py>(
+        &mut self,
+        py: Python<'py>,
+        moves: Vec<MoveArg>,
+        frame_delay: u32,
+        start: AnyPoint,
+    ) -> PyResult<&'py PyAny> {
+        let start = start.0;
+        let (w, h) = (self.width, self.height);
+        if out_of_bounds(start, w, h) {
+            return Err(PyValueError::new_err(format!(
+                "start {start:?} is out of bounds"
+            )));
+        }
+
+        let moves = extract_moves(moves)?;
+        let playback = play_moves(&self.walls, &self.mechanics(), start, &moves, w, h);
+        if let Some(idx) = playback.collided_at {
+            return Err(PyValueError::new_err(format!(
+                "move {idx} runs into a wall"
+            )));
+        }
+
+        let base = self.rendered(py)?.layers.flatten_without(Layer::Player);
+
+        // the player turns with every step like it does when it's moved, starting off facing up like a new maze;
+        // jumping through a portal keeps it facing the way it went in
+        let mut facing = (0, -1);
+        let mut last = start;
+        let steps: Vec<(Point, &Image<Pxl>)> = playback
+            .visited
+            .iter()
+            .map(|&xy| {
+                let step = (xy.0 - last.0, xy.1 - last.1);
+                if sprite_index(step).is_some() {
+                    facing = step;
+                }
+
+                last = xy;
+                (xy, self.sprite_facing(facing))
+            })
+            .collect();
+
+        let encoded = py.allow_threads(|| {
+            let frames = replay_frames(&base, &steps);
+            encode_gif(frames, frame_delay)
+        });
+
+        match encoded {
+            Ok(buf) => into_bytes_io(py, buf),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// renders the maze being carved out as an animated GIF in a `io.BytesIO` buffer,
+    /// going from a full grid of walls to the finished maze over `frames` frames
+    ///
+    /// this only works if the maze was generated with `record_generation=True`
+    #[pyo3(signature = (frame_delay = 100, *, frames = 30))]
+    fn render_generation_gif<'py>(
+        &self,
+        py: Python<'py>,
+        frame_delay: u32,
+        frames: usize,
+    ) -> PyResult<&'py PyAny> {
+        let frames = self.generation_frames(py, frames)?;
+        match py.allow_threads(|| encode_gif(frames, frame_delay)) {
+            Ok(buf) => into_bytes_io(py, buf),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// renders the maze being carved out as a list of PNG-encoded `bytes`,
+    /// going from a full grid of walls to the finished maze over `frames` frames
+    ///
+    /// this only works if the maze was generated with `record_generation=True`
+    #[pyo3(signature = (*, frames = 30))]
+    fn render_generation_frames<'py>(
+        &self,
+        py: Python<'py>,
+        frames: usize,
+    ) -> PyResult<Vec<&'py PyBytes>> {
+        let frames = self.generation_frames(py, frames)?;
+        let encoded: ImageResult<Vec<Vec<u8>>> =
+            py.allow_threads(|| frames.par_iter().map(encode_png).collect());
+
+        match encoded {
+            Ok(bufs) => Ok(bufs.iter().map(|buf| PyBytes::new(py, buf)).collect()),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// renders the solution path being traced out as an animated GIF in a `io.BytesIO` buffer,
+    /// drawing `segments_per_frame` cell-to-cell segments of the path each frame
+    ///
+    /// the animation is drawn over a fresh render of the maze (so without the player or a static path),
+    /// and raises `SolutionNotFound` if the solution hasn't been determined yet
+    #[pyo3(signature = (frame_delay = 100, *, segments_per_frame = 1))]
+    fn render_solution_animation<'py>(
+        &mut self,
+        py: Python<'py>,
+        frame_delay: u32,
+        segments_per_frame: usize,
+    ) -> PyResult<&'py PyAny> {
+        if segments_per_frame == 0 {
+            return Err(PyValueError::new_err(
+                "segments_per_frame must be at least 1",
+            ));
+        }
+
+        self.refresh_solution(py)?;
+        let path: EdgeVec = self.cached_solution()?.path.iter().rev().copied().collect();
+
+        let (w, h) = (self.width, self.height);
+        let (bg, wall, end_icon) = (&self.background, &self.wall_fill, &self.end_icon);
+        let style = self.draw_style;
+        let encoded = py.allow_threads(|| {
+            let base = maze_image(&self.walls, bg, wall, style, end_icon, w, h);
+            let frames = solution_frames(base, &path, self.solution_paint(), segments_per_frame);
+            encode_gif(frames, frame_delay)
+        });
+
+        match encoded {
+            Ok(buf) => into_bytes_io(py, buf),
+            Err(e) => Err(PyIOError::new_err(format!("could not write image: {e}"))),
+        }
+    }
+
+    /// moves the player as far as they can go in a particular direction, and return that position
+    ///
+    /// this will also re-draw the player on the maze, and record the move in the history
+    /// (and the trail, if a trail colour is set)
+    ///
+    /// the player stops early on the first portal (coming out of the other end) or switch (pressing it) they slide onto
+    ///
+    /// with `stop_at_goal`, the player stops on the goal if it slides over it instead of carrying on to the next wall,
+    /// and `(position, reached_goal)` is returned instead, where `reached_goal` is whether the player ended up on the goal
+    ///
+    /// `player` moves the player with that id (see `.add_player()`) instead, which picks up items and presses switches
+    /// the same way, but doesn't leave a trail or lift the fog
+    #[pyo3(signature = (current, direction, /, *, stop_at_goal = false, player = None))]
+    fn move_max(
+        &mut self,
+        py: Python,
+        current: AnyPoint,
+        direction: AnyDirection,
+        stop_at_goal: bool,
+        player: Option<&str>,
+    ) -> PyResult<PyObject> {
+        let who = self.player_index(player)?;
+        let (old, direction) = (current.0, direction.0);
+        let (w, h) = (self.width, self.height);
+        let goal = (w - 1, h - 1);
+        let stop = stop_at_goal.then_some(goal);
+        let m = Move::new(direction, 1, true);
+        let travel = self.mechanics().travel(&self.walls, old, m, 0, stop, w, h);
+        let current = travel.position;
+        let exit = (current != travel.entry).then_some(current);
+        self.move_player(py, who, old, travel.entry, direction, exit);
+        self.press_switches(py, travel.pressed);
+
+        let position = NamedPoint::from(current);
+        Ok(if stop_at_goal {
+            (position, current == goal).into_py(py)
+        } else {
+            position.into_py(py)
+        })
+    }
+
+    /// moves the player a single cell in a particular direction, if there isn't a wall (or the edge of the maze) in the way
+    ///
+    /// returns `(position, moved)`, where `position` is where the player is afterwards;
+    /// like `.move_max()`, this re-draws the player and records the move, even if it didn't go anywhere,
+    /// and stepping onto a portal or a switch goes through it or presses it; stepping onto ice carries on sliding,
+    /// so `position` can be further than a single cell away
+    ///
+    /// `player` moves the player with that id instead, like it does for `.move_max()`
+    #[pyo3(signature = (current, direction, /, *, player = None))]
+    fn move_step(
+        &mut self,
+        py: Python,
+        current: AnyPoint,
+        direction: AnyDirection,
+        player: Option<&str>,
+    ) -> PyResult<(NamedPoint, bool)> {
+        let who = self.player_index(player)?;
+        let (old, direction) = (current.0, direction.0);
+        if sprite_index(direction).is_none() {
+            let msg =
+                format!("expected UP, DOWN, LEFT, or RIGHT as a direction; got {direction:?}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let (w, h) = (self.width, self.height);
+        let m = Move::new(direction, 1, false);
+        let travel = self.mechanics().travel(&self.walls, old, m, 0, None, w, h);
+        let current = travel.position;
+        let exit = (current != travel.entry).then_some(current);
+        self.move_player(py, who, old, travel.entry, direction, exit);
+        self.press_switches(py, travel.pressed);
+
+        Ok((current.into(), !travel.collided))
+    }
+
+    /// returns every move the player (or the player with the id `player`, if it's given) has made so far,
+    /// as a list of `(from, to)` tuples
+    #[pyo3(signature = (*, player = None))]
+    fn get_history(&self, player: Option<&str>) -> PyResult<Vec<(Point, Point)>> {
+        Ok(match self.player_index(player)? {
+            None => self.history.clone(),
+            Some(i) => self.players[i].history.clone(),
+        })
+    }
+
+    /// forgets every move the player has made so far, along with which checkpoints it's reached
+    fn clear_history(&mut self) {
+        self.history.clear();
+        let reached = self.checkpoints[..self.checkpoints_reached.len()].iter();
+        let cells = reached.copied().collect();
+        self.checkpoints_reached.clear();
+        self.redraw_markers_at(&cells);
+    }
+
+    /// re-renders the maze image from scratch, from its walls and everything it's keeping track of
+    /// (the player, solution path, trail, markers, and fog), wiping anything else that was drawn on it like text
+    ///
+    /// a drawn solution path that's out of date with the walls is recomputed first,
+    /// and this does nothing if the image hasn't been rendered yet, since it'll be built fresh anyway
+    fn redraw(&mut self, py: Python) {
+        if self.canvas.is_none() {
+            return;
+        }
+
+        // a solution whose goal has been cut off gets left off, which is all there is to draw
+        let _ = self.refresh_solution(py);
+        let fresh = self.base_image(py);
+        self.canvas
+            .as_mut()
+            .expect("checked right above")
+            .reset(fresh);
+        self.draw_state(py);
+    }
+
+    /// checks whether the maze is a perfect maze, where every cell can be reached from the start
+    /// and there's only ever one way between two cells, like it is when it's first generated
+    ///
+    /// the returned `Validation` lists the cells that can't be reached and the passages that make loops,
+    /// handy after editing walls
+    fn validate(&self) -> Validation {
+        let (w, h) = (self.width, self.height);
+        let reachable = reachable_cells(&self.walls, (0, 0), w, h);
+        let unreachable = (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .filter(|xy| !reachable.contains(xy))
+            .collect();
+
+        Validation::new(unreachable, loop_passages(&self.walls, w, h))
+    }
+
+    /// the maze as a graph, a dict mapping every cell to a list of the cells it has an open path to
+    ///
+    /// this can go straight into `networkx.Graph()` (or `from_dict_of_lists`) for any analysis this library doesn't do
+    fn to_adjacency(&self) -> HashMap<Point, Vec<Point>> {
+        let (w, h) = (self.width, self.height);
+        (0..h)
+            .flat_map(|y| (0..w).map(move |x| (x, y)))
+            .map(|xy| (xy, open_neighbours(&self.walls, xy, w, h)))
+            .collect()
+    }
+
+    /// every open path between two neighbouring cells as an `(a, b)` tuple, once each,
+    /// the same graph as `.to_adjacency()` but as an edge list (e.g for `networkx.Graph(maze.edges())`)
+    fn edges(&self) -> Vec<(Point, Point)> {
+        passages(&self.walls, self.width, self.height)
+    }
+
+    /// how many cells across the maze is
+    #[getter]
+    const fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// how many cells tall the maze is
+    #[getter]
+    const fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// where the player starts out, the top left cell
+    #[getter]
+    const fn start(&self) -> NamedPoint {
+        NamedPoint::new(0, 0)
+    }
+
+    /// the goal, the bottom right cell
+    #[getter]
+    const fn end(&self) -> NamedPoint {
+        NamedPoint::new(self.width - 1, self.height - 1)
+    }
+
+    #[getter]
+    const fn bg_colour(&self) -> [u8; 4] {
+        self.background.colour.0
+    }
+
+    #[getter]
+    const fn solution_colour(&self) -> [u8; 4] {
+        self.solution_colour.0
+    }
+
+    /// whether `.compute_solution()` has been called (and found one), so the solution methods can be used
+    #[getter]
+    const fn is_solved_computed(&self) -> bool {
+        self.solution.is_some()
+    }
+
+    /// every wall between two neighbouring cells as an `(a, b)` tuple, with `a` left of or above `b`
+    ///
+    /// this is a snapshot, so it doesn't change along with the maze (e.g after `.remove_wall()`)
+    #[getter(walls)]
+    fn get_walls<'py>(&self, py: Python<'py>) -> PyResult<&'py PyFrozenSet> {
+        let walls: EdgeVec = self.walls.iter().copied().map(ordered_edge).collect();
+        PyFrozenSet::new(py, &walls)
+    }
+
+    /// every open path between two neighbouring cells, in the same form as `.walls`
+    ///
+    /// together the two of them cover every pair of neighbouring cells in the maze exactly once
+    #[getter(passages)]
+    fn get_passages<'py>(&self, py: Python<'py>) -> PyResult<&'py PyFrozenSet> {
+        PyFrozenSet::new(py, &passages(&self.walls, self.width, self.height))
+    }
+
+    /// saves the maze to a JSON string, which `maze.from_json()` loads back in, so a game can be picked up where it left off
+    ///
+    /// this has the maze's size, walls, start and goal, colours, the player's position and history, the trail, fog,
+    /// portals, switches, terrain, checkpoints, and whether the solution was computed and drawn;
+    /// markers (items included), other players, chasers, text, and images (like the icons) aren't saved
+    fn to_json(&self, py: Python) -> PyResult<String> {
+        let dict = self.saved().to_dict(py)?;
+        py.import("json")?.call_method1("dumps", (dict,))?.extract()
+    }
+
+    /// saves the same things as `.to_json()` into a compact `bytes` object, which `maze.from_bytes()` loads back in
+    ///
+    /// every spot a wall could be takes up a single bit, so a typical maze comes out to a few hundred bytes,
+    /// small enough to keep alongside each message of a game
+    fn to_bytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+        PyBytes::new(py, &self.saved().to_bytes())
+    }
+
+    /// a digest of the maze's size and walls as 16 hex digits, which doesn't care about colours, icons,
+    /// or anything going on in the maze, and stays the same between runs and machines, for spotting duplicate mazes
+    ///
+    /// with `symmetric` on, mazes that are rotations or mirror images of each other (see `.rotate()`, `.mirror()`,
+    /// and `.transpose()`) get the same digest too
+    #[pyo3(signature = (*, symmetric = false))]
+    fn canonical_hash(&self, symmetric: bool) -> String {
+        let (w, h) = (self.width, self.height);
+        let mut hash = structure_hash(&self.walls, w, h);
+        if symmetric {
+            let transforms: [&[Transform]; 7] = [
+                &[Transform::Rotate(1)],
+                &[Transform::Rotate(2)],
+                &[Transform::Rotate(3)],
+                &[Transform::MirrorHorizontal],
+                &[Transform::MirrorVertical],
+                &[Transform::Transpose],
+                // flipped across the other diagonal, which isn't one of the transforms by itself
+                &[Transform::Rotate(2), Transform::Transpose],
+            ];
+
+            for chain in transforms {
+                let (walls, (tw, th)) = chain
+                    .iter()
+                    .fold((self.walls.clone(), (w, h)), |(walls, (cw, ch)), t| {
+                        (t.walls(&walls, cw, ch), t.size(cw, ch))
+                    });
+                hash = hash.min(structure_hash(&walls, tw, th));
+            }
+        }
+
+        format!("{hash:016x}")
+    }
+
+    /// splits the maze up into the groups of cells that can reach each other, returning `(count, regions)`
+    /// where `regions` maps every cell to the number of the group it's in
+    ///
+    /// groups are numbered from 0 in the order their top left-most cell comes in row by row, so the start is always in 0;
+    /// a freshly generated maze is all one group
+    ///
+    /// with `repair` on, just enough walls are knocked down first to join every group back up
+    /// (re-rendering the image), so the maze always comes back as one group
+    #[pyo3(signature = (*, repair = false))]
+    fn components(&mut self, py: Python, repair: bool) -> (usize, HashMap<Point, usize>) {
+        let (w, h) = (self.width, self.height);
+        let (count, regions) = connected_regions(&self.walls, w, h);
+        if !repair || count == 1 {
+            return (count, regions);
+        }
+
+        connect_regions(&mut self.walls, w, h);
+        self.walls_changed(py);
+        connected_regions(&self.walls, w, h)
+    }
 
-use pyo3::prelude::*;
-use pyo3::{
-    create_exception,
-    exceptions::{PyException, PyIOError, PyValueError},
-    types::{PyBytes, PySequence, PyTuple},
-};
+    /// knocks down the wall between two neighbouring cells, doing nothing if there isn't one
+    ///
+    /// the image is re-rendered and a cached solution is recomputed the next time it's needed
+    #[pyo3(signature = (a, b, /))]
+    fn remove_wall(&mut self, py: Python, a: AnyPoint, b: AnyPoint) -> PyResult<()> {
+        let a = a.0;
+        let b = b.0;
+        check_neighbours(a, b, self.width, self.height)?;
+        if self.walls.remove(&(a, b)) | self.walls.remove(&(b, a)) {
+            self.walls_changed(py);
+        }
 
-create_exception!(maze, SolutionNotFound, PyException);
+        Ok(())
+    }
 
-/// bundles elements representing a maze
-#[pyclass(module = "maze")]
-struct Maze {
-    width: i32,
-    height: i32,
-    bg_colour: Pxl,
-    solution_colour: Pxl,
-    solution_moves: Option<(i32, Arc<Vec<String>>)>,
-    maze_image: Image<Pxl>,
-    player_icon: Image<Pxl>,
-    walls: HashSet<(Point, Point)>,
-}
+    /// puts up a wall between two neighbouring cells, doing nothing if there already is one
+    ///
+    /// if the wall would cut some cells off from the start, it's refused with a `ValueError`,
+    /// unless `allow_disconnect` is on, in which case it's put up anyway and the cells it cut off are returned;
+    /// the list is empty whenever the maze is still connected
+    ///
+    /// the image is re-rendered and a cached solution is recomputed the next time it's needed
+    #[pyo3(signature = (a, b, /, *, allow_disconnect = false))]
+    fn add_wall(
+        &mut self,
+        py: Python,
+        a: AnyPoint,
+        b: AnyPoint,
+        allow_disconnect: bool,
+    ) -> PyResult<Vec<Point>> {
+        let a = a.0;
+        let b = b.0;
+        let (w, h) = (self.width, self.height);
+        check_neighbours(a, b, w, h)?;
+        if wall_between(&self.walls, a, b) {
+            return Ok(vec![]);
+        }
 
-/// private methods (not exposed to the Python)
-impl Maze {
-    /// draws the solution path onto the maze image
-    fn draw_solution(&mut self, py: Python, solution: &EdgeVec) {
-        let img = std::mem::take(&mut self.maze_image);
+        let edge = ordered_edge((a, b));
+        self.walls.insert(edge);
+        let cut = cells_cut_off(&self.walls, edge, (0, 0), w, h);
+        if !cut.is_empty() && !allow_disconnect {
+            self.walls.remove(&edge);
+            let msg = format!(
+                "a wall between {a:?} and {b:?} would cut {} cell(s) off from the start, \
+                 pass `allow_disconnect=True` to put it up anyway",
+                cut.len()
+            );
+            return Err(PyValueError::new_err(msg));
+        }
 
-        self.maze_image = py.allow_threads(|| solution_image(img, solution, self.solution_colour));
+        self.walls_changed(py);
+        Ok(cut)
     }
-}
 
-/// public methods (exposed to the Python)
-#[pymethods]
-impl Maze {
-    /// whether or not two points are blocked off by a wall
-    #[pyo3(signature = (a, b, /))]
-    fn has_wall_between(&self, a: Point, b: Point) -> bool {
+    /// moves `n` walls somewhere else, for a game mode where the maze shifts around the player every turn,
+    /// and returns `(removed, added)` for every wall that was moved
+    ///
+    /// each wall that's knocked down is put back up somewhere along the way around it,
+    /// and only where it doesn't cut any cells off from the player (or the start, if it isn't drawn),
+    /// so the goal can always still be reached; with portals, switches, or terrain around,
+    /// it's also checked that they don't get in the way of reaching it
+    ///
+    /// fewer walls are moved if there aren't `n` that can be, and only the cells around the moved walls
+    /// are redrawn, unless the solution path is drawn, in which case it's redrawn along with the rest of the image
+    #[pyo3(signature = (n, /))]
+    fn shift_walls(&mut self, py: Python, n: usize) -> Vec<((Point, Point), (Point, Point))> {
         let (w, h) = (self.width, self.height);
-        wall_between(&self.walls, a, b) || out_of_bounds(b, w, h) || out_of_bounds(a, w, h)
+        let (from, goal) = (self.player_pos.unwrap_or((0, 0)), (w - 1, h - 1));
+        let mechanics = self.mechanics();
+        let random = RandomState::new();
+
+        let mut shifted = vec![];
+        for turn in 0..n {
+            let mut inner: EdgeVec = self
+                .walls
+                .iter()
+                .filter(|(a, b)| !out_of_bounds(*a, w, h) && !out_of_bounds(*b, w, h))
+                .copied()
+                .collect();
+            inner.sort_unstable_by_key(|edge| random.hash_one((turn, edge)));
+
+            let mut moved = None;
+            'walls: for wall in inner {
+                let mut around = passages_around(&self.walls, wall, w, h);
+                around.sort_unstable_by_key(|edge| random.hash_one((turn, edge)));
+                self.walls.remove(&wall);
+                for passage in around {
+                    let passage = ordered_edge(passage);
+                    self.walls.insert(passage);
+                    if cells_cut_off(&self.walls, passage, from, w, h).is_empty()
+                        && (mechanics.is_empty()
+                            || mechanics.reachable(&self.walls, from, w, h).contains(&goal))
+                    {
+                        moved = Some((wall, passage));
+                        break 'walls;
+                    }
+
+                    self.walls.remove(&passage);
+                }
+
+                self.walls.insert(wall);
+            }
+
+            let Some(pair) = moved else {
+                break;
+            };
+
+            shifted.push(pair);
+        }
+
+        if shifted.is_empty() {
+            return shifted;
+        }
+
+        if self.solution_drawn || self.canvas.is_none() {
+            self.walls_changed(py);
+            return shifted;
+        }
+
+        self.walls_version += 1;
+        let (w, h) = (self.width, self.height);
+        let (walls, bg, wall, end_icon) = (
+            &self.walls,
+            &self.background,
+            &self.wall_fill,
+            &self.end_icon,
+        );
+        let style = self.draw_style;
+        let canvas = self.canvas.as_mut().expect("checked right above");
+
+        // one pixel bigger all around, for the smoothed edges of supersampled walls
+        let regions: Vec<Rect> = shifted
+            .iter()
+            .flat_map(|((a, b), (c, d))| [*a, *b, *c, *d])
+            .map(|xy| {
+                let rect = surrounding_rect(xy);
+                Rect::at(rect.left() - 1, rect.top() - 1)
+                    .of_size(rect.width() + 2, rect.height() + 2)
+            })
+            .collect();
+
+        py.allow_threads(|| {
+            for &region in &regions {
+                let base = canvas.layers.base_mut();
+                redraw_maze_region(base, walls, bg, wall, style, end_icon, w, h, region);
+                canvas.composite(Some(region));
+            }
+        });
+
+        shifted
     }
 
-    /// removes the player (if it exists) at an XY coodinate
+    /// cuts the `w` by `h` rectangle of cells with its top left corner at `(x0, y0)` out into a new maze,
+    /// which looks the same as this one, for breaking a giant maze up into stages
     ///
-    /// this essentially just pastes the background colour over those coordinates
-    #[pyo3(signature = (xy, /))]
-    fn undraw_at(&mut self, xy: Point) {
-        let rect = Rect::at(xy.0 * 40, xy.1 * 40).of_size(37, 37);
-        draw_filled_rect_mut(&mut self.maze_image, rect, self.bg_colour);
+    /// walls crossing the edge of the rectangle become the new maze's border, and since paths that
+    /// left the rectangle and came back are cut off, a few walls are knocked down to keep every cell reachable;
+    /// the start and goal are the new maze's top left and bottom right corners like always
+    ///
+    /// nothing that's kept track of on this maze (the player, markers, trail, fog, etc.) carries over
+    #[pyo3(signature = (x0, y0, w, h, /))]
+    fn submaze(&self, x0: i32, y0: i32, w: i32, h: i32) -> PyResult<Self> {
+        let (width, height) = (self.width, self.height);
+        if w < 1
+            || h < 1
+            || out_of_bounds((x0, y0), width, height)
+            || w > width - x0
+            || h > height - y0
+        {
+            let msg = format!(
+                "submaze {:?} doesn't fit inside of the {width}x{height} maze",
+                (x0, y0, w, h)
+            );
+            return Err(PyValueError::new_err(msg));
+        }
+
+        let mut walls = clip_walls(&self.walls, (x0, y0, w, h));
+        connect_regions(&mut walls, w, h);
+
+        let (img_w, img_h) = maze_image_size(w, h);
+        let background = Background {
+            colour: self.background.colour,
+            image: self.background.image.as_ref().map(|img| {
+                let (left, top) = ((x0 * 40) as u32, (y0 * 40) as u32);
+                imageops::crop_imm(img, left, top, img_w, img_h).to_image()
+            }),
+        };
+
+        Ok(self.with_walls(walls, w, h, background))
     }
 
-    /// draws the player at a given XY coordinate
-    #[pyo3(signature = (xy, /))]
-    fn draw_player_at(&mut self, xy: Point) {
-        let (x, y) = (i64::from(xy.0) * 40, i64::from(xy.1) * 40);
-        imageops::overlay(&mut self.maze_image, &self.player_icon, x, y);
+    /// turns the maze clockwise by `n_quarter_turns` quarter turns (negative numbers turn it anticlockwise),
+    /// taking the player, markers, trail, fog, and move history along with it
+    ///
+    /// the start and goal stay in the top left and bottom right corners, so the solution is recomputed
+    /// and the rotated maze plays like a new one; the image is re-rendered, wiping anything like text that was drawn on it
+    ///
+    /// raises a `BufferError` if the maze isn't square and a buffer view of its image is still alive,
+    /// since the image changes size
+    #[pyo3(signature = (n_quarter_turns = 1, /))]
+    fn rotate(&mut self, py: Python, n_quarter_turns: i64) -> PyResult<()> {
+        match n_quarter_turns.rem_euclid(4) {
+            0 => Ok(()),
+            n => self.apply_transform(py, Transform::Rotate(n as u8)),
+        }
     }
 
-    /// determines the solution to the maze, along with a set of "perfect moves"
+    /// flips the maze, `"horizontal"` swapping its left and right sides and `"vertical"` its top and bottom
     ///
-    /// on the Discord bot, there is a button to move the furthest distance possible in a direction
-    /// this will count the moves in a solution, with the above condition in mind
+    /// everything is carried over the same way as with `.rotate()`
+    #[pyo3(signature = (axis, /))]
+    fn mirror(&mut self, py: Python, axis: &str) -> PyResult<()> {
+        let transform = match axis {
+            "horizontal" => Transform::MirrorHorizontal,
+            "vertical" => Transform::MirrorVertical,
+            _ => {
+                let msg = format!("axis must be 'horizontal' or 'vertical', not {axis:?}");
+                return Err(PyValueError::new_err(msg));
+            }
+        };
+
+        self.apply_transform(py, transform)
+    }
+
+    /// flips the maze across its diagonal from the top left to the bottom right, swapping its rows and columns
     ///
-    /// this will store the solution in an internal field;
-    /// to get the actual value, use `.get_solution()`
-    #[pyo3(signature = (*, draw_path))]
-    fn compute_solution(&mut self, py: Python, draw_path: bool) {
-        let (n_moves, moves, solution) = a_star_solution(&self.walls, self.width, self.height);
-        self.solution_moves = Some((n_moves, Arc::new(moves)));
+    /// everything is carried over the same way as with `.rotate()`
+    fn transpose(&mut self, py: Python) -> PyResult<()> {
+        self.apply_transform(py, Transform::Transpose)
+    }
+
+    /// swaps out the background, wall, and/or solution colours and re-renders the maze with them,
+    /// keeping the same walls, so it can be restyled in the middle of a game
+    ///
+    /// the player, trail, markers, text, and fog stay as they are, a drawn solution path is redrawn
+    /// in the new colour, and the border (if the maze has one) follows the wall colour
+    #[pyo3(signature = (*, bg = None, wall = None, solution = None))]
+    fn recolour(
+        &mut self,
+        py: Python,
+        bg: Option<Colour>,
+        wall: Option<Colour>,
+        solution: Option<Colour>,
+    ) {
+        if let Some(colour) = bg {
+            self.background.colour = colour.into();
+        }
+
+        if let Some(colour) = wall {
+            self.wall_fill.colour = colour.into();
+            if self.frame.border.is_some() {
+                self.frame.border = Some(colour.into());
+            }
+        }
 
-        if draw_path {
-            self.draw_solution(py, &solution);
+        if let Some(colour) = solution {
+            self.solution_colour = colour.into();
         }
+
+        let path_drawn = self.solution_drawn;
+        if bg.is_some() || wall.is_some() {
+            self.redraw_base(py);
+        } else if solution.is_none() {
+            return;
+        }
+
+        self.solution_drawn = path_drawn;
+        self.redraw_solution(py);
     }
 
-    /// returns the maze's solution if one has already been determined, otherwise raise `SolutionNotFound`
+    /// sets the colour of the trail drawn behind the player as it moves, or turns it off with `None`
     ///
-    /// the solution is essentially a tuple containing two items
-    /// the first is a `u32` of how many moves a "perfect run" would take
-    /// the second is a string of newline-separated human-readable directions (e.g "2 right", "3 left")
+    /// the trail that's already been drawn is recoloured (or hidden), and only moves made
+    /// while a colour is set become part of the trail
     ///
-    /// this call clones a Rust object and converts it to Python,
-    /// which introduces a significant amount of overhead (use it sparingly!)
-    fn get_solution_expensively<'py>(&mut self, py: Python<'py>) -> PyResult<&'py PyAny> {
-        const MSG: &str = "make sure to call `.compute_solution()` first";
-        let m = match self.solution_moves {
-            None => return Err(SolutionNotFound::new_err(MSG)),
-            Some(ref m) => m,
-        };
+    /// with a `fade` above 0, each segment gets more transparent with every move after it,
+    /// and is gone for good after `fade` moves, even if the fade is turned off again later
+    #[pyo3(signature = (colour, /, *, fade = 0))]
+    fn set_trail_colour(&mut self, py: Python, colour: Option<Colour>, fade: u32) -> PyResult<()> {
+        self.trail_colour = colour.map(Pxl::from);
 
-        let solution_args = PyTuple::new(py, [m.0.to_object(py), m.1.clone().to_object(py)]);
-        let collections = py.import("collections")?;
-        collections
-            .getattr("namedtuple")?
-            .call1(("Solution", ("move_count", "directions")))? // instantiates the namedtuple type
-            .call1(solution_args) // instantiates an instance of said type
+        self.trail_fade = fade;
+        self.drop_faded_trail();
+        self.redraw_trail(py);
+        Ok(())
     }
 
-    /// clones the maze image into a `io.BytesIO` buffer in Python
+    /// covers every cell in fog except for the ones within `radius` cells of the player,
+    /// the fog lifts as the player moves around, and stays lifted wherever it's been
     ///
-    /// this call clones a Rust object and converts it to Python,
-    /// which introduces a significant amount of overhead (use it sparingly!)
-    fn get_image_expensively<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
-        let mut buf = Cursor::new(vec![]);
-        match self.maze_image.write_to(&mut buf, ImageOutputFormat::Png) {
-            Ok(()) => (),
-            Err(e) => return Err(PyIOError::new_err(format!("could not write image: {e}"))),
+    /// calling this again covers the whole maze back up, `colour` can be left out if the maze's theme has a fog colour
+    #[pyo3(signature = (colour = None, /, *, radius = 1))]
+    fn enable_fog(&mut self, py: Python, colour: Option<Colour>, radius: i32) -> PyResult<()> {
+        let Some(colour) = colour.map(Pxl::from).or(self.fog_colour) else {
+            let msg = "no fog colour was given, and the maze's theme doesn't have one";
+            return Err(PyTypeError::new_err(msg));
+        };
+
+        if radius < 0 {
+            let msg = format!("fog radius can't be negative; got {radius}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        if let Some(canvas) = &mut self.canvas {
+            canvas.layers.fill(Layer::Fog, colour);
         }
 
-        let io = py.import("io")?;
-        let builtins = py.import("builtins")?;
+        self.fog = Some(Fog {
+            colour,
+            radius,
+            revealed: HashSet::new(),
+        });
 
-        let data = PyTuple::new(py, [buf.into_inner()]);
-        let arr = builtins.getattr("bytearray")?.call1(data)?;
+        if let Some(xy) = self.player_pos {
+            self.reveal_around(xy);
+        }
 
-        let init_bytes = PyTuple::new(py, [arr]);
-        io.getattr("BytesIO")?.call1(init_bytes)
+        self.composite(py, None);
+        Ok(())
     }
 
-    /// moves the player as far as they can go in a particular direction, and return that position
+    /// lifts all of the fog off of the maze
+    fn disable_fog(&mut self, py: Python) {
+        self.fog = None;
+        if let Some(canvas) = &mut self.canvas {
+            canvas.layers.clear(Layer::Fog);
+        }
+
+        self.composite(py, None);
+    }
+
+    /// stamps a caption (a `str`) or a small image (anything else the icons take, use a `pathlib.Path` for files)
+    /// into a corner of every exported image, replacing the last one
     ///
-    /// this will also re-draw the player on the maze
-    #[pyo3(signature = (current, direction, /))]
-    fn move_max(&mut self, mut current: Point, direction: (i32, i32)) -> Point {
-        let old = current;
-        loop {
-            // the next node one over in the direction to look
-            let n = (current.0 + direction.0, current.1 + direction.1);
-            if out_of_bounds(n, self.width, self.height) || self.has_wall_between(current, n) {
-                break;
+    /// `corner` is one of `"top-left"`, `"top-right"`, `"bottom-left"`, or `"bottom-right"`, and `inset` is how
+    /// many pixels in from the edges it goes; `colour` and `size` are only used for captions
+    #[pyo3(signature = (text_or_image, /, *, corner = "bottom-right", inset = 4, colour = None, size = 14.0))]
+    fn set_watermark(
+        &mut self,
+        text_or_image: &PyAny,
+        corner: &str,
+        inset: u32,
+        colour: Option<Colour>,
+        size: f32,
+    ) -> PyResult<()> {
+        let Some(corner) = Corner::from_name(corner) else {
+            let msg = format!("expected one of \"top-left\", \"top-right\", \"bottom-left\", or \"bottom-right\" as a corner; got {corner:?}");
+            return Err(PyValueError::new_err(msg));
+        };
+
+        let stamp = match text_or_image.extract::<String>() {
+            Ok(text) => {
+                if !(size > 0.0 && size.is_finite()) {
+                    let msg = format!("text size must be a positive number; got {size}");
+                    return Err(PyValueError::new_err(msg));
+                }
+
+                // the walls are already picked to stand out against the background
+                let colour = colour.map_or(self.wall_fill.colour, Pxl::from);
+                Stamp::Text { text, colour, size }
             }
+            Err(_) => Stamp::Image(extract_icon(text_or_image, "watermark")?),
+        };
 
-            current = n;
-        }
+        self.watermark = Some(Watermark {
+            stamp,
+            corner,
+            inset,
+        });
+
+        Ok(())
+    }
+
+    /// stops stamping a watermark onto exported images
+    fn clear_watermark(&mut self) {
+        self.watermark = None;
+    }
 
-        self.undraw_at(old);
-        self.draw_player_at(current);
-        current
+    /// wipes the trail drawn so far
+    fn clear_trail(&mut self, py: Python) {
+        self.trail.clear();
+        self.redraw_trail(py);
     }
 }
 
-/// takes a Python tuple of either RGB or RGBA values, and shoves it into `image::Rgba`
-macro_rules! into_rgba {
-    ($name:tt) => {
-        let len = $name.len().unwrap_or(0); // if a list/tuple has been passed, this will be `Some`
-        if len != 3 && len != 4 {
+/// builds direction labels out of a Python dict, anything left unspecified keeps its default
+fn extract_direction_labels(mapping: Option<&PyDict>, ascii: bool) -> PyResult<DirectionLabels> {
+    let mut labels = if ascii {
+        DirectionLabels::ascii()
+    } else {
+        DirectionLabels::default()
+    };
+
+    let Some(mapping) = mapping else {
+        return Ok(labels);
+    };
+
+    for (key, template) in mapping {
+        let key: &str = key.extract()?;
+        if !labels.set(key, template.extract()?) {
             return Err(PyValueError::new_err(format!(
-                "colour parameter expected RGB or RGBA collection; got value {}",
-                $name.repr()?
+                "unknown direction label {key:?}; expected one of {:?}",
+                DirectionLabels::KEYS
             )));
         }
+    }
+
+    Ok(labels)
+}
+
+/// checks over the image format and its options passed from Python
+fn extract_format(
+    format: &str,
+    quality: u8,
+    png_compression: &str,
+    png_filter: &str,
+) -> PyResult<OutputFormat> {
+    if !(1..=100).contains(&quality) {
+        let msg = format!("quality must be between 1 and 100; got {quality}");
+        return Err(PyValueError::new_err(msg));
+    }
+
+    let Some(compression) = png_compression_from_name(png_compression) else {
+        let msg = format!("expected one of \"fast\", \"default\", or \"best\" as a PNG compression level; got {png_compression:?}");
+        return Err(PyValueError::new_err(msg));
+    };
+
+    let Some(filter) = png_filter_from_name(png_filter) else {
+        let msg = format!("expected one of \"none\", \"sub\", \"up\", \"avg\", \"paeth\", or \"adaptive\" as a PNG filter; got {png_filter:?}");
+        return Err(PyValueError::new_err(msg));
+    };
 
-        let mut arr = [255u8; 4];
-        for (idx, i) in $name.extract::<Vec<u8>>()?.iter().enumerate() {
-            arr[idx] = *i;
+    match OutputFormat::from_name(format, quality) {
+        Some(OutputFormat::Png(..)) => Ok(OutputFormat::Png(compression, filter)),
+        Some(format) => Ok(format),
+        None => {
+            let msg = format!("expected one of \"png\", \"jpeg\", \"webp\", or \"bmp\" as a format; got {format:?}");
+            Err(PyValueError::new_err(msg))
         }
+    }
+}
+
+/// turns a resampling filter name from Python into `image`'s filter type, `what` is what it's for in the error
+fn extract_resize_filter(name: &str, what: &str) -> PyResult<imageops::FilterType> {
+    resize_filter_from_name(name).ok_or_else(|| {
+        let msg = format!("expected one of \"nearest\", \"triangle\" (or \"bilinear\"), \"catmullrom\" (or \"bicubic\"), \"gaussian\", or \"lanczos3\" as {what} filter; got {name:?}");
+        PyValueError::new_err(msg)
+    })
+}
+
+/// makes sure `a` and `b` are next to each other inside of the maze, so there's room for a wall between them
+fn check_neighbours(a: Point, b: Point, width: i32, height: i32) -> PyResult<()> {
+    if let Some(xy) = [a, b]
+        .into_iter()
+        .find(|xy| out_of_bounds(*xy, width, height))
+    {
+        return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+    }
+
+    if (a.0 - b.0).abs() + (a.1 - b.1).abs() != 1 {
+        let msg = format!("{a:?} and {b:?} aren't next to each other");
+        return Err(PyValueError::new_err(msg));
+    }
+
+    Ok(())
+}
+
+/// turns a crop from Python into an `(x, y, width, height)` rectangle of cells, it's either one of those
+/// already or a list of cells to fit a rectangle around
+fn extract_crop(crop: &PyAny, width: i32, height: i32) -> PyResult<(i32, i32, i32, i32)> {
+    let rect = match crop.extract::<(i32, i32, i32, i32)>() {
+        Ok(rect) => rect,
+        Err(_) => {
+            let Ok(cells) = crop.extract::<Vec<AnyPoint>>() else {
+                let msg = format!(
+                    "expected an (x, y, width, height) tuple or a list of cells to crop to; got {}",
+                    crop.repr()?
+                );
+                return Err(PyTypeError::new_err(msg));
+            };
+
+            let cells: Vec<Point> = cells.into_iter().map(|xy| xy.0).collect();
+            let Some(&first) = cells.first() else {
+                return Err(PyValueError::new_err(
+                    "can't crop to an empty list of cells",
+                ));
+            };
+
+            if let Some(xy) = cells.iter().find(|xy| out_of_bounds(**xy, width, height)) {
+                return Err(PyValueError::new_err(format!("{xy:?} is out of bounds")));
+            }
 
-        let $name = Rgba(arr);
+            let (mut min, mut max) = (first, first);
+            for &(x, y) in &cells {
+                min = (min.0.min(x), min.1.min(y));
+                max = (max.0.max(x), max.1.max(y));
+            }
+
+            (min.0, min.1, max.0 - min.0 + 1, max.1 - min.1 + 1)
+        }
     };
+
+    let (x, y, w, h) = rect;
+    if w < 1 || h < 1 || out_of_bounds((x, y), width, height) || w > width - x || h > height - y {
+        let msg = format!("crop {rect:?} doesn't fit inside of the {width}x{height} maze");
+        return Err(PyValueError::new_err(msg));
+    }
+
+    Ok(rect)
+}
+
+/// wraps encoded image data in a Python `io.BytesIO` buffer
+fn into_bytes_io<'py>(py: Python<'py>, buf: Vec<u8>) -> PyResult<&'py PyAny> {
+    let io = py.import("io")?;
+    let builtins = py.import("builtins")?;
+
+    let data = PyTuple::new(py, [buf]);
+    let arr = builtins.getattr("bytearray")?.call1(data)?;
+
+    let init_bytes = PyTuple::new(py, [arr]);
+    io.getattr("BytesIO")?.call1(init_bytes)
+}
+
+/// checks over `(direction, amount, is_max)` tuples from Python and turns them into moves
+fn extract_moves(moves: Vec<MoveArg>) -> PyResult<Vec<Move>> {
+    moves
+        .into_iter()
+        .map(|(AnyDirection(direction), amount, is_max)| {
+            if !matches!(direction, (0, -1) | (0, 1) | (-1, 0) | (1, 0)) {
+                let msg =
+                    format!("expected UP, DOWN, LEFT, or RIGHT as a direction; got {direction:?}");
+                return Err(PyValueError::new_err(msg));
+            }
+
+            if !is_max && amount < 1 {
+                let msg = format!("move amounts must be at least 1; got {amount}");
+                return Err(PyValueError::new_err(msg));
+            }
+
+            Ok(Move::new(direction, amount, is_max))
+        })
+        .collect()
+}
+
+/// turns an icon from Python into an image, either encoded `bytes`, a path to an image file,
+/// or a `PIL.Image.Image`
+///
+/// PIL images are duck-typed so that Pillow doesn't need to be installed for the other cases
+fn extract_icon(icon: &PyAny, name: &str) -> PyResult<Image<Pxl>> {
+    if let Ok(bytes) = icon.downcast::<PyBytes>() {
+        return bytes_to_image(bytes, name);
+    }
+
+    if let Ok(path) = icon.extract::<PathBuf>() {
+        return path_to_image(&path, name);
+    }
+
+    if !icon.hasattr("convert")? || !icon.hasattr("tobytes")? {
+        let msg = format!("{name} image must be image bytes, a path, or a PIL image");
+        return Err(PyTypeError::new_err(msg));
+    }
+
+    let rgba = icon.call_method1("convert", ("RGBA",))?;
+    let (w, h): (u32, u32) = rgba.getattr("size")?.extract()?;
+    let raw: Vec<u8> = rgba.call_method0("tobytes")?.extract()?;
+    Image::from_raw(w, h, raw).ok_or_else(|| {
+        PyValueError::new_err(format!("{name} image: pixel data doesn't match its size"))
+    })
+}
+
+/// turns a marker from Python into an icon, either anything that's a `Colour` or anything `extract_icon` takes
+///
+/// strings are only treated as paths if they aren't a hex code or colour name
+fn extract_marker(marker: &PyAny, supersample: u32) -> PyResult<Image<Pxl>> {
+    let is_colour_str = marker
+        .extract::<&str>()
+        .is_ok_and(|s| parse_colour(s).is_some());
+    if is_colour_str
+        || marker.is_instance_of::<PyTuple>()
+        || marker.is_instance_of::<PyList>()
+        || marker.is_instance_of::<PyLong>()
+    {
+        let Colour(colour) = marker.extract()?;
+        return Ok(marker_dot(colour, supersample));
+    }
+
+    let icon = extract_icon(marker, "marker")?;
+    Ok(fit_icon(icon, imageops::FilterType::Lanczos3))
 }
 
 /// new maze of a given width and height
+///
+/// the maze image isn't rendered until something first needs it (see `Maze.render()`), and with
+/// `render=False` it never is, for servers that only need to generate, solve, and move around in mazes;
+/// anything that needs the image then raises `RuntimeError`, and drawing the player, trail, fog, etc.
+/// just keeps track of them without drawing anything
 #[pyfunction]
-#[pyo3(signature = (*, width, height, bg_colour, wall_colour, solution_colour, player = None, endzone = None))]
+#[pyo3(signature = (*, width, height, bg_colour = None, bg_image = None, bg_image_fit = "stretch", wall_colour = None, solution_colour = None, solution_gradient = None, theme = None, palette = None, player = None, endzone = None, direction_labels = None, ascii_directions = false, record_generation = false, icon_filter = "lanczos3", player_facing = "fixed", trail_colour = None, trail_fade = 0, supersample = None, wall_cap = None, wall_dash = None, wall_jitter = 0, wall_texture = None, border = false, border_openings = false, margin = 0, render = true))]
 #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
 fn generate_maze<'py>(
     py: Python<'py>,
     width: i32,
     height: i32,
-    bg_colour: &'py PySequence,
-    wall_colour: &'py PySequence,
-    solution_colour: &'py PySequence,
-    player: Option<&'py PyBytes>,
-    endzone: Option<&'py PyBytes>,
+    bg_colour: Option<Colour>,
+    bg_image: Option<&'py PyAny>,
+    bg_image_fit: &str,
+    wall_colour: Option<Colour>,
+    solution_colour: Option<Colour>,
+    solution_gradient: Option<Colour>,
+    theme: Option<&'py PyAny>,
+    palette: Option<&str>,
+    player: Option<&'py PyAny>,
+    endzone: Option<&'py PyAny>,
+    direction_labels: Option<&'py PyDict>,
+    ascii_directions: bool,
+    record_generation: bool,
+    icon_filter: &str,
+    player_facing: &str,
+    trail_colour: Option<Colour>,
+    trail_fade: u32,
+    supersample: Option<u32>,
+    wall_cap: Option<&str>,
+    wall_dash: Option<(u32, u32)>,
+    wall_jitter: u8,
+    wall_texture: Option<&'py PyAny>,
+    border: bool,
+    border_openings: bool,
+    margin: u32,
+    render: bool,
 ) -> PyResult<Maze> {
-    into_rgba!(bg_colour);
-    into_rgba!(wall_colour);
-    into_rgba!(solution_colour);
+    let theme = match theme {
+        None => None,
+        Some(theme) => match theme.extract::<&str>() {
+            Ok(name) => Some(Theme::preset(name)?),
+            Err(_) => Some(theme.extract::<Theme>()?),
+        },
+    };
+
+    // a palette only swaps out the colours, so it goes on top of the theme's style if there's one of those too
+    let theme = match palette {
+        None => theme,
+        Some(name) => {
+            let palette = theme::palette(name)?;
+            Some(match theme {
+                None => Theme::from_palette(palette),
+                Some(theme) => theme.recoloured(palette),
+            })
+        }
+    };
+
+    let required = |colour: Option<Pxl>, name: &str| {
+        colour.ok_or_else(|| {
+            let msg = format!("generate_maze() needs either a theme or a {name}");
+            PyTypeError::new_err(msg)
+        })
+    };
+
+    let bg_colour = required(
+        bg_colour
+            .map(Pxl::from)
+            .or(theme.as_ref().map(|t| t.bg_colour)),
+        "bg_colour",
+    )?;
+    let wall_colour = required(
+        wall_colour
+            .map(Pxl::from)
+            .or(theme.as_ref().map(|t| t.wall_colour)),
+        "wall_colour",
+    )?;
+    let solution_colour = required(
+        solution_colour
+            .map(Pxl::from)
+            .or(theme.as_ref().map(|t| t.solution_colour)),
+        "solution_colour",
+    )?;
+
+    let trail_colour = trail_colour
+        .map(Pxl::from)
+        .or(theme.as_ref().and_then(|t| t.trail_colour));
+    let fog_colour = theme.as_ref().and_then(|t| t.fog_colour);
+
+    let mut draw_style = theme.as_ref().map(|t| t.style).unwrap_or_default();
+    override_style(&mut draw_style, wall_cap, wall_dash, supersample)?;
+
+    let frame = Frame {
+        border: border.then_some(wall_colour),
+        openings: border_openings,
+        margin,
+    };
 
     let (width, height) = (width, height);
-    let (walls, _) = generate_edges(width, height);
+    let direction_labels = extract_direction_labels(direction_labels, ascii_directions)?;
+
+    let (walls, paths) = generate_edges(width, height);
+    let icon_filter = extract_resize_filter(icon_filter, "an icon")?;
+
+    let Some(bg_image_fit) = BackgroundFit::from_name(bg_image_fit) else {
+        let msg = format!(
+            "expected one of \"stretch\" or \"tile\" as a background fit; got {bg_image_fit:?}"
+        );
+        return Err(PyValueError::new_err(msg));
+    };
+
+    let bg_image = match bg_image {
+        None => None,
+        Some(img) => {
+            let img = extract_icon(img, "background")?;
+            if img.width() == 0 || img.height() == 0 {
+                return Err(PyValueError::new_err("background image can't be empty"));
+            }
+
+            Some(img)
+        }
+    };
+
+    // the fallback icons go by what the background looks like overall, which is about the same before it's fitted
+    let mut background = Background {
+        colour: bg_colour,
+        image: bg_image,
+    };
+    let icon_bg = average_colour(&background);
+
+    // a maze that's never rendered doesn't need a background the size of its image
+    let size = maze_image_size(width, height);
+    background.image = background
+        .image
+        .take()
+        .filter(|_| render)
+        .map(|img| py.allow_threads(|| fit_background(&img, bg_image_fit, size)));
+
     let player_icon = match player {
-        None => fallback_image("player", bg_colour),
-        Some(img) => bytes_to_image(img, "player")?,
+        None => fallback_image("player", icon_bg, wall_colour)?,
+        Some(img) => extract_icon(img, "player")?,
+    };
+
+    let (player_icon, player_sprites) = match player_facing {
+        "fixed" => (fit_icon(player_icon, icon_filter), None),
+        // the fallback icon is a single frame rather than a spritesheet, so it's turned like `"rotate"` does
+        "rotate" | "spritesheet" if player_facing == "rotate" || player.is_none() => {
+            let icon = fit_icon(player_icon, icon_filter);
+            let sprites = rotated_sprites(&icon);
+            (icon, Some(sprites))
+        }
+        "spritesheet" => {
+            if player_icon.width() < 4 {
+                let msg = "player spritesheet has to be at least 4 pixels wide (one per frame)";
+                return Err(PyValueError::new_err(msg));
+            }
+
+            let sprites = split_spritesheet(&player_icon).map(|frame| fit_icon(frame, icon_filter));
+            (sprites[0].clone(), Some(sprites))
+        }
+        _ => {
+            let msg = format!("expected one of \"fixed\", \"rotate\", or \"spritesheet\" as a player facing mode; got {player_facing:?}");
+            return Err(PyValueError::new_err(msg));
+        }
     };
 
     let end_icon = match endzone {
-        None => fallback_image("endzone", bg_colour),
-        Some(img) => bytes_to_image(img, "endzone")?,
+        None => fit_icon(
+            fallback_image("endzone", icon_bg, wall_colour)?,
+            icon_filter,
+        ),
+        Some(img) => fit_icon(extract_icon(img, "endzone")?, icon_filter),
+    };
+
+    let texture = match wall_texture {
+        None => None,
+        Some(img) => {
+            let texture = extract_icon(img, "wall texture")?;
+            if texture.width() == 0 || texture.height() == 0 {
+                return Err(PyValueError::new_err("wall texture can't be empty"));
+            }
+
+            Some(texture)
+        }
     };
 
-    // screw the GIL
-    let maze_image =
-        py.allow_threads(|| maze_image(&walls, bg_colour, wall_colour, &end_icon, width, height));
+    let wall_fill = WallFill {
+        colour: wall_colour,
+        jitter: wall_jitter,
+        // only has to differ between mazes, so the hasher's random keys are good enough
+        seed: RandomState::new().hash_one(0),
+        texture,
+    };
 
     Ok(Maze {
         walls,
-        maze_image,
+        walls_version: 0,
+        carve_order: record_generation.then_some(paths),
+        views: 0,
+        canvas: None,
+        headless: !render,
         width,
         height,
-        bg_colour,
+        background,
+        wall_fill,
         player_icon,
+        player_sprites,
+        player_facing: (0, -1),
+        player_pos: None,
+        player_drawn_at: 0,
+        players: vec![],
+        chasers: vec![],
+        draws: 0,
+        markers: vec![],
+        collected_items: vec![],
+        portals: vec![],
+        switches: vec![],
+        terrain: HashMap::new(),
+        checkpoints: vec![],
+        checkpoints_reached: vec![],
+        history: vec![],
+        trail_colour,
+        trail: vec![],
+        trail_fade,
+        fog: None,
+        draw_style,
+        frame,
+        watermark: None,
+        fog_colour,
+        end_icon,
         solution_colour,
-        solution_moves: None,
+        solution_gradient: solution_gradient.map(Pxl::from),
+        solution: None,
+        solution_drawn: false,
+        direction_labels,
     })
 }
 
-const ALL: [&str; 8] = [
+/// loads a maze saved with `Maze.to_json()`, with its walls, colours, and game state
+///
+/// images and the rest of how the maze looks aren't saved, so they can be passed in again as `style`,
+/// which takes any of `generate_maze`'s arguments other than the size (the saved colours are used unless they're given)
+#[pyfunction]
+#[pyo3(signature = (s, /, **style))]
+fn from_json(py: Python, s: &str, style: Option<&PyDict>) -> PyResult<Py<Maze>> {
+    let loaded = py.import("json")?.call_method1("loads", (s,))?;
+    let Ok(dict) = loaded.downcast::<PyDict>() else {
+        return Err(PyValueError::new_err(
+            "invalid saved maze: expected a JSON object",
+        ));
+    };
+
+    maze_from_saved(py, SavedMaze::from_dict(dict)?, style)
+}
+
+/// loads a maze saved with `Maze.to_bytes()`, taking the same `style` as `from_json`
+#[pyfunction]
+#[pyo3(signature = (data, /, **style))]
+fn from_bytes(py: Python, data: &[u8], style: Option<&PyDict>) -> PyResult<Py<Maze>> {
+    maze_from_saved(py, SavedMaze::from_bytes(data)?, style)
+}
+
+/// generates a maze in the saved maze's size and colours (plus whatever `style` changes), then loads the rest onto it
+fn maze_from_saved(py: Python, saved: SavedMaze, style: Option<&PyDict>) -> PyResult<Py<Maze>> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("bg_colour", hex_colour(saved.bg_colour))?;
+    kwargs.set_item("wall_colour", hex_colour(saved.wall_colour))?;
+    kwargs.set_item("solution_colour", hex_colour(saved.solution_colour))?;
+    kwargs.set_item("solution_gradient", saved.solution_gradient.map(hex_colour))?;
+    kwargs.set_item("trail_colour", saved.trail_colour.map(hex_colour))?;
+    if let Some(style) = style {
+        for key in ["width", "height"] {
+            if style.contains(key)? {
+                let msg = format!(
+                    "the size of a saved maze can't be changed, so `{key}` can't be passed in"
+                );
+                return Err(PyTypeError::new_err(msg));
+            }
+        }
+
+        kwargs.update(style.as_mapping())?;
+    }
+
+    kwargs.set_item("width", saved.width)?;
+    kwargs.set_item("height", saved.height)?;
+
+    // the generated walls get thrown out, but everything else about how the maze looks is built the usual way
+    let maze: &PyCell<Maze> = wrap_pyfunction!(generate_maze, py)?
+        .call((), Some(kwargs))?
+        .downcast()?;
+    maze.borrow_mut().load_saved(py, saved);
+    Ok(maze.into())
+}
+
+/// joins a grid of mazes into one bigger maze, `grid` being a list of rows (so `[[a, b]]` puts `a` and `b` side by side),
+/// whose solver and renderer work across the seams like it was generated in one go
+///
+/// every maze in a row has to be as tall as the others, and every maze in a column as wide;
+/// the stitched maze looks like the top left one, and the start and goal are its top left and bottom right corners
+///
+/// `doorways` are pairs of neighbouring cells (in the stitched maze's coordinates) on either side of a seam
+/// to open up, every maze has to be reachable through them; without any, each pair of neighbouring mazes
+/// gets a doorway somewhere random along the seam between them
+#[pyfunction]
+#[pyo3(signature = (grid, /, *, doorways = None))]
+fn stitch_mazes(
+    grid: Vec<Vec<PyRef<Maze>>>,
+    doorways: Option<Vec<(AnyPoint, AnyPoint)>>,
+) -> PyResult<Maze> {
+    let columns = grid.first().map_or(0, Vec::len);
+    if columns == 0 || grid.iter().any(|row| row.len() != columns) {
+        let msg = "grid has to be a non-empty list of rows that all have the same amount of mazes";
+        return Err(PyValueError::new_err(msg));
+    }
+
+    let heights: Vec<i32> = grid.iter().map(|row| row[0].height).collect();
+    let widths: Vec<i32> = grid[0].iter().map(|m| m.width).collect();
+    let mut pieces = vec![];
+    let mut y0 = 0;
+    for (r, row) in grid.iter().enumerate() {
+        let mut x0 = 0;
+        for (c, m) in row.iter().enumerate() {
+            if (m.width, m.height) != (widths[c], heights[r]) {
+                let msg = format!(
+                    "the {}x{} maze at row {r}, column {c} doesn't line up with the rest of its row and column, \
+                     which are {} cells tall and {} cells wide",
+                    m.width, m.height, heights[r], widths[c]
+                );
+                return Err(PyValueError::new_err(msg));
+            }
+
+            pieces.push(Piece {
+                walls: &m.walls,
+                offset: (x0, y0),
+                size: (m.width, m.height),
+            });
+            x0 += m.width;
+        }
+
+        y0 += heights[r];
+    }
+
+    let (width, height) = (widths.iter().sum(), heights.iter().sum());
+    let seams = Seams::new(&pieces, width, height);
+    let doorways: EdgeSet = match doorways {
+        Some(pairs) => {
+            let pairs: EdgeVec = pairs.into_iter().map(|(a, b)| (a.0, b.0)).collect();
+            for &(a, b) in &pairs {
+                if out_of_bounds(a, width, height)
+                    || out_of_bounds(b, width, height)
+                    || !seams.crosses(a, b)
+                {
+                    let msg = format!("{a:?} and {b:?} aren't neighbours on either side of a seam");
+                    return Err(PyValueError::new_err(msg));
+                }
+            }
+
+            pairs.into_iter().collect()
+        }
+        None => {
+            let random = RandomState::new();
+            seams
+                .edges
+                .iter()
+                .map(|(key, edges)| edges[random.hash_one(key) as usize % edges.len()])
+                .collect()
+        }
+    };
+
+    let walls = seams.stitch_walls(&pieces, &doorways);
+    if reachable_cells(&walls, (0, 0), width, height).len() != (width * height) as usize {
+        let msg = "not every maze can be reached through the doorways";
+        return Err(PyValueError::new_err(msg));
+    }
+
+    let first = &grid[0][0];
+    let background = Background {
+        colour: first.background.colour,
+        image: first.background.image.as_ref().map(|_| {
+            let (w, h) = maze_image_size(width, height);
+            let mut img = Image::new(w, h);
+            for (piece, m) in pieces.iter().zip(grid.iter().flatten()) {
+                if let Some(bg) = &m.background.image {
+                    let (x, y) = piece.offset;
+                    imageops::overlay(&mut img, bg, i64::from(x) * 40, i64::from(y) * 40);
+                }
+            }
+
+            img
+        }),
+    };
+
+    Ok(first.with_walls(walls, width, height, background))
+}
+
+/// sets the directory that fallback player/endzone icons are loaded from, named like
+/// `player-white.png` and `endzone-black.png`, pass `None` to go back to the built-in icons
+///
+/// with `strict` on, an icon that can't be loaded raises an error in `generate_maze`;
+/// otherwise it falls back to the built-in icon, and then to a translucent square
+#[pyfunction]
+#[pyo3(signature = (path, /, *, strict = false))]
+fn set_asset_dir(path: Option<PathBuf>, strict: bool) -> PyResult<()> {
+    if let Some(dir) = path.as_ref().filter(|dir| !dir.is_dir()) {
+        let msg = format!("{} is not a directory", dir.display());
+        return Err(PyNotADirectoryError::new_err(msg));
+    }
+
+    let mut settings = ASSET_SETTINGS.write().unwrap_or_else(|e| e.into_inner());
+    *settings = AssetSettings { dir: path, strict };
+    Ok(())
+}
+
+const ALL: [&str; 18] = [
     "__version__",
     "Maze",
+    "Solution",
+    "Cell",
+    "Direction",
+    "Point",
+    "Theme",
     "generate_maze",
+    "set_asset_dir",
+    "stitch_mazes",
+    "from_json",
+    "from_bytes",
+    "Validation",
     "SolutionNotFound",
     "UP",
     "DOWN",
@@ -234,15 +4285,27 @@ const ALL: [&str; 8] = [
 
 #[pymodule]
 fn maze(py: Python, m: &PyModule) -> PyResult<()> {
+    load_fallback_icons();
+
     m.add_function(wrap_pyfunction!(generate_maze, m)?)?;
+    m.add_function(wrap_pyfunction!(set_asset_dir, m)?)?;
+    m.add_function(wrap_pyfunction!(stitch_mazes, m)?)?;
+    m.add_function(wrap_pyfunction!(from_json, m)?)?;
+    m.add_function(wrap_pyfunction!(from_bytes, m)?)?;
     m.add_class::<Maze>()?;
+    m.add_class::<Solution>()?;
+    m.add_class::<Cell>()?;
+    m.add_class::<Direction>()?;
+    m.add_class::<NamedPoint>()?;
+    m.add_class::<Theme>()?;
+    m.add_class::<Validation>()?;
 
     m.add("SolutionNotFound", py.get_type::<SolutionNotFound>())?;
 
-    m.add("UP", (0, -1))?;
-    m.add("DOWN", (0, 1))?;
-    m.add("LEFT", (-1, 0))?;
-    m.add("RIGHT", (1, 0))?;
+    m.add("UP", Direction::UP)?;
+    m.add("DOWN", Direction::DOWN)?;
+    m.add("LEFT", Direction::LEFT)?;
+    m.add("RIGHT", Direction::RIGHT)?;
 
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
     m.add("__all__", ALL)?;
@@ -0,0 +1,114 @@
+// pyo3 0.20's `#[new]` expands into impls that newer compilers flag as non-local
+#![allow(non_local_definitions)]
+
+use crate::types::Point;
+
+use pyo3::prelude::*;
+
+use pyo3::{
+    exceptions::{PyIndexError, PyTypeError},
+    pyclass::CompareOp,
+    types::{PyIterator, PySequence, PyTuple},
+};
+
+/// an XY coordinate handed back to Python, with `.x` and `.y` to make bot code easier to read
+///
+/// positions used to be plain `(x, y)` tuples, so it still unpacks, indexes, compares,
+/// and hashes like one to keep older code working
+#[pyclass(module = "maze", name = "Point", frozen)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NamedPoint {
+    #[pyo3(get)]
+    x: i32,
+    #[pyo3(get)]
+    y: i32,
+}
+
+impl From<Point> for NamedPoint {
+    fn from((x, y): Point) -> Self {
+        Self { x, y }
+    }
+}
+
+#[pymethods]
+impl NamedPoint {
+    #[new]
+    pub(crate) const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    fn __richcmp__(&self, py: Python, other: &PyAny, op: CompareOp) -> PyObject {
+        let Ok(other) = other.extract::<AnyPoint>() else {
+            return py.NotImplemented();
+        };
+
+        match op {
+            CompareOp::Eq => ((self.x, self.y) == other.0).into_py(py),
+            CompareOp::Ne => ((self.x, self.y) != other.0).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    /// the same as the `(x, y)` tuple's hash, since the two compare equal
+    fn __hash__(&self, py: Python) -> PyResult<isize> {
+        PyTuple::new(py, [self.x, self.y]).hash()
+    }
+
+    fn __len__(&self) -> usize {
+        2
+    }
+
+    fn __getitem__(&self, idx: isize) -> PyResult<i32> {
+        match idx {
+            0 | -2 => Ok(self.x),
+            1 | -1 => Ok(self.y),
+            _ => Err(PyIndexError::new_err("point index out of range")),
+        }
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<&'py PyIterator> {
+        PyIterator::from_object(PyTuple::new(py, [self.x, self.y]))
+    }
+
+    const fn __getnewargs__(&self) -> (i32, i32) {
+        (self.x, self.y)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Point(x={}, y={})", self.x, self.y)
+    }
+}
+
+/// a point from Python, a `Point` or any other 2-item sequence of ints (tuples, lists, etc.)
+pub struct AnyPoint(pub Point);
+
+impl<'source> FromPyObject<'source> for AnyPoint {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(point) = ob.downcast::<PyCell<NamedPoint>>() {
+            let point = point.get();
+            return Ok(Self((point.x, point.y)));
+        }
+
+        if let Ok(xy) = ob.extract::<Point>() {
+            return Ok(Self(xy));
+        }
+
+        let invalid = || {
+            let msg = format!(
+                "expected an (x, y) point; got {}",
+                ob.repr().map_or_else(|_| "?".into(), ToString::to_string)
+            );
+            PyTypeError::new_err(msg)
+        };
+
+        // strings are sequences too, but never of ints
+        let seq = ob.downcast::<PySequence>().map_err(|_| invalid())?;
+        if seq.len()? != 2 {
+            return Err(invalid());
+        }
+
+        let x = seq.get_item(0)?.extract().map_err(|_| invalid())?;
+        let y = seq.get_item(1)?.extract().map_err(|_| invalid())?;
+        Ok(Self((x, y)))
+    }
+}
@@ -0,0 +1,140 @@
+use pyo3::prelude::*;
+use pyo3::{exceptions::PyBufferError, ffi, types::PyBytes};
+
+use std::{
+    io,
+    os::raw::{c_int, c_void},
+    ptr,
+};
+
+/// adapts a Python file-like object (anything with a `write` method) into an `io::Write`
+pub struct PyFileWriter<'py> {
+    file: &'py PyAny,
+    /// whether `file` is a raw (unbuffered) stream, which returns `None` from `write` when nothing could be written
+    raw: bool,
+    /// the exception raised by the last failed call to `write`, so it can be re-raised as-is
+    pub error: Option<PyErr>,
+}
+
+impl<'py> PyFileWriter<'py> {
+    pub fn new(file: &'py PyAny) -> Self {
+        let raw = file
+            .py()
+            .import("io")
+            .and_then(|io| file.is_instance(io.getattr("RawIOBase")?))
+            .unwrap_or(false);
+
+        Self {
+            file,
+            raw,
+            error: None,
+        }
+    }
+
+    /// the error to raise after writing failed, preferring the Python exception if there was one
+    pub fn into_err(self, e: impl std::fmt::Display) -> PyErr {
+        self.error.unwrap_or_else(|| {
+            pyo3::exceptions::PyIOError::new_err(format!("could not write image: {e}"))
+        })
+    }
+}
+
+impl io::Write for PyFileWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let data = PyBytes::new(self.file.py(), buf);
+        match self.file.call_method1("write", (data,)) {
+            // non-blocking raw streams return `None` when they'd block, which isn't the same as having
+            // written everything like it is for other file-likes that don't say how much they wrote
+            Ok(written) if written.is_none() && self.raw => Err(io::ErrorKind::WouldBlock.into()),
+            // raw (unbuffered) streams may write less than they're given
+            Ok(written) => Ok(written.extract().unwrap_or(buf.len())),
+            Err(e) => {
+                let msg = e.to_string();
+                self.error = Some(e);
+                Err(io::Error::other(msg))
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.file.hasattr("flush").unwrap_or(false) {
+            return Ok(());
+        }
+
+        match self.file.call_method0("flush") {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                let msg = e.to_string();
+                self.error = Some(e);
+                Err(io::Error::other(msg))
+            }
+        }
+    }
+}
+
+/// fills in a read-only, C-contiguous `(height, width, 4)` view over an RGBA image's pixels
+///
+/// the shape and strides are boxed up and stashed in `view.internal`,
+/// so `release_image_view` has to be called on the view once Python is done with it
+///
+/// # Safety
+/// `view` has to point to a `Py_buffer` handed over by Python, and `data` has to stay alive
+/// (and not be reallocated) until the view is released, which `owner` is responsible for
+pub unsafe fn fill_image_view(
+    view: *mut ffi::Py_buffer,
+    flags: c_int,
+    data: &[u8],
+    (width, height): (u32, u32),
+    owner: &PyAny,
+) -> PyResult<()> {
+    if view.is_null() {
+        return Err(PyBufferError::new_err("view is null"));
+    }
+
+    if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+        return Err(PyBufferError::new_err("the maze image is read-only"));
+    }
+
+    let (w, h) = (width as isize, height as isize);
+    // `[shape..., strides...]`
+    let dims = Box::into_raw(Box::new([h, w, 4, w * 4, 4, 1]));
+
+    (*view).obj = ffi::_Py_NewRef(owner.as_ptr());
+    (*view).buf = data.as_ptr() as *mut c_void;
+    (*view).len = data.len() as isize;
+    (*view).readonly = 1;
+    (*view).itemsize = 1;
+    (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+        c"B".as_ptr().cast_mut()
+    } else {
+        ptr::null_mut()
+    };
+
+    // without `PyBUF_ND`, the consumer wants a flat run of bytes
+    if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+        (*view).ndim = 3;
+        (*view).shape = dims.cast::<isize>();
+    } else {
+        (*view).ndim = 1;
+        (*view).shape = ptr::null_mut();
+    }
+
+    (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+        dims.cast::<isize>().add(3)
+    } else {
+        ptr::null_mut()
+    };
+
+    (*view).suboffsets = ptr::null_mut();
+    (*view).internal = dims.cast();
+
+    Ok(())
+}
+
+/// frees what `fill_image_view` allocated for a view
+///
+/// # Safety
+/// `view` has to have been filled in by `fill_image_view`, and can't be released twice
+pub unsafe fn release_image_view(view: *mut ffi::Py_buffer) {
+    drop(Box::from_raw((*view).internal.cast::<[isize; 6]>()));
+}
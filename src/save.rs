@@ -0,0 +1,1119 @@
+use crate::algorithms::MAX_SWITCHES;
+use crate::colour::{hex_colour, Colour};
+use crate::theme::override_style;
+use crate::types::{
+    Chaser, Corner, DirectionLabels, DrawStyle, EdgeSet, EdgeVec, Fog, Frame, Marker, Player,
+    Point, Portal, Pxl, Stamp, Switch, Terrain, WallCap, Watermark,
+};
+use crate::util::{out_of_bounds, wall_between};
+
+use std::collections::{HashMap, HashSet};
+
+use imageproc::definitions::Image;
+use pyo3::prelude::*;
+use pyo3::{
+    exceptions::PyValueError,
+    types::{PyBytes, PyDict},
+};
+
+/// written into every saved maze, and bumped whenever what gets saved changes
+pub const SAVE_VERSION: u32 = 1;
+
+/// the first bytes of every maze saved with `.to_bytes()`
+const MAGIC: &[u8; 2] = b"MZ";
+
+// which of the optional parts a packed maze has, packed as a varint
+const HAS_GRADIENT: u64 = 1;
+const HAS_TRAIL_COLOUR: u64 = 1 << 1;
+const HAS_PLAYER: u64 = 1 << 2;
+const HAS_FOG: u64 = 1 << 3;
+const SOLVED: u64 = 1 << 4;
+const SOLUTION_DRAWN: u64 = 1 << 5;
+const HAS_PORTALS: u64 = 1 << 6;
+const HAS_SWITCHES: u64 = 1 << 7;
+const HAS_TERRAIN: u64 = 1 << 8;
+const HAS_CHECKPOINTS: u64 = 1 << 9;
+
+/// the widest or tallest a saved maze can be
+const MAX_SIZE: i32 = 1 << 15;
+
+/// makes sure a saved maze's size is in range, before anything is worked out from it
+fn check_size(width: i32, height: i32) -> PyResult<()> {
+    if (1..=MAX_SIZE).contains(&width) && (1..=MAX_SIZE).contains(&height) {
+        Ok(())
+    } else {
+        Err(invalid(format!("a {width}x{height} maze isn't possible")))
+    }
+}
+
+/// everything about a maze that gets saved, its walls, its colours, and the game being played on it
+///
+/// icons, images, and the rest of how the maze looks aren't, they get passed in again when it's loaded
+#[derive(Clone, Debug)]
+pub struct SavedMaze {
+    pub width: i32,
+    pub height: i32,
+    pub walls: EdgeSet,
+    pub bg_colour: Pxl,
+    pub wall_colour: Pxl,
+    pub solution_colour: Pxl,
+    pub solution_gradient: Option<Pxl>,
+    pub trail_colour: Option<Pxl>,
+    /// decides how far each wall gets nudged when they're jittered, so they look the same after loading
+    pub wall_seed: u64,
+    pub player_pos: Option<Point>,
+    pub player_facing: (i32, i32),
+    pub history: EdgeVec,
+    pub trail: EdgeVec,
+    pub fog: Option<Fog>,
+    pub portals: Vec<Portal>,
+    pub switches: Vec<Switch>,
+    /// every cell that isn't normal ground
+    pub terrain: HashMap<Point, Terrain>,
+    pub checkpoints: Vec<Point>,
+    /// how many moves into the history the player was when it reached each checkpoint
+    pub checkpoints_reached: Vec<usize>,
+    /// whether the solution had been computed
+    pub solved: bool,
+    pub solution_drawn: bool,
+}
+
+impl SavedMaze {
+    /// makes sure that everything fits inside of the maze, since it might've been edited by hand
+    pub fn check(&self) -> PyResult<()> {
+        let (w, h) = (self.width, self.height);
+        check_size(w, h)?;
+
+        let outside = |xy: &Point| out_of_bounds(*xy, w, h);
+        let cells = self
+            .walls
+            .iter()
+            .chain(&self.history)
+            .chain(&self.trail)
+            .chain(self.portals.iter().map(|p| &p.ends))
+            .chain(self.switches.iter().flat_map(|s| &s.walls))
+            .flat_map(|(a, b)| [a, b])
+            .chain(self.switches.iter().map(|s| &s.xy))
+            .chain(self.terrain.keys())
+            .chain(&self.checkpoints)
+            .chain(&self.player_pos)
+            .chain(self.fog.iter().flat_map(|fog| &fog.revealed));
+
+        if let Some(xy) = cells.into_iter().find(|xy| outside(xy)) {
+            return Err(invalid(format!("{xy:?} is outside of the {w}x{h} maze")));
+        }
+
+        if let Some((a, b)) = self
+            .walls
+            .iter()
+            .chain(self.switches.iter().flat_map(|s| &s.walls))
+            .find(|(a, b)| (a.0 - b.0).abs() + (a.1 - b.1).abs() != 1)
+        {
+            return Err(invalid(format!(
+                "{a:?} and {b:?} aren't next to each other, so there can't be a wall between them"
+            )));
+        }
+
+        let mut portal_cells = HashSet::new();
+        for (a, b) in self.portals.iter().map(|p| p.ends) {
+            if a == b || !portal_cells.insert(a) || !portal_cells.insert(b) {
+                return Err(invalid(format!(
+                    "the portal between {a:?} and {b:?} overlaps with itself or another portal"
+                )));
+            }
+        }
+
+        if self.switches.len() > MAX_SWITCHES {
+            let msg = format!("a maze can't have more than {MAX_SWITCHES} switches");
+            return Err(invalid(msg));
+        }
+
+        for xy in self.switches.iter().map(|s| s.xy) {
+            if !portal_cells.insert(xy) {
+                return Err(invalid(format!(
+                    "the switch at {xy:?} overlaps with a portal or another switch"
+                )));
+            }
+        }
+
+        let mut checkpoints = HashSet::new();
+        if let Some(xy) = self.checkpoints.iter().find(|xy| !checkpoints.insert(*xy)) {
+            return Err(invalid(format!(
+                "there's more than one checkpoint at {xy:?}"
+            )));
+        }
+
+        let mut last = 0;
+        for &reached in &self.checkpoints_reached {
+            if reached < last || reached > self.history.len() {
+                let msg =
+                    "the checkpoints were reached out of order, or after the end of the history";
+                return Err(invalid(msg.to_string()));
+            }
+            last = reached;
+        }
+
+        if self.checkpoints_reached.len() > self.checkpoints.len() {
+            let msg = "more checkpoints were reached than there are";
+            return Err(invalid(msg.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// the dict that gets dumped to JSON
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let (w, h) = (self.width, self.height);
+        let mut walls: EdgeVec = self.walls.iter().copied().collect();
+        walls.sort_unstable();
+
+        let colours = PyDict::new(py);
+        colours.set_item("bg", hex_colour(self.bg_colour))?;
+        colours.set_item("wall", hex_colour(self.wall_colour))?;
+        colours.set_item("solution", hex_colour(self.solution_colour))?;
+        colours.set_item("solution_gradient", self.solution_gradient.map(hex_colour))?;
+        colours.set_item("trail", self.trail_colour.map(hex_colour))?;
+
+        let player = PyDict::new(py);
+        player.set_item("xy", self.player_pos)?;
+        player.set_item("facing", self.player_facing)?;
+
+        let fog = match &self.fog {
+            None => None,
+            Some(fog) => {
+                let mut revealed: Vec<Point> = fog.revealed.iter().copied().collect();
+                revealed.sort_unstable();
+
+                let dict = PyDict::new(py);
+                dict.set_item("colour", hex_colour(fog.colour))?;
+                dict.set_item("radius", fog.radius)?;
+                dict.set_item("revealed", revealed)?;
+                Some(dict)
+            }
+        };
+
+        let portals = self
+            .portals
+            .iter()
+            .map(|p| {
+                let dict = PyDict::new(py);
+                dict.set_item("ends", p.ends)?;
+                dict.set_item("colour", hex_colour(p.colour))?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let switches = self
+            .switches
+            .iter()
+            .map(|s| {
+                let dict = PyDict::new(py);
+                dict.set_item("xy", s.xy)?;
+                dict.set_item("walls", &s.walls)?;
+                dict.set_item("colour", hex_colour(s.colour))?;
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        let terrain = PyDict::new(py);
+        for kind in [Terrain::Ice, Terrain::Mud] {
+            let mut cells: Vec<Point> = self
+                .terrain
+                .iter()
+                .filter(|(_, t)| **t == kind)
+                .map(|(xy, _)| *xy)
+                .collect();
+            cells.sort_unstable();
+            terrain.set_item(kind.name(), cells)?;
+        }
+
+        let checkpoints = PyDict::new(py);
+        checkpoints.set_item("cells", &self.checkpoints)?;
+        checkpoints.set_item("reached_at", &self.checkpoints_reached)?;
+
+        let solution = PyDict::new(py);
+        solution.set_item("computed", self.solved)?;
+        solution.set_item("drawn", self.solution_drawn)?;
+
+        let dict = PyDict::new(py);
+        dict.set_item("version", SAVE_VERSION)?;
+        dict.set_item("width", w)?;
+        dict.set_item("height", h)?;
+        dict.set_item("start", (0, 0))?;
+        dict.set_item("goal", (w - 1, h - 1))?;
+        dict.set_item("walls", walls)?;
+        dict.set_item("colours", colours)?;
+        dict.set_item("wall_seed", self.wall_seed)?;
+        dict.set_item("player", player)?;
+        dict.set_item("history", &self.history)?;
+        dict.set_item("trail", &self.trail)?;
+        dict.set_item("fog", fog)?;
+        dict.set_item("portals", portals)?;
+        dict.set_item("switches", switches)?;
+        dict.set_item("terrain", terrain)?;
+        dict.set_item("checkpoints", checkpoints)?;
+        dict.set_item("solution", solution)?;
+        Ok(dict)
+    }
+
+    /// reads back what `.to_dict()` wrote, after it's been through JSON
+    pub fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let version: u32 = field(dict, "version")?;
+        if version != SAVE_VERSION {
+            let msg = format!(
+                "can't load a maze saved in version {version} of the format, only {SAVE_VERSION}"
+            );
+            return Err(invalid(msg));
+        }
+
+        let (width, height) = (field(dict, "width")?, field(dict, "height")?);
+        check_size(width, height)?;
+        let ([sx, sy], [gx, gy]): ([i32; 2], [i32; 2]) =
+            (field(dict, "start")?, field(dict, "goal")?);
+        if (sx, sy) != (0, 0) || (gx, gy) != (width - 1, height - 1) {
+            let msg = "only mazes that start in the top left corner and end in the bottom right one can be loaded";
+            return Err(invalid(msg.to_string()));
+        }
+
+        let colours: &PyDict = field(dict, "colours")?;
+        let player: &PyDict = field(dict, "player")?;
+        let solution: &PyDict = field(dict, "solution")?;
+        let fog = match field::<Option<&PyDict>>(dict, "fog")? {
+            None => None,
+            Some(fog) => Some(Fog {
+                colour: field::<Colour>(fog, "colour")?.into(),
+                radius: field(fog, "radius")?,
+                revealed: cells(field(fog, "revealed")?).into_iter().collect(),
+            }),
+        };
+
+        let portals = field::<Vec<&PyDict>>(dict, "portals")?
+            .into_iter()
+            .map(|p| {
+                let [[ax, ay], [bx, by]]: [[i32; 2]; 2] = field(p, "ends")?;
+                Ok(Portal {
+                    ends: ((ax, ay), (bx, by)),
+                    colour: field::<Colour>(p, "colour")?.into(),
+                })
+            })
+            .collect::<PyResult<_>>()?;
+
+        let switches = field::<Vec<&PyDict>>(dict, "switches")?
+            .into_iter()
+            .map(|s| {
+                let [x, y]: [i32; 2] = field(s, "xy")?;
+                Ok(Switch {
+                    xy: (x, y),
+                    walls: edges(field(s, "walls")?),
+                    colour: field::<Colour>(s, "colour")?.into(),
+                })
+            })
+            .collect::<PyResult<_>>()?;
+
+        let mut terrain = HashMap::new();
+        let kinds: HashMap<String, Vec<[i32; 2]>> = field(dict, "terrain")?;
+        for (name, points) in kinds {
+            let Some(kind) = Terrain::from_name(&name) else {
+                return Err(invalid(format!("{name:?} isn't a kind of terrain")));
+            };
+
+            if kind != Terrain::Normal {
+                terrain.extend(cells(points).into_iter().map(|xy| (xy, kind)));
+            }
+        }
+
+        let checkpoints: &PyDict = field(dict, "checkpoints")?;
+        let (checkpoints, checkpoints_reached) = (
+            cells(field(checkpoints, "cells")?),
+            field(checkpoints, "reached_at")?,
+        );
+
+        let [fx, fy]: [i32; 2] = field(player, "facing")?;
+        let saved = Self {
+            width,
+            height,
+            walls: edges(field(dict, "walls")?).into_iter().collect(),
+            bg_colour: field::<Colour>(colours, "bg")?.into(),
+            wall_colour: field::<Colour>(colours, "wall")?.into(),
+            solution_colour: field::<Colour>(colours, "solution")?.into(),
+            solution_gradient: field::<Option<Colour>>(colours, "solution_gradient")?
+                .map(Pxl::from),
+            trail_colour: field::<Option<Colour>>(colours, "trail")?.map(Pxl::from),
+            wall_seed: field(dict, "wall_seed")?,
+            player_pos: field::<Option<[i32; 2]>>(player, "xy")?.map(|[x, y]| (x, y)),
+            player_facing: (fx, fy),
+            history: edges(field(dict, "history")?),
+            trail: edges(field(dict, "trail")?),
+            fog,
+            portals,
+            switches,
+            terrain,
+            checkpoints,
+            checkpoints_reached,
+            solved: field(solution, "computed")?,
+            solution_drawn: field(solution, "drawn")?,
+        };
+
+        saved.check()?;
+        Ok(saved)
+    }
+
+    /// packs the maze into as few bytes as reasonably possible, with a bit for every spot a wall could be
+    ///
+    /// numbers are LEB128 varints (zigzagged if they can be negative) and cells are packed into a single
+    /// `y * width + x` number, so most of them only take a byte or two
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (w, h) = (self.width, self.height);
+        let mut out = Packer::default();
+        out.bytes.extend(MAGIC);
+        out.uint(u64::from(SAVE_VERSION));
+        out.uint(w as u64);
+        out.uint(h as u64);
+
+        let flags = [
+            (self.solution_gradient.is_some(), HAS_GRADIENT),
+            (self.trail_colour.is_some(), HAS_TRAIL_COLOUR),
+            (self.player_pos.is_some(), HAS_PLAYER),
+            (self.fog.is_some(), HAS_FOG),
+            (self.solved, SOLVED),
+            (self.solution_drawn, SOLUTION_DRAWN),
+            (!self.portals.is_empty(), HAS_PORTALS),
+            (!self.switches.is_empty(), HAS_SWITCHES),
+            (!self.terrain.is_empty(), HAS_TERRAIN),
+            (!self.checkpoints.is_empty(), HAS_CHECKPOINTS),
+        ];
+        out.uint(
+            flags
+                .iter()
+                .filter(|(set, _)| *set)
+                .map(|(_, bit)| bit)
+                .sum(),
+        );
+
+        for colour in [self.bg_colour, self.wall_colour, self.solution_colour]
+            .iter()
+            .chain(&self.solution_gradient)
+            .chain(&self.trail_colour)
+        {
+            out.bytes.extend(colour.0);
+        }
+
+        out.bytes.extend(self.wall_seed.to_le_bytes());
+        out.bits(
+            (0..h)
+                .flat_map(|y| (0..w - 1).map(move |x| ((x, y), (x + 1, y))))
+                .map(|(a, b)| wall_between(&self.walls, a, b)),
+        );
+        out.bits(
+            (0..h - 1)
+                .flat_map(|y| (0..w).map(move |x| ((x, y), (x, y + 1))))
+                .map(|(a, b)| wall_between(&self.walls, a, b)),
+        );
+
+        if let Some(xy) = self.player_pos {
+            out.cell(xy, w);
+        }
+
+        out.int(self.player_facing.0);
+        out.int(self.player_facing.1);
+        for moves in [&self.history, &self.trail] {
+            out.uint(moves.len() as u64);
+            for &(from, to) in moves {
+                out.cell(from, w);
+                out.cell(to, w);
+            }
+        }
+
+        if let Some(fog) = &self.fog {
+            out.bytes.extend(fog.colour.0);
+            out.int(fog.radius);
+            out.bits((0..h).flat_map(|y| (0..w).map(move |x| fog.revealed.contains(&(x, y)))));
+        }
+
+        if !self.portals.is_empty() {
+            out.uint(self.portals.len() as u64);
+            for p in &self.portals {
+                out.cell(p.ends.0, w);
+                out.cell(p.ends.1, w);
+                out.bytes.extend(p.colour.0);
+            }
+        }
+
+        if !self.switches.is_empty() {
+            out.uint(self.switches.len() as u64);
+            for s in &self.switches {
+                out.cell(s.xy, w);
+                out.uint(s.walls.len() as u64);
+                for &(a, b) in &s.walls {
+                    out.cell(a, w);
+                    out.cell(b, w);
+                }
+                out.bytes.extend(s.colour.0);
+            }
+        }
+
+        if !self.terrain.is_empty() {
+            let mut terrain: Vec<_> = self.terrain.iter().collect();
+            terrain.sort_unstable_by_key(|(xy, _)| **xy);
+            out.uint(terrain.len() as u64);
+            for (xy, kind) in terrain {
+                out.cell(*xy, w);
+                out.bytes.push(match kind {
+                    Terrain::Normal => 0,
+                    Terrain::Ice => 1,
+                    Terrain::Mud => 2,
+                });
+            }
+        }
+
+        if !self.checkpoints.is_empty() {
+            out.uint(self.checkpoints.len() as u64);
+            for xy in &self.checkpoints {
+                out.cell(*xy, w);
+            }
+
+            out.uint(self.checkpoints_reached.len() as u64);
+            for reached in &self.checkpoints_reached {
+                out.uint(*reached as u64);
+            }
+        }
+
+        out.bytes
+    }
+
+    /// reads back what `.to_bytes()` wrote
+    pub fn from_bytes(data: &[u8]) -> PyResult<Self> {
+        let Some(data) = data.strip_prefix(MAGIC) else {
+            return Err(invalid("these bytes aren't a packed maze".to_string()));
+        };
+
+        let mut input = Unpacker {
+            bytes: data,
+            pos: 0,
+        };
+        let version = input.uint()?;
+        if version != u64::from(SAVE_VERSION) {
+            let msg = format!(
+                "can't load a maze saved in version {version} of the format, only {SAVE_VERSION}"
+            );
+            return Err(invalid(msg));
+        }
+
+        let (w, h) = (input.size()?, input.size()?);
+        let flags = input.uint()?;
+        let has = |bit| flags & bit != 0;
+
+        let mut colour = || -> PyResult<Pxl> { Ok(image::Rgba(input.array()?)) };
+        let (bg_colour, wall_colour, solution_colour) = (colour()?, colour()?, colour()?);
+        let solution_gradient = has(HAS_GRADIENT).then(&mut colour).transpose()?;
+        let trail_colour = has(HAS_TRAIL_COLOUR).then(&mut colour).transpose()?;
+        let wall_seed = u64::from_le_bytes(input.array()?);
+
+        // the bits are read first, so that a maze that's too big for the bytes it came with fails before
+        // anything the size of it is built
+        let vertical_bits = input.bits((h * (w - 1)) as usize)?;
+        let horizontal_bits = input.bits(((h - 1) * w) as usize)?;
+        let vertical = (0..h).flat_map(|y| (0..w - 1).map(move |x| ((x, y), (x + 1, y))));
+        let horizontal = (0..h - 1).flat_map(|y| (0..w).map(move |x| ((x, y), (x, y + 1))));
+        let walls: EdgeSet = vertical
+            .zip(vertical_bits)
+            .chain(horizontal.zip(horizontal_bits))
+            .filter(|(_, wall)| *wall)
+            .map(|(e, _)| e)
+            .collect();
+
+        let player_pos = has(HAS_PLAYER).then(|| input.cell(w)).transpose()?;
+        let player_facing = (input.int()?, input.int()?);
+        let mut moves = || -> PyResult<EdgeVec> {
+            (0..input.uint()?)
+                .map(|_| Ok((input.cell(w)?, input.cell(w)?)))
+                .collect()
+        };
+        let (history, trail) = (moves()?, moves()?);
+
+        let fog = match has(HAS_FOG) {
+            false => None,
+            true => {
+                let colour = image::Rgba(input.array()?);
+                let radius = input.int()?;
+                let cells = (0..h).flat_map(|y| (0..w).map(move |x| (x, y)));
+                let bits = input.bits((w * h) as usize)?;
+                let revealed = cells
+                    .zip(bits)
+                    .filter(|(_, seen)| *seen)
+                    .map(|(xy, _)| xy)
+                    .collect();
+                Some(Fog {
+                    colour,
+                    radius,
+                    revealed,
+                })
+            }
+        };
+
+        let portals = match has(HAS_PORTALS) {
+            false => vec![],
+            true => (0..input.uint()?)
+                .map(|_| {
+                    Ok(Portal {
+                        ends: (input.cell(w)?, input.cell(w)?),
+                        colour: image::Rgba(input.array()?),
+                    })
+                })
+                .collect::<PyResult<_>>()?,
+        };
+
+        let switches = match has(HAS_SWITCHES) {
+            false => vec![],
+            true => (0..input.uint()?)
+                .map(|_| {
+                    let xy = input.cell(w)?;
+                    let walls = (0..input.uint()?)
+                        .map(|_| Ok((input.cell(w)?, input.cell(w)?)))
+                        .collect::<PyResult<_>>()?;
+                    Ok(Switch {
+                        xy,
+                        walls,
+                        colour: image::Rgba(input.array()?),
+                    })
+                })
+                .collect::<PyResult<_>>()?,
+        };
+
+        let terrain = match has(HAS_TERRAIN) {
+            false => HashMap::new(),
+            true => (0..input.uint()?)
+                .map(|_| {
+                    let xy = input.cell(w)?;
+                    let kind = match input.byte()? {
+                        1 => Terrain::Ice,
+                        2 => Terrain::Mud,
+                        n => return Err(invalid(format!("{n} isn't a kind of terrain"))),
+                    };
+                    Ok((xy, kind))
+                })
+                .collect::<PyResult<_>>()?,
+        };
+
+        let (checkpoints, checkpoints_reached) = match has(HAS_CHECKPOINTS) {
+            false => (vec![], vec![]),
+            true => {
+                let cells = (0..input.uint()?)
+                    .map(|_| input.cell(w))
+                    .collect::<PyResult<_>>()?;
+                let reached = (0..input.uint()?)
+                    .map(|_| Ok(input.uint()? as usize))
+                    .collect::<PyResult<_>>()?;
+                (cells, reached)
+            }
+        };
+
+        if input.pos != input.bytes.len() {
+            return Err(invalid(
+                "there are extra bytes after the end of the maze".to_string(),
+            ));
+        }
+
+        let saved = Self {
+            width: w,
+            height: h,
+            walls,
+            bg_colour,
+            wall_colour,
+            solution_colour,
+            solution_gradient,
+            trail_colour,
+            wall_seed,
+            player_pos,
+            player_facing,
+            history,
+            trail,
+            fog,
+            portals,
+            switches,
+            terrain,
+            checkpoints,
+            checkpoints_reached,
+            solved: has(SOLVED),
+            solution_drawn: has(SOLUTION_DRAWN),
+        };
+
+        saved.check()?;
+        Ok(saved)
+    }
+}
+
+/// everything about how a maze looks that `SavedMaze` leaves out, so that pickling (where none of it can be
+/// passed in again) keeps the whole maze
+///
+/// only text drawn straight onto the image is lost, since the image is rebuilt from scratch
+#[derive(Clone, Debug)]
+pub struct Look {
+    pub bg_image: Option<Image<Pxl>>,
+    pub wall_jitter: u8,
+    pub wall_texture: Option<Image<Pxl>>,
+    pub player_icon: Image<Pxl>,
+    pub player_sprites: Option<[Image<Pxl>; 4]>,
+    pub end_icon: Image<Pxl>,
+    pub trail_fade: u32,
+    pub draw_style: DrawStyle,
+    pub frame: Frame,
+    pub watermark: Option<Watermark>,
+    pub fog_colour: Option<Pxl>,
+    pub direction_labels: DirectionLabels,
+    pub markers: Vec<Marker>,
+    pub players: Vec<Player>,
+    pub chasers: Vec<Chaser>,
+    pub collected_items: Vec<Point>,
+    pub carve_order: Option<EdgeVec>,
+    pub headless: bool,
+}
+
+/// how images are pickled, `(width, height, rgba_bytes)`
+type ImageState<'py> = (u32, u32, &'py [u8]);
+
+impl Look {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<&'py PyDict> {
+        let image = |img: &Image<Pxl>| (img.width(), img.height(), PyBytes::new(py, img.as_raw()));
+        let labels = &self.direction_labels;
+        let watermark = self.watermark.as_ref().map(|w| {
+            let stamp: PyObject = match &w.stamp {
+                Stamp::Text { text, colour, size } => {
+                    ("text", text.clone(), hex_colour(*colour), *size).into_py(py)
+                }
+                Stamp::Image(img) => ("image", image(img)).into_py(py),
+            };
+
+            (stamp, w.corner.name(), w.inset)
+        });
+
+        let dict = PyDict::new(py);
+        dict.set_item("bg_image", self.bg_image.as_ref().map(image))?;
+        dict.set_item("wall_jitter", self.wall_jitter)?;
+        dict.set_item("wall_texture", self.wall_texture.as_ref().map(image))?;
+        dict.set_item("player_icon", image(&self.player_icon))?;
+        dict.set_item(
+            "player_sprites",
+            self.player_sprites
+                .as_ref()
+                .map(|s| s.each_ref().map(image)),
+        )?;
+        dict.set_item("end_icon", image(&self.end_icon))?;
+        dict.set_item("trail_fade", self.trail_fade)?;
+        dict.set_item("wall_cap", self.draw_style.wall_cap.name())?;
+        dict.set_item("wall_dash", self.draw_style.wall_dash)?;
+        dict.set_item("supersample", self.draw_style.supersample)?;
+        dict.set_item("border", self.frame.border.map(hex_colour))?;
+        dict.set_item("border_openings", self.frame.openings)?;
+        dict.set_item("margin", self.frame.margin)?;
+        dict.set_item("watermark", watermark)?;
+        dict.set_item("fog_colour", self.fog_colour.map(hex_colour))?;
+        dict.set_item(
+            "direction_labels",
+            [
+                &labels.up,
+                &labels.down,
+                &labels.left,
+                &labels.right,
+                &labels.max_up,
+                &labels.max_down,
+                &labels.max_left,
+                &labels.max_right,
+            ],
+        )?;
+        dict.set_item(
+            "markers",
+            self.markers
+                .iter()
+                .map(|m| (m.xy, m.tag.clone(), image(&m.icon)))
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "players",
+            self.players
+                .iter()
+                .map(|p| (p.id.clone(), p.pos, p.history.clone(), image(&p.icon)))
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item(
+            "chasers",
+            self.chasers
+                .iter()
+                .map(|c| {
+                    (
+                        c.id.clone(),
+                        c.pos,
+                        c.target.clone(),
+                        c.speed,
+                        image(&c.icon),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )?;
+        dict.set_item("collected_items", &self.collected_items)?;
+        dict.set_item("carve_order", &self.carve_order)?;
+        dict.set_item("headless", self.headless)?;
+        Ok(dict)
+    }
+
+    pub fn from_dict(dict: &PyDict) -> PyResult<Self> {
+        let labels: Vec<String> = field(dict, "direction_labels")?;
+        let mut direction_labels = DirectionLabels::default();
+        if labels.len() != DirectionLabels::KEYS.len() {
+            return Err(invalid("\"direction_labels\" is malformed".to_string()));
+        }
+
+        for (key, label) in DirectionLabels::KEYS.into_iter().zip(labels) {
+            direction_labels.set(key, label);
+        }
+
+        let Some(wall_cap) = WallCap::from_name(field(dict, "wall_cap")?) else {
+            return Err(invalid("\"wall_cap\" is malformed".to_string()));
+        };
+
+        let watermark = match field::<Option<(&PyAny, &str, u32)>>(dict, "watermark")? {
+            None => None,
+            Some((stamp, corner, inset)) => {
+                let stamp = match stamp.extract::<(&str, String, Colour, f32)>() {
+                    Ok((_, text, colour, size)) => Stamp::Text {
+                        text,
+                        colour: colour.into(),
+                        size,
+                    },
+                    Err(_) => Stamp::Image(image_from_state(stamp.extract::<(&str, _)>()?.1)?),
+                };
+
+                let Some(corner) = Corner::from_name(corner) else {
+                    return Err(invalid("\"watermark\" is malformed".to_string()));
+                };
+
+                Some(Watermark {
+                    stamp,
+                    corner,
+                    inset,
+                })
+            }
+        };
+
+        let markers = field::<Vec<(Point, Option<String>, ImageState)>>(dict, "markers")?
+            .into_iter()
+            .map(|(xy, tag, icon)| {
+                let icon = image_from_state(icon)?;
+                Ok(Marker { xy, tag, icon })
+            })
+            .collect::<PyResult<_>>()?;
+
+        let players = field::<Vec<(String, Option<Point>, EdgeVec, ImageState)>>(dict, "players")?
+            .into_iter()
+            .map(|(id, pos, history, icon)| {
+                let icon = image_from_state(icon)?;
+                Ok(Player {
+                    id,
+                    icon,
+                    pos,
+                    history,
+                    drawn_at: 0,
+                })
+            })
+            .collect::<PyResult<_>>()?;
+
+        let chasers =
+            field::<Vec<(String, Point, Option<String>, u32, ImageState)>>(dict, "chasers")?
+                .into_iter()
+                .map(|(id, pos, target, speed, icon)| {
+                    let icon = image_from_state(icon)?;
+                    Ok(Chaser {
+                        id,
+                        icon,
+                        pos,
+                        target,
+                        speed,
+                        drawn_at: 0,
+                    })
+                })
+                .collect::<PyResult<_>>()?;
+
+        let player_sprites = match field::<Option<[ImageState; 4]>>(dict, "player_sprites")? {
+            None => None,
+            Some([a, b, c, d]) => Some([
+                image_from_state(a)?,
+                image_from_state(b)?,
+                image_from_state(c)?,
+                image_from_state(d)?,
+            ]),
+        };
+
+        // checked the same way as when they're passed in, since they decide how much drawing the walls takes
+        let mut draw_style = DrawStyle {
+            wall_cap,
+            ..DrawStyle::default()
+        };
+        override_style(
+            &mut draw_style,
+            None,
+            field(dict, "wall_dash")?,
+            Some(field(dict, "supersample")?),
+        )?;
+
+        Ok(Self {
+            bg_image: field::<Option<_>>(dict, "bg_image")?
+                .map(image_from_state)
+                .transpose()?,
+            wall_jitter: field(dict, "wall_jitter")?,
+            wall_texture: field::<Option<_>>(dict, "wall_texture")?
+                .map(image_from_state)
+                .transpose()?,
+            player_icon: image_from_state(field(dict, "player_icon")?)?,
+            player_sprites,
+            end_icon: image_from_state(field(dict, "end_icon")?)?,
+            trail_fade: field(dict, "trail_fade")?,
+            draw_style,
+            frame: Frame {
+                border: field::<Option<Colour>>(dict, "border")?.map(Pxl::from),
+                openings: field(dict, "border_openings")?,
+                margin: field(dict, "margin")?,
+            },
+            watermark,
+            fog_colour: field::<Option<Colour>>(dict, "fog_colour")?.map(Pxl::from),
+            direction_labels,
+            markers,
+            players,
+            chasers,
+            collected_items: field(dict, "collected_items")?,
+            carve_order: field(dict, "carve_order")?,
+            headless: field(dict, "headless")?,
+        })
+    }
+
+    /// makes sure what was unpickled could've been made on a `width` by `height` maze,
+    /// the same way it's checked when it's passed in
+    pub fn check(&self, width: i32, height: i32) -> PyResult<()> {
+        // every icon's been fitted to a cell by the time it's pickled
+        let images = [&self.player_icon, &self.end_icon]
+            .into_iter()
+            .chain(&self.wall_texture)
+            .chain(self.player_sprites.iter().flatten())
+            .chain(self.markers.iter().map(|m| &m.icon))
+            .chain(self.players.iter().map(|p| &p.icon))
+            .chain(self.chasers.iter().map(|c| &c.icon));
+
+        if images
+            .into_iter()
+            .any(|img| img.width() == 0 || img.height() == 0)
+        {
+            return Err(invalid(
+                "icons and wall textures can't be empty".to_string(),
+            ));
+        }
+
+        let outside = |xy: &Point| out_of_bounds(*xy, width, height);
+        let cells = self
+            .players
+            .iter()
+            .flat_map(|p| &p.history)
+            .chain(self.carve_order.iter().flatten())
+            .flat_map(|(a, b)| [a, b])
+            .chain(self.markers.iter().map(|m| &m.xy))
+            .chain(self.players.iter().flat_map(|p| &p.pos))
+            .chain(self.chasers.iter().map(|c| &c.pos))
+            .chain(&self.collected_items);
+
+        if let Some(xy) = cells.into_iter().find(|xy| outside(xy)) {
+            return Err(invalid(format!(
+                "{xy:?} is outside of the {width}x{height} maze"
+            )));
+        }
+
+        let mut ids = HashSet::new();
+        if let Some(id) = self
+            .players
+            .iter()
+            .map(|p| &p.id)
+            .find(|id| !ids.insert(*id))
+        {
+            return Err(invalid(format!(
+                "there's more than one player with the id {id:?}"
+            )));
+        }
+
+        let mut ids = HashSet::new();
+        for chaser in &self.chasers {
+            let id = &chaser.id;
+            if !ids.insert(id) {
+                return Err(invalid(format!(
+                    "there's more than one chaser with the id {id:?}"
+                )));
+            }
+
+            if chaser.speed == 0 {
+                return Err(invalid(format!("the chaser {id:?} has a speed of 0")));
+            }
+
+            if let Some(target) = chaser
+                .target
+                .as_ref()
+                .filter(|target| !self.players.iter().any(|p| &p.id == *target))
+            {
+                return Err(invalid(format!(
+                    "the chaser {id:?} is after a player with the id {target:?}, but there isn't one"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn image_from_state((w, h, raw): ImageState) -> PyResult<Image<Pxl>> {
+    Image::from_raw(w, h, raw.to_vec()).ok_or_else(|| {
+        invalid(format!(
+            "a {w}x{h} image can't be made out of {} bytes",
+            raw.len()
+        ))
+    })
+}
+
+/// builds up the bytes for `SavedMaze::to_bytes`
+#[derive(Default)]
+struct Packer {
+    bytes: Vec<u8>,
+}
+
+impl Packer {
+    fn uint(&mut self, mut n: u64) {
+        loop {
+            let byte = (n & 0x7F) as u8;
+            n >>= 7;
+            if n == 0 {
+                self.bytes.push(byte);
+                return;
+            }
+
+            self.bytes.push(byte | 0x80);
+        }
+    }
+
+    /// zigzagged, so that small negative numbers stay small
+    fn int(&mut self, n: i32) {
+        self.uint(u64::from(((n << 1) ^ (n >> 31)) as u32));
+    }
+
+    fn cell(&mut self, (x, y): Point, width: i32) {
+        self.uint((y * width + x) as u64);
+    }
+
+    /// packs bools 8 to a byte, lowest bit first
+    fn bits(&mut self, bits: impl Iterator<Item = bool>) {
+        let mut current = 0u8;
+        let mut filled = 0;
+        for bit in bits {
+            current |= u8::from(bit) << filled;
+            filled += 1;
+            if filled == 8 {
+                self.bytes.push(current);
+                (current, filled) = (0, 0);
+            }
+        }
+
+        if filled > 0 {
+            self.bytes.push(current);
+        }
+    }
+}
+
+/// reads through the bytes for `SavedMaze::from_bytes`
+struct Unpacker<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Unpacker<'_> {
+    fn take(&mut self, n: usize) -> PyResult<&[u8]> {
+        let Some(taken) = self.bytes.get(self.pos..self.pos + n) else {
+            return Err(invalid(
+                "the bytes end in the middle of the maze".to_string(),
+            ));
+        };
+
+        self.pos += n;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> PyResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> PyResult<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    fn uint(&mut self) -> PyResult<u64> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(n);
+            }
+        }
+
+        Err(invalid("a number is too big".to_string()))
+    }
+
+    fn int(&mut self) -> PyResult<i32> {
+        let Ok(n) = u32::try_from(self.uint()?) else {
+            return Err(invalid("a number is too big".to_string()));
+        };
+
+        Ok((n >> 1) as i32 ^ -((n & 1) as i32))
+    }
+
+    /// a maze's width or height, which has to fit in an `i32` even once its cells are counted up
+    fn size(&mut self) -> PyResult<i32> {
+        match i32::try_from(self.uint()?) {
+            Ok(n) if (1..=MAX_SIZE).contains(&n) => Ok(n),
+            _ => Err(invalid("the maze's size is out of range".to_string())),
+        }
+    }
+
+    fn cell(&mut self, width: i32) -> PyResult<Point> {
+        let Ok(i) = i32::try_from(self.uint()?) else {
+            return Err(invalid("a cell is out of range".to_string()));
+        };
+
+        Ok((i % width, i / width))
+    }
+
+    fn bits(&mut self, count: usize) -> PyResult<Vec<bool>> {
+        let bytes = self.take(count.div_ceil(8))?;
+        Ok((0..count)
+            .map(|i| bytes[i / 8] >> (i % 8) & 1 == 1)
+            .collect())
+    }
+}
+
+fn invalid(msg: String) -> PyErr {
+    PyValueError::new_err(format!("invalid saved maze: {msg}"))
+}
+
+/// pulls `key` out of `dict`, JSON arrays come back as lists so points are read in as `[x, y]` arrays
+fn field<'py, T: FromPyObject<'py>>(dict: &'py PyDict, key: &str) -> PyResult<T> {
+    let Some(value) = dict.get_item(key)? else {
+        return Err(invalid(format!("{key:?} is missing")));
+    };
+
+    value
+        .extract()
+        .map_err(|e| invalid(format!("{key:?} is malformed ({e})")))
+}
+
+fn cells(points: Vec<[i32; 2]>) -> Vec<Point> {
+    points.into_iter().map(|[x, y]| (x, y)).collect()
+}
+
+fn edges(pairs: Vec<[[i32; 2]; 2]>) -> EdgeVec {
+    pairs
+        .into_iter()
+        .map(|[[ax, ay], [bx, by]]| ((ax, ay), (bx, by)))
+        .collect()
+}
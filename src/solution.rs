@@ -0,0 +1,38 @@
+use crate::algorithms::describe_moves;
+use crate::types::{DirectionLabels, Point, SolutionData};
+
+use pyo3::prelude::*;
+
+/// a maze's solution, as handed over to Python
+#[pyclass(module = "maze", frozen)]
+pub struct Solution {
+    /// how many moves a "perfect run" would take
+    #[pyo3(get)]
+    move_count: i32,
+    /// human-readable directions (e.g "2 right", "3 left") for the perfect run
+    #[pyo3(get)]
+    directions: Vec<String>,
+    /// every cell along the solution path, from start to end
+    #[pyo3(get)]
+    path: Vec<Point>,
+}
+
+impl Solution {
+    pub fn new(data: &SolutionData, labels: &DirectionLabels) -> Self {
+        Self {
+            move_count: data.move_count,
+            directions: describe_moves(&data.moves, labels),
+            path: data.cells(),
+        }
+    }
+}
+
+#[pymethods]
+impl Solution {
+    fn __repr__(&self) -> String {
+        format!(
+            "Solution(move_count={}, directions={:?})",
+            self.move_count, self.directions
+        )
+    }
+}
@@ -0,0 +1,360 @@
+// pyo3 0.20's `#[new]` expands into impls that newer compilers flag as non-local
+#![allow(non_local_definitions)]
+
+use crate::colour::Colour;
+use crate::types::{DrawStyle, Pxl, WallCap};
+
+use image::Rgba;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// the names of the built-in themes, in the order they're listed to Python
+const PRESETS: [&str; 4] = ["dark", "light", "neon", "paper"];
+
+/// just the colours of a theme
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Palette {
+    pub bg: Pxl,
+    pub wall: Pxl,
+    pub solution: Pxl,
+    pub trail: Pxl,
+    pub fog: Pxl,
+}
+
+const fn rgb(r: u8, g: u8, b: u8) -> Pxl {
+    Rgba([r, g, b, 255])
+}
+
+/// colourblind-safe palettes (built from the Okabe-Ito, Paul Tol, and IBM Design sets),
+/// picked so that the solution and trail stand out from each other and the walls with any common kind of colourblindness
+const PALETTES: [(&str, Palette); 5] = [
+    (
+        "okabe-ito",
+        Palette {
+            bg: rgb(255, 255, 255),
+            wall: rgb(0, 0, 0),
+            solution: rgb(213, 94, 0),
+            trail: rgb(0, 114, 178),
+            fog: rgb(153, 153, 153),
+        },
+    ),
+    (
+        "okabe-ito-dark",
+        Palette {
+            bg: rgb(25, 25, 25),
+            wall: rgb(240, 240, 240),
+            solution: rgb(230, 159, 0),
+            trail: rgb(86, 180, 233),
+            fog: rgb(10, 10, 10),
+        },
+    ),
+    (
+        "tol-bright",
+        Palette {
+            bg: rgb(255, 255, 255),
+            wall: rgb(51, 51, 51),
+            solution: rgb(238, 102, 119),
+            trail: rgb(68, 119, 170),
+            fog: rgb(187, 187, 187),
+        },
+    ),
+    (
+        "tol-high-contrast",
+        Palette {
+            bg: rgb(255, 255, 255),
+            wall: rgb(0, 0, 0),
+            solution: rgb(187, 85, 102),
+            trail: rgb(0, 68, 136),
+            fog: rgb(221, 170, 51),
+        },
+    ),
+    (
+        "ibm",
+        Palette {
+            bg: rgb(255, 255, 255),
+            wall: rgb(0, 0, 0),
+            solution: rgb(220, 38, 127),
+            trail: rgb(100, 143, 255),
+            fog: rgb(255, 176, 0),
+        },
+    ),
+];
+
+/// one of the colourblind-safe palettes by name (case-insensitive)
+pub fn palette_from_name(name: &str) -> Option<Palette> {
+    let name = name.to_ascii_lowercase();
+    PALETTES
+        .iter()
+        .find(|(palette_name, _)| *palette_name == name)
+        .map(|(_, palette)| *palette)
+}
+
+/// same as `palette_from_name`, but with an error for Python if there's no palette with that name
+pub fn palette(name: &str) -> PyResult<Palette> {
+    palette_from_name(name).ok_or_else(|| {
+        let names = PALETTES.map(|(name, _)| name);
+        let msg = format!("expected one of {names:?} as a palette; got {name:?}");
+        PyValueError::new_err(msg)
+    })
+}
+
+/// a set of colours and drawing options to generate mazes with, so they don't all have to be passed in every time
+#[pyclass(module = "maze", frozen)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub bg_colour: Pxl,
+    pub wall_colour: Pxl,
+    pub solution_colour: Pxl,
+    pub trail_colour: Option<Pxl>,
+    /// the colour `Maze.enable_fog` uses if it isn't given one
+    pub fog_colour: Option<Pxl>,
+    pub style: DrawStyle,
+}
+
+impl Theme {
+    /// one of the built-in themes by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let theme = match name.to_ascii_lowercase().as_str() {
+            "dark" => Self {
+                bg_colour: rgb(30, 30, 36),
+                wall_colour: rgb(220, 220, 230),
+                solution_colour: rgb(255, 95, 95),
+                trail_colour: Some(rgb(95, 160, 255)),
+                fog_colour: Some(rgb(15, 15, 18)),
+                style: DrawStyle {
+                    wall_cap: WallCap::Round,
+                    ..DrawStyle::default()
+                },
+            },
+            "light" => Self {
+                bg_colour: rgb(255, 255, 255),
+                wall_colour: rgb(0, 0, 0),
+                solution_colour: rgb(220, 40, 40),
+                trail_colour: Some(rgb(40, 110, 220)),
+                fog_colour: Some(rgb(200, 200, 200)),
+                style: DrawStyle::default(),
+            },
+            "neon" => Self {
+                bg_colour: rgb(10, 5, 25),
+                wall_colour: rgb(0, 255, 200),
+                solution_colour: rgb(255, 0, 170),
+                trail_colour: Some(rgb(255, 230, 0)),
+                fog_colour: Some(rgb(5, 0, 15)),
+                style: DrawStyle {
+                    wall_cap: WallCap::Round,
+                    supersample: 2,
+                    ..DrawStyle::default()
+                },
+            },
+            "paper" => Self {
+                bg_colour: rgb(245, 237, 215),
+                wall_colour: rgb(70, 55, 40),
+                solution_colour: rgb(180, 60, 40),
+                trail_colour: Some(rgb(90, 120, 160)),
+                fog_colour: Some(rgb(220, 210, 185)),
+                style: DrawStyle {
+                    wall_cap: WallCap::Butt,
+                    supersample: 2,
+                    ..DrawStyle::default()
+                },
+            },
+            _ => return None,
+        };
+
+        Some(theme)
+    }
+
+    /// a theme with the palette's colours and the default style
+    pub fn from_palette(palette: Palette) -> Self {
+        Self {
+            bg_colour: palette.bg,
+            wall_colour: palette.wall,
+            solution_colour: palette.solution,
+            trail_colour: Some(palette.trail),
+            fog_colour: Some(palette.fog),
+            style: DrawStyle::default(),
+        }
+    }
+
+    /// swaps every colour for the palette's, keeping the style
+    pub fn recoloured(self, palette: Palette) -> Self {
+        Self {
+            style: self.style,
+            ..Self::from_palette(palette)
+        }
+    }
+}
+
+/// the longest a wall dash (or the gap after one) can be, which is about the length of a wall
+const MAX_DASH: u32 = 40;
+
+/// swaps in whichever of the style options were passed in from Python, making sure they're valid
+pub fn override_style(
+    style: &mut DrawStyle,
+    wall_cap: Option<&str>,
+    wall_dash: Option<(u32, u32)>,
+    supersample: Option<u32>,
+) -> PyResult<()> {
+    if let Some(supersample) = supersample {
+        if !(1..=4).contains(&supersample) {
+            let msg = format!("supersample must be between 1 and 4; got {supersample}");
+            return Err(PyValueError::new_err(msg));
+        }
+
+        style.supersample = supersample;
+    }
+
+    if let Some(name) = wall_cap {
+        let Some(cap) = WallCap::from_name(name) else {
+            let msg = format!(
+                "expected one of \"square\", \"butt\", or \"round\" as a wall cap; got {name:?}"
+            );
+            return Err(PyValueError::new_err(msg));
+        };
+
+        style.wall_cap = cap;
+    }
+
+    if let Some(dash) = wall_dash {
+        if !(1..=MAX_DASH).contains(&dash.0) || !(1..=MAX_DASH).contains(&dash.1) {
+            let msg = format!(
+                "wall dashes and the gaps between them must be between 1 and {MAX_DASH} pixels long; got {dash:?}"
+            );
+            return Err(PyValueError::new_err(msg));
+        }
+
+        style.wall_dash = Some(dash);
+    }
+
+    Ok(())
+}
+
+#[pymethods]
+impl Theme {
+    #[new]
+    #[pyo3(signature = (*, bg_colour, wall_colour, solution_colour, trail_colour = None, fog_colour = None, wall_cap = "square", wall_dash = None, supersample = 1))]
+    #[allow(clippy::too_many_arguments)] // they're all keyword-only in Python
+    fn new(
+        bg_colour: Colour,
+        wall_colour: Colour,
+        solution_colour: Colour,
+        trail_colour: Option<Colour>,
+        fog_colour: Option<Colour>,
+        wall_cap: &str,
+        wall_dash: Option<(u32, u32)>,
+        supersample: u32,
+    ) -> PyResult<Self> {
+        let mut style = DrawStyle::default();
+        override_style(&mut style, Some(wall_cap), wall_dash, Some(supersample))?;
+
+        Ok(Self {
+            bg_colour: bg_colour.into(),
+            wall_colour: wall_colour.into(),
+            solution_colour: solution_colour.into(),
+            trail_colour: trail_colour.map(Pxl::from),
+            fog_colour: fog_colour.map(Pxl::from),
+            style,
+        })
+    }
+
+    /// one of the built-in themes, `"dark"`, `"light"`, `"neon"`, or `"paper"`
+    #[staticmethod]
+    pub fn preset(name: &str) -> PyResult<Self> {
+        Self::from_name(name).ok_or_else(|| {
+            let msg = format!("expected one of {PRESETS:?} as a theme; got {name:?}");
+            PyValueError::new_err(msg)
+        })
+    }
+
+    /// the names that `Theme.preset` takes
+    #[staticmethod]
+    fn presets() -> [&'static str; 4] {
+        PRESETS
+    }
+
+    /// a theme with one of the colourblind-safe palettes and the default style
+    #[staticmethod]
+    fn palette(name: &str) -> PyResult<Self> {
+        Ok(Self::from_palette(palette(name)?))
+    }
+
+    /// the names that `Theme.palette` (and `generate_maze(palette=...)`) take
+    #[staticmethod]
+    fn palettes() -> Vec<&'static str> {
+        PALETTES.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// a copy of this theme with its colours swapped for one of the colourblind-safe palettes
+    fn with_palette(&self, name: &str) -> PyResult<Self> {
+        Ok(self.clone().recoloured(palette(name)?))
+    }
+
+    #[getter]
+    fn bg_colour(&self) -> [u8; 4] {
+        self.bg_colour.0
+    }
+
+    #[getter]
+    fn wall_colour(&self) -> [u8; 4] {
+        self.wall_colour.0
+    }
+
+    #[getter]
+    fn solution_colour(&self) -> [u8; 4] {
+        self.solution_colour.0
+    }
+
+    #[getter]
+    fn trail_colour(&self) -> Option<[u8; 4]> {
+        self.trail_colour.map(|c| c.0)
+    }
+
+    #[getter]
+    fn fog_colour(&self) -> Option<[u8; 4]> {
+        self.fog_colour.map(|c| c.0)
+    }
+
+    #[getter]
+    fn wall_cap(&self) -> &'static str {
+        match self.style.wall_cap {
+            WallCap::Square => "square",
+            WallCap::Butt => "butt",
+            WallCap::Round => "round",
+        }
+    }
+
+    #[getter]
+    fn wall_dash(&self) -> Option<(u32, u32)> {
+        self.style.wall_dash
+    }
+
+    #[getter]
+    fn supersample(&self) -> u32 {
+        self.style.supersample
+    }
+
+    fn __repr__(&self) -> String {
+        if let Some(name) = PRESETS
+            .iter()
+            .find(|name| Self::from_name(name).as_ref() == Some(self))
+        {
+            return format!("Theme.preset({name:?})");
+        }
+
+        if let Some((name, _)) = PALETTES
+            .iter()
+            .find(|(_, palette)| Self::from_palette(*palette) == *self)
+        {
+            return format!("Theme.palette({name:?})");
+        }
+
+        let tuple = |Rgba([r, g, b, a]): Pxl| (r, g, b, a);
+        format!(
+            "Theme(bg_colour={:?}, wall_colour={:?}, solution_colour={:?})",
+            tuple(self.bg_colour),
+            tuple(self.wall_colour),
+            tuple(self.solution_colour),
+        )
+    }
+}
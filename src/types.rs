@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 use image::Rgba;
+use imageproc::definitions::Image;
 
 /// XY coordinate
 pub type Point = (i32, i32);
@@ -13,3 +14,409 @@ pub type EdgeVec = Vec<(Point, Point)>;
 
 /// just so that i don't need to manually change this every time
 pub type Pxl = Rgba<u8>;
+
+/// `(direction, amount, is_max)` a `Move` as it's handed over to Python
+pub type MoveTuple = ((i32, i32), i32, bool);
+
+/// `(position, visited, collided_at)` the result of playing back moves, as it's handed over to Python
+pub type SimulationTuple = (Point, Vec<Point>, Option<usize>);
+
+/// something drawn on a cell with `Maze.draw_marker_at`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub xy: Point,
+    pub tag: Option<String>,
+    pub icon: Image<Pxl>,
+}
+
+/// a player added with `Maze.add_player`, on top of the one every maze has
+#[derive(Clone, Debug, PartialEq)]
+pub struct Player {
+    pub id: String,
+    pub icon: Image<Pxl>,
+    pub pos: Option<Point>,
+    /// `(from, to)` of every move it's made
+    pub history: EdgeVec,
+    /// when it was last drawn, whichever player was drawn last goes on top when several share a cell
+    pub drawn_at: u64,
+}
+
+/// something added with `Maze.add_chaser` that walks towards a player every time `Maze.move_chasers` is called
+#[derive(Clone, Debug, PartialEq)]
+pub struct Chaser {
+    pub id: String,
+    pub icon: Image<Pxl>,
+    pub pos: Point,
+    /// the id of the player it's after, or `None` for the player the maze was generated with
+    pub target: Option<String>,
+    /// how many cells it walks every time it moves
+    pub speed: u32,
+    /// when it was last drawn, the same as `Player::drawn_at`
+    pub drawn_at: u64,
+}
+
+/// how the ends of walls (and of each dash, for dashed walls) get drawn
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WallCap {
+    /// walls run over the corners they meet at, making solid square corners
+    #[default]
+    Square,
+    /// walls stop halfway into the corners, leaving a notch wherever two of them meet
+    Butt,
+    /// like `Butt` but with rounded ends, which also rounds off every corner
+    Round,
+}
+
+impl WallCap {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "square" => Some(Self::Square),
+            "butt" => Some(Self::Butt),
+            "round" => Some(Self::Round),
+            _ => None,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Square => "square",
+            Self::Butt => "butt",
+            Self::Round => "round",
+        }
+    }
+}
+
+/// how the shapes making up the maze get drawn
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DrawStyle {
+    pub wall_cap: WallCap,
+    /// `(on, off)` lengths in pixels, for dashed walls
+    pub wall_dash: Option<(u32, u32)>,
+    /// how many times bigger walls and shapes like marker dots are drawn before being scaled down,
+    /// 1 to not smooth them out
+    pub supersample: u32,
+}
+
+impl Default for DrawStyle {
+    fn default() -> Self {
+        Self {
+            wall_cap: WallCap::default(),
+            wall_dash: None,
+            supersample: 1,
+        }
+    }
+}
+
+/// what gets drawn underneath the walls and icons
+#[derive(Clone, Debug, PartialEq)]
+pub struct Background {
+    pub colour: Pxl,
+    /// drawn over `colour` (which shows through wherever it's transparent), already fit to the size of the maze image
+    pub image: Option<Image<Pxl>>,
+}
+
+/// how a background image gets fit to the size of the maze image
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BackgroundFit {
+    Stretch,
+    /// repeated from the top left at its original size
+    Tile,
+}
+
+impl BackgroundFit {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "stretch" => Some(Self::Stretch),
+            "tile" => Some(Self::Tile),
+            _ => None,
+        }
+    }
+}
+
+/// which way the maze is looked at when it's exported
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Projection {
+    /// straight down, the same as the maze's own image
+    TopDown,
+    /// from an angle, with the walls extruded into blocks
+    Isometric,
+}
+
+impl Projection {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top-down" => Some(Self::TopDown),
+            "isometric" => Some(Self::Isometric),
+            _ => None,
+        }
+    }
+}
+
+/// what the walls get filled in with
+#[derive(Clone, Debug, PartialEq)]
+pub struct WallFill {
+    pub colour: Pxl,
+    /// how far each wall's brightness can be randomly nudged up or down, 0 to keep every wall the same colour
+    pub jitter: u8,
+    /// decides how far each wall gets nudged, so that re-rendering the maze doesn't shuffle the colours around
+    pub seed: u64,
+    /// tiled along the walls in place of `colour`
+    pub texture: Option<Image<Pxl>>,
+}
+
+/// how the solution path gets coloured in
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PathPaint {
+    Solid(Pxl),
+    /// fades from the first colour at the start to the second one at the goal
+    Gradient(Pxl, Pxl),
+}
+
+/// what gets put around the maze when it's exported
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Frame {
+    /// the colour of the outer wall drawn around the maze, if there is one
+    pub border: Option<Pxl>,
+    /// whether to leave gaps in the border next to the start and the goal
+    pub openings: bool,
+    /// how many pixels of background to pad the image out with on each side
+    pub margin: u32,
+}
+
+/// what the ground of a cell is like, which changes how moving onto it works
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Terrain {
+    /// a single step moves a single cell
+    #[default]
+    Normal,
+    /// stepping onto it sends the player sliding until something stops them, like a max move
+    Ice,
+    /// stepping onto it costs 2 moves instead of 1
+    Mud,
+}
+
+impl Terrain {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "ice" => Some(Self::Ice),
+            "mud" => Some(Self::Mud),
+            _ => None,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Ice => "ice",
+            Self::Mud => "mud",
+        }
+    }
+}
+
+/// one of the corners of an image
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top-left" => Some(Self::TopLeft),
+            "top-right" => Some(Self::TopRight),
+            "bottom-left" => Some(Self::BottomLeft),
+            "bottom-right" => Some(Self::BottomRight),
+            _ => None,
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::TopLeft => "top-left",
+            Self::TopRight => "top-right",
+            Self::BottomLeft => "bottom-left",
+            Self::BottomRight => "bottom-right",
+        }
+    }
+}
+
+/// what a watermark is made up of
+#[derive(Clone, Debug, PartialEq)]
+pub enum Stamp {
+    Text {
+        text: String,
+        colour: Pxl,
+        size: f32,
+    },
+    Image(Image<Pxl>),
+}
+
+/// a caption or small image stamped into a corner of exported images
+#[derive(Clone, Debug, PartialEq)]
+pub struct Watermark {
+    pub stamp: Stamp,
+    pub corner: Corner,
+    /// how far in from the edges of the image it goes, in pixels
+    pub inset: u32,
+}
+
+/// fog covering every cell the player hasn't been near yet
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fog {
+    pub colour: Pxl,
+    /// how many cells around the player get uncovered
+    pub radius: i32,
+    pub revealed: HashSet<Point>,
+}
+
+/// two linked cells, stepping onto either one puts the player on the other
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Portal {
+    pub ends: (Point, Point),
+    /// what the rings marking both ends are drawn in
+    pub colour: Pxl,
+}
+
+/// a cell that toggles a set of walls whenever the player steps onto it, opening them if they're closed and the other way around
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Switch {
+    pub xy: Point,
+    /// `(a, b)` with `a` left of or above `b`
+    pub walls: EdgeVec,
+    /// what the plate marking the switch is drawn in
+    pub colour: Pxl,
+}
+
+/// a single move in a "perfect run"
+///
+/// `amount` is how many cells the move covers, max moves always count as 1
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub direction: (i32, i32),
+    pub amount: i32,
+    pub is_max: bool,
+}
+
+impl Move {
+    pub const fn new(direction: (i32, i32), amount: i32, is_max: bool) -> Self {
+        Self {
+            direction,
+            amount,
+            is_max,
+        }
+    }
+
+    /// how many moves this counts as towards a run's total
+    pub const fn cost(&self) -> i32 {
+        if self.is_max {
+            1
+        } else {
+            self.amount
+        }
+    }
+
+    pub const fn as_tuple(&self) -> MoveTuple {
+        (self.direction, self.amount, self.is_max)
+    }
+}
+
+/// format templates for the human-readable directions of a perfect run
+///
+/// any `{amount}` in a template gets replaced with the amount of cells the move covers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirectionLabels {
+    pub up: String,
+    pub down: String,
+    pub left: String,
+    pub right: String,
+    pub max_up: String,
+    pub max_down: String,
+    pub max_left: String,
+    pub max_right: String,
+}
+
+impl DirectionLabels {
+    /// the keys accepted by `DirectionLabels::set`, in the same order as the fields
+    pub const KEYS: [&'static str; 8] = [
+        "up",
+        "down",
+        "left",
+        "right",
+        "max_up",
+        "max_down",
+        "max_left",
+        "max_right",
+    ];
+
+    /// plain-ASCII labels (e.g "2 right", "MAX up") for places that mangle the default arrows
+    pub fn ascii() -> Self {
+        Self {
+            up: "{amount} up".to_string(),
+            down: "{amount} down".to_string(),
+            left: "{amount} left".to_string(),
+            right: "{amount} right".to_string(),
+            max_up: "MAX up".to_string(),
+            max_down: "MAX down".to_string(),
+            max_left: "MAX left".to_string(),
+            max_right: "MAX right".to_string(),
+        }
+    }
+
+    /// overrides the template under a given key, returns `false` if the key doesn't exist
+    pub fn set(&mut self, key: &str, template: String) -> bool {
+        let field = match key {
+            "up" => &mut self.up,
+            "down" => &mut self.down,
+            "left" => &mut self.left,
+            "right" => &mut self.right,
+            "max_up" => &mut self.max_up,
+            "max_down" => &mut self.max_down,
+            "max_left" => &mut self.max_left,
+            "max_right" => &mut self.max_right,
+            _ => return false,
+        };
+
+        *field = template;
+        true
+    }
+}
+
+impl Default for DirectionLabels {
+    fn default() -> Self {
+        Self {
+            up: "↑ {amount} up (+{amount})".to_string(),
+            down: "↓ {amount} down (+{amount})".to_string(),
+            left: "⇽ {amount} left (+{amount})".to_string(),
+            right: "⇾ {amount} right (+{amount})".to_string(),
+            max_up: "⇈ Max up (+1)".to_string(),
+            max_down: "⇊ Max down (+1)".to_string(),
+            max_left: "⇇ Max left (+1)".to_string(),
+            max_right: "⇉ Max right (+1)".to_string(),
+        }
+    }
+}
+
+/// everything the solver figured out about a maze, cached on the maze once computed
+#[derive(Clone, Debug, Default)]
+pub struct SolutionData {
+    pub move_count: i32,
+    pub moves: Vec<Move>,
+    /// `(parent, child)` edges, going from the end of the maze back to the start
+    pub path: EdgeVec,
+    /// which revision of the maze's walls this was computed against
+    pub walls_version: u64,
+}
+
+impl SolutionData {
+    /// the cells along the solution path, in order from start to end
+    pub fn cells(&self) -> Vec<Point> {
+        let mut cells = Vec::with_capacity(self.path.len() + 1);
+        cells.push((0, 0));
+        cells.extend(self.path.iter().rev().map(|(_, child)| *child));
+        cells
+    }
+}
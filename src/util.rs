@@ -1,4 +1,6 @@
-use crate::types::{EdgeSet, Point};
+use crate::types::{EdgeSet, EdgeVec, Point};
+
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 /// gets the neighbours for this node one to the right and one down
 #[rustfmt::skip]
@@ -31,3 +33,379 @@ pub const fn out_of_bounds(node: Point, width: i32, height: i32) -> bool {
 pub fn wall_between(walls: &EdgeSet, a: Point, b: Point) -> bool {
     walls.contains(&(a, b)) || walls.contains(&(b, a))
 }
+
+/// the edge between two cells with the one that comes first (left of or above the other) first,
+/// since walls can be stored either way around
+pub fn ordered_edge((a, b): (Point, Point)) -> (Point, Point) {
+    if a <= b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// every cell that can be seen from `from` looking straight up, down, left, and right,
+/// until a wall or the edge of the maze blocks the view
+///
+/// `from` itself comes first, then each direction in turn, nearest cells first
+pub fn line_of_sight(walls: &EdgeSet, from: Point, width: i32, height: i32) -> Vec<Point> {
+    let mut visible = vec![from];
+    for direction in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+        let end = slide(walls, from, direction, width, height);
+        visible.extend(cells_between(from, end).into_iter().skip(1));
+    }
+
+    visible
+}
+
+/// every cell on the straight line from `from` to `to`, including both ends
+///
+/// the two cells have to share a row or a column
+pub fn cells_between(from: Point, to: Point) -> Vec<Point> {
+    let step = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let mut cells = vec![from];
+    let mut current = from;
+    while current != to {
+        current = (current.0 + step.0, current.1 + step.1);
+        cells.push(current);
+    }
+
+    cells
+}
+
+/// every cell within `radius` cells of `centre` (as the crow flies), clipped to the maze
+pub fn cells_within(centre: Point, radius: i32, width: i32, height: i32) -> Vec<Point> {
+    let mut cells = vec![];
+    for y in centre.1 - radius..=centre.1 + radius {
+        for x in centre.0 - radius..=centre.0 + radius {
+            let (dx, dy) = (x - centre.0, y - centre.1);
+            if dx * dx + dy * dy <= radius * radius && !out_of_bounds((x, y), width, height) {
+                cells.push((x, y));
+            }
+        }
+    }
+
+    cells
+}
+
+/// the neighbours of `node` that aren't blocked off by a wall, right, down, left, then up
+pub fn open_neighbours(walls: &EdgeSet, node: Point, width: i32, height: i32) -> Vec<Point> {
+    let mut adjacent = all_neighbours(node, width, height);
+    adjacent.retain(|n| !wall_between(walls, node, *n));
+    adjacent
+}
+
+/// whether a single step from `from` by `(dx, dy)` stays inside the maze without going through a wall
+pub fn can_step(
+    walls: &EdgeSet,
+    from: Point,
+    (dx, dy): (i32, i32),
+    width: i32,
+    height: i32,
+) -> bool {
+    let to = (from.0 + dx, from.1 + dy);
+    !out_of_bounds(from, width, height)
+        && !out_of_bounds(to, width, height)
+        && !wall_between(walls, from, to)
+}
+
+/// every pair of neighbouring cells that aren't blocked off by a wall, once each,
+/// with the top/left cell first and going row by row
+pub fn passages(walls: &EdgeSet, width: i32, height: i32) -> EdgeVec {
+    let mut passages = vec![];
+    for y in 0..height {
+        for x in 0..width {
+            for n in partial_neighbours((x, y), width, height) {
+                if !wall_between(walls, (x, y), n) {
+                    passages.push(((x, y), n));
+                }
+            }
+        }
+    }
+
+    passages
+}
+
+/// the maze as a graph in compressed sparse row form, `(indptr, indices)`, with `(x, y)` as node `y * width + x`
+///
+/// node `i`'s open neighbours are `indices[indptr[i]..indptr[i + 1]]`, in increasing order
+pub fn csr_adjacency(walls: &EdgeSet, width: i32, height: i32) -> (Vec<i32>, Vec<i32>) {
+    let mut indptr = Vec::with_capacity((width * height + 1) as usize);
+    let mut indices = vec![];
+    indptr.push(0);
+    for y in 0..height {
+        for x in 0..width {
+            let start = indices.len();
+            let open = open_neighbours(walls, (x, y), width, height);
+            indices.extend(open.iter().map(|(nx, ny)| ny * width + nx));
+            indices[start..].sort_unstable();
+            indptr.push(indices.len() as i32);
+        }
+    }
+
+    (indptr, indices)
+}
+
+/// every cell that can be walked to from `from` without going through a wall, `from` included
+pub fn reachable_cells(walls: &EdgeSet, from: Point, width: i32, height: i32) -> HashSet<Point> {
+    let mut seen = HashSet::from([from]);
+    let mut stack = vec![from];
+    while let Some(node) = stack.pop() {
+        for n in open_neighbours(walls, node, width, height) {
+            if seen.insert(n) {
+                stack.push(n);
+            }
+        }
+    }
+
+    seen
+}
+
+/// how many single steps it takes to walk from `from` to every cell it can reach
+pub fn step_distances(
+    walls: &EdgeSet,
+    from: Point,
+    width: i32,
+    height: i32,
+) -> HashMap<Point, usize> {
+    let mut distances = HashMap::from([(from, 0)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        let next = distances[&node] + 1;
+        for n in open_neighbours(walls, node, width, height) {
+            if let Entry::Vacant(e) = distances.entry(n) {
+                e.insert(next);
+                queue.push_back(n);
+            }
+        }
+    }
+
+    distances
+}
+
+/// the cells along one of the shortest walks from `from` to `to` (not including `from`),
+/// or `None` if walls cut them off from each other
+pub fn shortest_path(
+    walls: &EdgeSet,
+    from: Point,
+    to: Point,
+    width: i32,
+    height: i32,
+) -> Option<Vec<Point>> {
+    let mut came_from = HashMap::from([(from, from)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(node) = queue.pop_front() {
+        if node == to {
+            break;
+        }
+
+        for n in open_neighbours(walls, node, width, height) {
+            if let Entry::Vacant(e) = came_from.entry(n) {
+                e.insert(node);
+                queue.push_back(n);
+            }
+        }
+    }
+
+    let mut path = vec![];
+    let mut current = to;
+    while current != from {
+        path.push(current);
+        current = *came_from.get(&current)?;
+    }
+    path.reverse();
+
+    Some(path)
+}
+
+/// the passages along the shortest way around the wall between `a` and `b`, which are the ones that can be
+/// walled up in its place without cutting anything off (in a maze without loops, anyway)
+pub fn passages_around(
+    walls: &EdgeSet,
+    (a, b): (Point, Point),
+    width: i32,
+    height: i32,
+) -> EdgeVec {
+    let Some(path) = shortest_path(walls, a, b, width, height) else {
+        return vec![];
+    };
+
+    std::iter::once(a)
+        .chain(path.iter().copied())
+        .zip(path.iter().copied())
+        .collect()
+}
+
+/// splits the maze up into the groups of cells that can reach each other, returning how many there are
+/// and which one every cell is in
+///
+/// they're numbered in the order their top left-most cell comes in row by row, so the start is always in 0
+pub fn connected_regions(
+    walls: &EdgeSet,
+    width: i32,
+    height: i32,
+) -> (usize, HashMap<Point, usize>) {
+    let mut region_of = HashMap::with_capacity((width * height) as usize);
+    let mut count = 0;
+    for y in 0..height {
+        for x in 0..width {
+            if !region_of.contains_key(&(x, y)) {
+                for cell in reachable_cells(walls, (x, y), width, height) {
+                    region_of.insert(cell, count);
+                }
+
+                count += 1;
+            }
+        }
+    }
+
+    (count, region_of)
+}
+
+/// the cells that a wall between `a` and `b` cuts off from `start`, sorted, or nothing if there's still a way around it
+///
+/// the wall has to already be in `walls`
+pub fn cells_cut_off(
+    walls: &EdgeSet,
+    (a, b): (Point, Point),
+    start: Point,
+    width: i32,
+    height: i32,
+) -> Vec<Point> {
+    let from_a = reachable_cells(walls, a, width, height);
+    if from_a.contains(&b) {
+        return vec![];
+    }
+
+    let mut cut = if from_a.contains(&start) {
+        reachable_cells(walls, b, width, height)
+            .into_iter()
+            .collect()
+    } else {
+        from_a.into_iter().collect::<Vec<_>>()
+    };
+
+    cut.sort_unstable();
+    cut
+}
+
+/// whether two sets of walls block off the same spots, no matter which way around their edges are stored
+pub fn same_walls(a: &EdgeSet, b: &EdgeSet) -> bool {
+    a.len() == b.len() && a.iter().all(|(x, y)| wall_between(b, *x, *y))
+}
+
+/// a 64-bit FNV-1a hash of the maze's size and walls, which (unlike `DefaultHasher`) is the same
+/// on every platform and every version of Rust, so it can be stored and compared later
+pub fn structure_hash(walls: &EdgeSet, width: i32, height: i32) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let (horizontal, vertical) = wall_grids(walls, width, height);
+    let bits: Vec<bool> = horizontal.into_iter().chain(vertical).flatten().collect();
+    let packed = bits.chunks(8).map(|chunk| {
+        chunk
+            .iter()
+            .rev()
+            .fold(0u8, |byte, bit| byte << 1 | u8::from(*bit))
+    });
+
+    width
+        .to_le_bytes()
+        .into_iter()
+        .chain(height.to_le_bytes())
+        .chain(packed)
+        .fold(OFFSET, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+/// splits the walls into two grids, `horizontal[y][x]` being the wall under `(x, y)`
+/// and `vertical[y][x]` being the wall to the right of `(x, y)`
+///
+/// the outer border isn't included, so the grids are `(height - 1) x width` and `height x (width - 1)`
+pub fn wall_grids(walls: &EdgeSet, width: i32, height: i32) -> (Vec<Vec<bool>>, Vec<Vec<bool>>) {
+    let horizontal = (0..height - 1)
+        .map(|y| {
+            (0..width)
+                .map(|x| wall_between(walls, (x, y), (x, y + 1)))
+                .collect()
+        })
+        .collect();
+    let vertical = (0..height)
+        .map(|y| {
+            (0..width - 1)
+                .map(|x| wall_between(walls, (x, y), (x + 1, y)))
+                .collect()
+        })
+        .collect();
+
+    (horizontal, vertical)
+}
+
+/// joins lines into newline-separated chunks of at most `max_len` characters each
+///
+/// lines are never split up unless a single line is longer than `max_len` by itself
+pub fn chunk_lines(lines: &[String], max_len: usize) -> Vec<String> {
+    let mut chunks = vec![];
+    let mut current = String::new();
+    let mut current_len = 0;
+
+    for line in lines {
+        let line_len = line.chars().count();
+        // +1 for the newline joining it onto the current chunk
+        if current_len > 0 && current_len + 1 + line_len > max_len {
+            chunks.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        if line_len > max_len {
+            let chars: Vec<char> = line.chars().collect();
+            let mut pieces = chars.chunks(max_len).map(String::from_iter).peekable();
+            while let Some(piece) = pieces.next() {
+                if pieces.peek().is_none() {
+                    current_len = piece.chars().count();
+                    current = piece;
+                } else {
+                    chunks.push(piece);
+                }
+            }
+
+            continue;
+        }
+
+        if current_len > 0 {
+            current.push('\n');
+            current_len += 1;
+        }
+
+        current.push_str(line);
+        current_len += line_len;
+    }
+
+    if current_len > 0 {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// steps from a node in a direction until a wall or the edge of the maze is in the way,
+/// and returns where it stopped
+pub fn slide(
+    walls: &EdgeSet,
+    from: Point,
+    direction: (i32, i32),
+    width: i32,
+    height: i32,
+) -> Point {
+    let mut current = from;
+    loop {
+        // the next node one over in the direction to look
+        let n = (current.0 + direction.0, current.1 + direction.1);
+        if out_of_bounds(n, width, height) || wall_between(walls, current, n) {
+            return current;
+        }
+
+        current = n;
+    }
+}
@@ -0,0 +1,43 @@
+use crate::types::Point;
+
+use pyo3::prelude::*;
+
+/// what `Maze.validate()` found out about a maze, as handed over to Python
+#[pyclass(module = "maze", frozen)]
+pub struct Validation {
+    /// whether every cell can be reached from the start, and there's only ever one way to get there
+    #[pyo3(get)]
+    is_perfect: bool,
+    /// every cell that can't be reached from the start
+    #[pyo3(get)]
+    unreachable: Vec<Point>,
+    /// `(a, b)` passages that each close off a loop, walling all of them up would get rid of every loop
+    #[pyo3(get)]
+    loops: Vec<(Point, Point)>,
+}
+
+impl Validation {
+    pub fn new(unreachable: Vec<Point>, loops: Vec<(Point, Point)>) -> Self {
+        Self {
+            is_perfect: unreachable.is_empty() && loops.is_empty(),
+            unreachable,
+            loops,
+        }
+    }
+}
+
+#[pymethods]
+impl Validation {
+    fn __repr__(&self) -> String {
+        format!(
+            "Validation(is_perfect={}, unreachable={}, loops={})",
+            if self.is_perfect { "True" } else { "False" },
+            self.unreachable.len(),
+            self.loops.len()
+        )
+    }
+
+    fn __bool__(&self) -> bool {
+        self.is_perfect
+    }
+}